@@ -7,7 +7,6 @@ mod logging;
 mod options;
 mod verbose;
 
-use clap::Parser;
 use color_eyre::eyre;
 use std::process::ExitCode;
 
@@ -16,8 +15,7 @@ async fn main() -> ExitCode {
     let result: eyre::Result<()> = async {
         color_eyre::install()?;
 
-        let mut options = options::Options::parse();
-        options::fix(&mut options);
+        let options = options::parse_from(std::env::args_os());
         common::bumpversion(options).await
     }
     .await;
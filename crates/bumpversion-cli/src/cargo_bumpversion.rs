@@ -10,7 +10,6 @@ mod logging;
 mod options;
 mod verbose;
 
-use clap::Parser;
 use color_eyre::eyre;
 use std::ffi::{OsStr, OsString};
 use std::process::ExitCode;
@@ -54,8 +53,7 @@ async fn main() -> ExitCode {
     let result: eyre::Result<()> = async {
         color_eyre::install()?;
 
-        let mut options = options::Options::parse_from(normalize_args(std::env::args_os()));
-        options::fix(&mut options);
+        let options = options::parse_from(normalize_args(std::env::args_os()));
         common::bumpversion(options).await
     }
     .await;
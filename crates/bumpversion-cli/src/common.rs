@@ -6,12 +6,19 @@ use bumpversion::{
     BumpError,
     command::Error as CommandError,
     config, hooks,
-    vcs::{TagAndRevision, VersionControlSystem, git::GitRepository},
+    report::VersionReport,
+    vcs::{TagAndRevision, VersionControlSystem},
 };
 use color_eyre::eyre::{self, WrapErr};
 use std::process::ExitCode;
 
-fn render_pre_commit_failure(error: &hooks::Error) -> String {
+/// The version control backend, in-process if the `gitoxide` feature is enabled.
+#[cfg(not(feature = "gitoxide"))]
+type Repository = bumpversion::vcs::git::GitRepository;
+#[cfg(feature = "gitoxide")]
+type Repository = bumpversion::vcs::gitoxide::GitoxideRepository;
+
+fn render_pre_commit_failure(error: &hooks::Error, rolled_back: bool) -> String {
     let mut sections = match error {
         hooks::Error::Command(CommandError::Failed { command, output }) => {
             let exit_code = output
@@ -33,11 +40,16 @@ fn render_pre_commit_failure(error: &hooks::Error) -> String {
         _ => vec![format!("Pre-commit hook failed:\n  {error}")],
     };
 
-    sections.push("The version changes are still in your working tree.".to_string());
-    sections.push(
-        "Either revert them and start over, or fix the issue and run:\n  bumpversion finalize --allow-dirty"
-            .to_string(),
-    );
+    if rolled_back {
+        sections.push("The version changes were rolled back.".to_string());
+        sections.push("Fix the issue and run the bump again.".to_string());
+    } else {
+        sections.push("The version changes are still in your working tree.".to_string());
+        sections.push(
+            "Either revert them and start over, or fix the issue and run:\n  bumpversion finalize --allow-dirty"
+                .to_string(),
+        );
+    }
     sections.join("\n\n")
 }
 
@@ -46,10 +58,12 @@ pub(crate) fn report_result(result: eyre::Result<()>) -> ExitCode {
     match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(error) => {
-            if let Some(BumpError::PreCommitHook(source)) =
-                error.downcast_ref::<BumpError<GitRepository>>()
+            if let Some(BumpError::PreCommitHook {
+                source,
+                rolled_back,
+            }) = error.downcast_ref::<BumpError<Repository>>()
             {
-                eprintln!("{}", render_pre_commit_failure(source));
+                eprintln!("{}", render_pre_commit_failure(source, *rolled_back));
             } else {
                 eprintln!("{error:?}");
             }
@@ -62,10 +76,7 @@ pub(crate) fn report_result(result: eyre::Result<()>) -> ExitCode {
 ///
 /// # Errors
 /// Returns an error if the repo is dirty and not allowed by config.
-async fn check_is_dirty(
-    repo: &GitRepository,
-    config: &config::FinalizedConfig,
-) -> eyre::Result<()> {
+async fn check_is_dirty(repo: &Repository, config: &config::FinalizedConfig) -> eyre::Result<()> {
     let dirty_files = repo.dirty_files().await?;
     if !config.global.allow_dirty && !dirty_files.is_empty() {
         eyre::bail!(
@@ -81,19 +92,46 @@ async fn check_is_dirty(
     Ok(())
 }
 
+/// Print `value` to stdout as a single JSON document.
+fn print_json(value: &impl serde::Serialize) -> eyre::Result<()> {
+    let json = serde_json::to_string_pretty(value).wrap_err("failed to serialize output")?;
+    println!("{json}");
+    Ok(())
+}
+
+/// The bump requested by `--new-version`, a lifecycle subcommand, or a component to bump.
+fn requested_bump<'a>(
+    options: &'a options::Options,
+    component: Option<&'a str>,
+) -> eyre::Result<bumpversion::Bump<'a>> {
+    let bump = match (options.new_version.as_deref(), &options.command) {
+        (Some(new_version), _) => bumpversion::Bump::NewVersion(new_version),
+        (None, Some(options::SubCommand::Pre(pre_options))) => {
+            bumpversion::Bump::Pre(pre_options.component.as_deref())
+        }
+        (None, Some(options::SubCommand::Promote)) => bumpversion::Bump::Promote,
+        (None, Some(options::SubCommand::Release)) => bumpversion::Bump::Release,
+        (None, _) => bumpversion::Bump::Component(
+            component.ok_or_else(|| eyre::eyre!("missing version component to bump"))?,
+        ),
+    };
+    Ok(bump)
+}
+
 /// Entry point for the `bumpversion` CLI.
 ///
 /// Processes command-line `options`, loads the project config, and performs the bump.
 pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
     let start = std::time::Instant::now();
 
+    let json_output = options.output == options::OutputFormat::Json;
     let color_choice = options.color_choice.unwrap_or(termcolor::ColorChoice::Auto);
-    let use_color = crate::logging::setup(options.log_level, color_choice)?;
+    let use_color = crate::logging::setup(options.log_level, color_choice, json_output)?;
     colored::control::set_override(use_color);
 
     let cwd = std::env::current_dir().wrap_err("could not determine current working dir")?;
     let dir = options.dir.as_deref().unwrap_or(&cwd).canonicalize()?;
-    let repo = GitRepository::open(&dir)?;
+    let repo = Repository::open(&dir)?;
 
     let printer = bumpversion::diagnostics::Printer::stderr(color_choice.into());
 
@@ -105,8 +143,9 @@ pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
     if let Some(path) = config_file {
         eyre::ensure!(path.is_file(), "config file {path:?} does not exist");
     }
+    let package = options.package.as_deref();
     let (config_file_path, mut config) =
-        bumpversion::find_config(&dir, config_file, &cli_overrides, &printer)
+        bumpversion::find_config(&dir, config_file, package, &cli_overrides, &printer)
             .await?
             .ok_or_else(|| {
                 if let Some(path) = config_file {
@@ -116,6 +155,11 @@ pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
                 }
             })?;
 
+    // explained before the files are resolved, which moves them out of the config
+    if let Some(options::SubCommand::Explain(explain_options)) = &options.command {
+        return handle_explain(explain_options, &config, &printer, options.output);
+    }
+
     let components = config::version::version_component_configs(&config);
     let (bump, cli_files) = options::parse_positional_arguments(&mut options, &components)?;
 
@@ -144,7 +188,12 @@ pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
 
     let is_read_only_command = matches!(
         options.command,
-        Some(options::SubCommand::Show(_) | options::SubCommand::ShowBump(_))
+        Some(
+            options::SubCommand::Show(_)
+                | options::SubCommand::ShowBump(_)
+                | options::SubCommand::Check
+                | options::SubCommand::Describe(_)
+        )
     );
 
     if !is_read_only_command {
@@ -165,7 +214,8 @@ pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
         config.global.included_paths = Some(cli_files);
     }
 
-    let verbosity: bumpversion::logging::Verbosity = if options.verbosity.quiet > 0 {
+    // The verbose report goes to stdout, where it would corrupt the JSON document.
+    let verbosity: bumpversion::logging::Verbosity = if options.verbosity.quiet > 0 || json_output {
         bumpversion::logging::Verbosity::Off
     } else {
         options.verbosity.verbose.into()
@@ -182,55 +232,189 @@ pub async fn bumpversion(mut options: options::Options) -> eyre::Result<()> {
         config_file: Some(config_file_path),
     };
 
-    if let Some(command) = options.command
-        && handle_subcommand(command, &manager).await?
+    if let Some(command) = &options.command
+        && handle_subcommand(command, &manager, &printer, options.output).await?
     {
         tracing::info!(elapsed = ?start.elapsed(), "done");
         return Ok(());
     }
 
-    let bump = if let Some(new_version) = options.new_version.as_deref() {
-        bumpversion::Bump::NewVersion(new_version)
-    } else {
-        let bump = bump
-            .as_deref()
-            .ok_or_else(|| eyre::eyre!("missing version component to bump"))?;
-        bumpversion::Bump::Component(bump)
+    let bump = match options.command {
+        Some(options::SubCommand::Auto) if options.new_version.is_none() => {
+            manager.auto_bump().await?
+        }
+        _ => requested_bump(&options, bump.as_deref())?,
     };
 
-    manager.bump(bump).await?;
+    run_bump(&manager, bump, &printer, json_output).await?;
 
     tracing::info!(elapsed = ?start.elapsed(), "done");
     Ok(())
 }
 
-async fn handle_subcommand<L>(
-    command: options::SubCommand,
-    manager: &bumpversion::BumpVersion<GitRepository, L>,
+/// Perform the `bump`, printing the report as JSON if `json_output` is set.
+async fn run_bump<L, W>(
+    manager: &bumpversion::BumpVersion<Repository, L>,
+    bump: bumpversion::Bump<'_>,
+    printer: &bumpversion::diagnostics::Printer<W>,
+    json_output: bool,
+) -> eyre::Result<()>
+where
+    L: bumpversion::logging::Log,
+    W: codespan_reporting::term::WriteStyle,
+{
+    let report = match manager.bump(bump).await {
+        Ok(report) => report,
+        Err(err) => {
+            match err {
+                BumpError::ReplaceVersion(ref source) => {
+                    for diagnostic in source.diagnostics() {
+                        printer.emit(&diagnostic)?;
+                    }
+                }
+                BumpError::Preflight(ref problems) => {
+                    for problem in problems {
+                        printer.emit(&problem.to_diagnostic(printer))?;
+                    }
+                }
+                _ => {}
+            }
+            return Err(err.into());
+        }
+    };
+    for file in &report.files {
+        for diagnostic in file.diagnostics() {
+            printer.emit(&diagnostic)?;
+        }
+    }
+    if json_output {
+        print_json(&report)?;
+    }
+    Ok(())
+}
+
+async fn handle_subcommand<L, W>(
+    command: &options::SubCommand,
+    manager: &bumpversion::BumpVersion<Repository, L>,
+    printer: &bumpversion::diagnostics::Printer<W>,
+    output: options::OutputFormat,
 ) -> eyre::Result<bool>
 where
     L: bumpversion::logging::Log,
+    W: codespan_reporting::term::WriteStyle,
 {
     match command {
         options::SubCommand::Show(show_options) => {
-            handle_show(&show_options, manager)?;
+            handle_show(show_options, manager, output)?;
             Ok(true)
         }
         options::SubCommand::ShowBump(show_bump_options) => {
-            handle_show_bump(&show_bump_options, manager)?;
+            handle_show_bump(show_bump_options, manager, output)?;
             Ok(true)
         }
         options::SubCommand::Finalize => {
             manager.finalize().await?;
             Ok(true)
         }
+        options::SubCommand::Check => {
+            handle_check(manager, printer, output).await?;
+            Ok(true)
+        }
+        options::SubCommand::Describe(describe_options) => {
+            let report = manager.describe(describe_options.component.as_deref())?;
+            if output == options::OutputFormat::Json {
+                print_json(&report)?;
+            } else {
+                println!("{}", report.version);
+            }
+            Ok(true)
+        }
         _ => Ok(false),
     }
 }
 
+/// Print the effective settings with their origins, pointing at where the config file sets them.
+fn handle_explain<W>(
+    options: &options::ExplainOptions,
+    config: &config::FinalizedConfig,
+    printer: &bumpversion::diagnostics::Printer<W>,
+    output: options::OutputFormat,
+) -> eyre::Result<()>
+where
+    W: codespan_reporting::term::WriteStyle,
+{
+    let (settings, lookup): (_, fn(&str) -> Option<&'static str>) = match options.file.as_deref() {
+        None => (config.global.settings(), config::provenance::global_key),
+        Some(file) => {
+            let (_, file_config) = config
+                .files
+                .iter()
+                .find(|(input_file, _)| match input_file {
+                    config::InputFile::Path(path) => path == std::path::Path::new(file),
+                    config::InputFile::GlobPattern { pattern, .. } => pattern == file,
+                })
+                .ok_or_else(|| eyre::eyre!("no configured file {file:?}"))?;
+            (file_config.settings(), config::provenance::file_key)
+        }
+    };
+    let settings: Vec<_> = match options.key.as_deref() {
+        None => settings,
+        Some(name) => {
+            let key = lookup(name).ok_or_else(|| eyre::eyre!("unknown setting {name:?}"))?;
+            settings
+                .into_iter()
+                .filter(|setting| setting.key == key)
+                .collect()
+        }
+    };
+
+    if output == options::OutputFormat::Json {
+        return print_json(&settings);
+    }
+    for setting in &settings {
+        println!("{} = {}  # {}", setting.key, setting.value, setting.origin);
+    }
+    // a snippet for every setting would bury the values
+    if options.key.is_some() {
+        for diagnostic in settings.iter().filter_map(config::Setting::to_diagnostic) {
+            printer.emit(&diagnostic)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check the repository against the current version, failing if anything disagrees.
+async fn handle_check<L, W>(
+    manager: &bumpversion::BumpVersion<Repository, L>,
+    printer: &bumpversion::diagnostics::Printer<W>,
+    output: options::OutputFormat,
+) -> eyre::Result<()>
+where
+    L: bumpversion::logging::Log,
+    W: codespan_reporting::term::WriteStyle,
+{
+    let report = manager.check().await?;
+    if output == options::OutputFormat::Json {
+        print_json(&report)?;
+    } else {
+        for problem in &report.problems {
+            printer.emit(&problem.to_diagnostic(printer))?;
+        }
+    }
+    let problems = report.problems.len();
+    eyre::ensure!(
+        report.is_ok(),
+        "{problems} {} with version {}",
+        if problems == 1 { "problem" } else { "problems" },
+        report.current_version
+    );
+    Ok(())
+}
+
 fn handle_show<VCS, L>(
     options: &options::ShowOptions,
     manager: &bumpversion::BumpVersion<VCS, L>,
+    output: options::OutputFormat,
 ) -> eyre::Result<()>
 where
     VCS: VersionControlSystem,
@@ -278,6 +462,26 @@ where
         .chain(extra_ctx.iter().map(|(k, v)| (k.as_str(), v.as_str())))
         .collect();
 
+    if output == options::OutputFormat::Json {
+        // Without variables, everything that could be shown is.
+        let values: std::collections::BTreeMap<&str, &str> = if options.variables.is_empty() {
+            ctx.into_iter().collect()
+        } else {
+            options
+                .variables
+                .iter()
+                .filter_map(|variable| {
+                    let value = ctx.get(variable.as_str());
+                    if value.is_none() {
+                        tracing::warn!("variable {variable} not found in context");
+                    }
+                    Some((variable.as_str(), *value?))
+                })
+                .collect()
+        };
+        return print_json(&values);
+    }
+
     for variable in &options.variables {
         if let Some(value) = ctx.get(variable.as_str()) {
             if options.variables.len() > 1 {
@@ -299,6 +503,7 @@ where
 fn handle_show_bump<VCS, L>(
     options: &options::ShowBumpOptions,
     manager: &bumpversion::BumpVersion<VCS, L>,
+    output: options::OutputFormat,
 ) -> eyre::Result<()>
 where
     VCS: VersionControlSystem,
@@ -344,6 +549,18 @@ where
     let new_version_serialized =
         new_version.serialize(serialize_version_patterns, &ctx_without_new_version)?;
 
+    if output == options::OutputFormat::Json {
+        #[derive(serde::Serialize)]
+        struct ShowBump {
+            current_version: VersionReport,
+            new_version: VersionReport,
+        }
+        return print_json(&ShowBump {
+            current_version: VersionReport::new(current_version_serialized, &current_version),
+            new_version: VersionReport::new(&new_version_serialized, &new_version),
+        });
+    }
+
     // Mimic bump-my-version output format
     // It usually prints a list of changes or the new version details.
     // Let's print the basic info for now.
@@ -3,16 +3,20 @@
 //! Configures `tracing` subscriber with compact formatting and color choice.
 use color_eyre::eyre;
 use termcolor::ColorChoice;
-use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, layer::SubscriberExt};
 
 /// Setup logging
 ///
+/// Logs go to stdout, or to stderr with `to_stderr` so they do not mix with output meant
+/// for another program.
+///
 /// # Errors
 /// - If the logging directive cannot be parsed.
 /// - If the global tracing subscriber cannot be installed.
 pub fn setup(
     log_level: Option<tracing::metadata::Level>,
     color_choice: ColorChoice,
+    to_stderr: bool,
 ) -> eyre::Result<bool> {
     let default_log_level = log_level.unwrap_or(tracing::metadata::Level::WARN);
     let default_log_directive = format!(
@@ -54,7 +58,11 @@ pub fn setup(
         ColorChoice::Never => false,
         ColorChoice::Auto => {
             use std::io::IsTerminal;
-            std::io::stdout().is_terminal()
+            if to_stderr {
+                std::io::stderr().is_terminal()
+            } else {
+                std::io::stdout().is_terminal()
+            }
         }
     };
 
@@ -62,7 +70,11 @@ pub fn setup(
         .compact()
         .without_time()
         .with_ansi(use_color)
-        .with_writer(std::io::stdout);
+        .with_writer(if to_stderr {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        });
 
     let subscriber = tracing_subscriber::registry()
         .with(fmt_layer_pretty_compact)
@@ -3,6 +3,8 @@
 //! Defines flags, positional arguments, and configuration overrides via environment.
 use bumpversion::config;
 use color_eyre::eyre;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 /// Trait to invert an `Option<bool>`, used for negatable flags (e.g., --flag/--no-flag).
@@ -33,8 +35,48 @@ pub enum SubCommand {
     Show(ShowOptions),
     #[clap(name = "show-bump")]
     ShowBump(ShowBumpOptions),
+    /// Verify that every configured file and the latest tag agree with the current version.
+    #[clap(name = "check")]
+    Check,
+    /// Print the development version of the working tree, derived from the latest tag.
+    #[clap(name = "describe")]
+    Describe(DescribeOptions),
+    /// Print the effective value of each setting and where it came from.
+    #[clap(name = "explain")]
+    Explain(ExplainOptions),
     #[clap(name = "bump")]
     Bump(BumpOptions),
+    /// Enter a pre-release by bumping a component, or bump the pre-release number.
+    #[clap(name = "pre")]
+    Pre(PreOptions),
+    /// Promote a pre-release to its next label, e.g. from `rc` to `final`.
+    #[clap(name = "promote")]
+    Promote,
+    /// Release a pre-release, e.g. from `1.3.0-rc.1` to `1.3.0`.
+    #[clap(name = "release")]
+    Release,
+    /// Bump the component the Conventional Commits since the latest tag call for.
+    #[clap(name = "auto")]
+    Auto,
+}
+
+impl SubCommand {
+    /// Bump the component of the same name instead of running a pre-release lifecycle command,
+    /// so that `bumpversion release` keeps bumping a component called `release`.
+    fn or_component_bump(self, components: &config::VersionComponentConfigs) -> Self {
+        let (component, args) = match self {
+            Self::Pre(opts) if components.contains_key("pre") => {
+                ("pre", opts.component.into_iter().chain(opts.args).collect())
+            }
+            Self::Promote if components.contains_key("promote") => ("promote", vec![]),
+            Self::Release if components.contains_key("release") => ("release", vec![]),
+            command => return command,
+        };
+        Self::Bump(BumpOptions {
+            component: component.to_string(),
+            args,
+        })
+    }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -52,6 +94,24 @@ pub struct ShowBumpOptions {
     pub args: Vec<String>,
 }
 
+#[derive(clap::Args, Debug, Clone)]
+pub struct DescribeOptions {
+    #[arg(help = "The version component whose next version is available as `new_version`")]
+    pub component: Option<String>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ExplainOptions {
+    #[arg(help = "The setting to explain, or all of them if omitted")]
+    pub key: Option<String>,
+
+    #[arg(
+        long = "file",
+        help = "explain the settings of the configured file with this path or glob pattern"
+    )]
+    pub file: Option<String>,
+}
+
 #[derive(clap::Args, Debug, Clone)]
 pub struct BumpOptions {
     #[arg(help = "The version component to bump")]
@@ -61,6 +121,25 @@ pub struct BumpOptions {
     pub args: Vec<String>,
 }
 
+#[derive(clap::Args, Debug, Clone)]
+pub struct PreOptions {
+    #[arg(help = "The version component to bump when entering a pre-release")]
+    pub component: Option<String>,
+
+    #[arg()]
+    pub args: Vec<String>,
+}
+
+/// Format of the output on stdout.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A single JSON document.
+    Json,
+}
+
 /// Logging flags to `#[command(flatten)]` into your CLI
 #[derive(clap::Args, Debug, Clone, Copy, Default)]
 pub struct Verbosity {
@@ -111,6 +190,15 @@ pub struct Options {
     )]
     pub config_file: Option<PathBuf>,
 
+    #[clap(
+        long = "package",
+        short = 'p',
+        help = "package of a monorepo to bump, as configured in [tool.bumpversion.packages.<name>]",
+        env = "BUMPVERSION_PACKAGE",
+        global = true
+    )]
+    pub package: Option<String>,
+
     #[arg(
         long = "color",
         env = "BUMPVERSION_COLOR",
@@ -122,6 +210,16 @@ pub struct Options {
     #[command(flatten)]
     pub verbosity: Verbosity,
 
+    #[arg(
+        long = "output",
+        env = "BUMPVERSION_OUTPUT",
+        value_enum,
+        default_value_t,
+        help = "format of the output; `json` prints one JSON document and sends logs to stderr",
+        global = true
+    )]
+    pub output: OutputFormat,
+
     #[arg(
         long = "log",
         env = "BUMPVERSION_LOG_LEVEL",
@@ -149,6 +247,15 @@ pub struct Options {
     )]
     pub no_allow_dirty: Option<bool>,
 
+    #[clap(
+        long = "allow-downgrade",
+        help = "don't abort if the new version is not newer than the current version",
+        env = "BUMPVERSION_ALLOW_DOWNGRADE",
+        action = clap::ArgAction::SetTrue,
+        global = true,
+    )]
+    pub allow_downgrade: Option<bool>,
+
     #[clap(
         long = "current-version",
         help = "version that needs to be updated",
@@ -173,6 +280,14 @@ pub struct Options {
     )]
     pub parse_version_pattern: Option<String>,
 
+    #[clap(
+        long = "scheme",
+        help = "built-in version scheme (semver, pep440 or calver:<format>)",
+        env = "BUMPVERSION_SCHEME",
+        global = true
+    )]
+    pub scheme: Option<String>,
+
     #[clap(
         long = "serialize",
         help = "how to format what is parsed back to a version",
@@ -270,6 +385,15 @@ pub struct Options {
     )]
     pub dry_run: Option<bool>,
 
+    #[clap(
+        long = "keep-on-failure",
+        help = "keep the rewritten files if a hook or the commit fails, instead of restoring them",
+        env = "BUMPVERSION_KEEP_ON_FAILURE",
+        action = clap::ArgAction::SetTrue,
+        global = true,
+    )]
+    pub keep_on_failure: Option<bool>,
+
     #[clap(
         long = "commit",
         help = "commit to version control",
@@ -340,6 +464,14 @@ pub struct Options {
     )]
     pub tag_message: Option<String>,
 
+    #[clap(
+        long = "tag-conflict",
+        help = "what to do when the tag already exists (warn, error or replace)",
+        env = "BUMPVERSION_TAG_CONFLICT",
+        global = true
+    )]
+    pub tag_conflict: Option<String>,
+
     #[clap(
         short = 'm',
         long = "message",
@@ -357,11 +489,89 @@ pub struct Options {
     )]
     pub commit_args: Option<String>,
 
+    #[clap(
+        long = "dev-version",
+        help = "development version printed by describe",
+        env = "BUMPVERSION_DEV_VERSION",
+        global = true
+    )]
+    pub dev_version: Option<String>,
+
+    #[clap(
+        long = "push",
+        help = "push the commit and tag to the remote",
+        env = "BUMPVERSION_PUSH",
+        action = clap::ArgAction::SetTrue,
+        global = true,
+    )]
+    pub push: Option<bool>,
+
+    #[clap(
+        long = "no-push",
+        help = "do not push the commit and tag to the remote",
+        env = "BUMPVERSION_NO_PUSH",
+        action = clap::ArgAction::SetTrue,
+        global = true,
+    )]
+    pub no_push: Option<bool>,
+
+    #[clap(
+        long = "push-remote",
+        help = "remote to push to",
+        env = "BUMPVERSION_PUSH_REMOTE",
+        global = true
+    )]
+    pub push_remote: Option<String>,
+
     #[clap(subcommand)]
     pub command: Option<SubCommand>,
 
     #[arg()]
     pub args: Vec<String>,
+
+    /// Where each option given on the command line or in the environment came from, by argument
+    #[clap(skip)]
+    pub origins: HashMap<String, config::Origin>,
+}
+
+/// Parse the options from `args`, recording where each given option came from.
+///
+/// Exits with the usage if the arguments are invalid, like [`clap::Parser::parse_from`].
+pub fn parse_from<I, T>(args: I) -> Options
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    use clap::{CommandFactory, FromArgMatches, parser::ValueSource};
+
+    let mut command = Options::command();
+    let matches = command
+        .try_get_matches_from_mut(args)
+        .unwrap_or_else(|err| err.exit());
+    let mut options =
+        Options::from_arg_matches(&matches).unwrap_or_else(|err| err.format(&mut command).exit());
+    options.origins = command
+        .get_arguments()
+        .filter_map(|arg| {
+            let id = arg.get_id().as_str();
+            // an unset flag still has a value, but it is not the one that was given
+            if matches!(arg.get_action(), clap::ArgAction::SetTrue) && !matches.get_flag(id) {
+                return None;
+            }
+            let origin = match matches.value_source(id)? {
+                ValueSource::CommandLine => config::Origin::CommandLine {
+                    flag: format!("--{}", arg.get_long()?),
+                },
+                ValueSource::EnvVariable => config::Origin::Environment {
+                    variable: arg.get_env()?.to_string_lossy().into_owned(),
+                },
+                _ => return None,
+            };
+            Some((id.to_string(), origin))
+        })
+        .collect();
+    fix(&mut options);
+    options
 }
 
 pub fn fix(options: &mut Options) {
@@ -375,6 +585,7 @@ pub fn fix(options: &mut Options) {
     for boolean_option in [
         &mut options.allow_dirty,
         &mut options.no_allow_dirty,
+        &mut options.allow_downgrade,
         &mut options.regex,
         &mut options.no_regex,
         &mut options.no_configured_files,
@@ -383,12 +594,15 @@ pub fn fix(options: &mut Options) {
         &mut options.ignore_missing_version,
         &mut options.no_ignore_missing_version,
         &mut options.dry_run,
+        &mut options.keep_on_failure,
         &mut options.commit,
         &mut options.no_commit,
         &mut options.tag,
         &mut options.no_tag,
         &mut options.sign_tags,
         &mut options.no_sign_tag,
+        &mut options.push,
+        &mut options.no_push,
     ] {
         if *boolean_option != Some(true) {
             *boolean_option = None;
@@ -403,6 +617,10 @@ pub fn parse_positional_arguments(
     let mut cli_files = vec![];
     let mut bump: Option<String> = None;
 
+    options.command = options
+        .command
+        .take()
+        .map(|command| command.or_component_bump(components));
     if let Some(command) = &options.command {
         match command {
             SubCommand::Major => bump = Some("major".to_string()),
@@ -412,7 +630,18 @@ pub fn parse_positional_arguments(
                 bump = Some(opts.component.clone());
                 cli_files.extend(opts.args.iter().map(PathBuf::from));
             }
-            SubCommand::Finalize | SubCommand::Show(_) | SubCommand::ShowBump(_) => {
+            SubCommand::Pre(opts) => {
+                cli_files.extend(opts.args.iter().map(PathBuf::from));
+            }
+            SubCommand::Finalize
+            | SubCommand::Auto
+            | SubCommand::Promote
+            | SubCommand::Release
+            | SubCommand::Check
+            | SubCommand::Describe(_)
+            | SubCommand::Explain(_)
+            | SubCommand::Show(_)
+            | SubCommand::ShowBump(_) => {
                 // These commands don't produce a 'bump' action or files in the same way
                 // They are handled separately in common.rs
             }
@@ -444,6 +673,40 @@ pub fn parse_positional_arguments(
     Ok((bump, cli_files))
 }
 
+/// The global setting each option overrides, along with the arguments that set it.
+const OVERRIDES: &[(&str, &[&str])] = &[
+    ("allow_dirty", &["allow_dirty", "no_allow_dirty"]),
+    ("allow_downgrade", &["allow_downgrade"]),
+    ("current_version", &["current_version"]),
+    ("parse", &["parse_version_pattern"]),
+    ("serialize", &["serialize_version_patterns"]),
+    ("scheme", &["scheme"]),
+    ("search", &["search"]),
+    ("replace", &["replace"]),
+    ("no_configured_files", &["no_configured_files"]),
+    (
+        "ignore_missing_files",
+        &["ignore_missing_files", "no_ignore_missing_files"],
+    ),
+    (
+        "ignore_missing_version",
+        &["ignore_missing_version", "no_ignore_missing_version"],
+    ),
+    ("dry_run", &["dry_run"]),
+    ("keep_on_failure", &["keep_on_failure"]),
+    ("commit", &["commit", "no_commit"]),
+    ("tag", &["tag", "no_tag"]),
+    ("sign_tags", &["sign_tags", "no_sign_tag"]),
+    ("tag_name", &["tag_name"]),
+    ("tag_message", &["tag_message"]),
+    ("tag_conflict", &["tag_conflict"]),
+    ("commit_message", &["commit_message"]),
+    ("commit_args", &["commit_args"]),
+    ("push", &["push", "no_push"]),
+    ("push_remote", &["push_remote"]),
+    ("dev_version", &["dev_version"]),
+];
+
 pub fn global_cli_config(options: &Options) -> eyre::Result<bumpversion::config::GlobalConfig> {
     let search_as_regex = options.regex.or(options.no_regex.invert()).unwrap_or(false);
 
@@ -467,6 +730,12 @@ pub fn global_cli_config(options: &Options) -> eyre::Result<bumpversion::config:
         .map(bumpversion::config::Regex::try_from)
         .transpose()?;
 
+    let scheme = options
+        .scheme
+        .as_deref()
+        .map(str::parse::<bumpversion::config::Scheme>)
+        .transpose()?;
+
     let serialize_version_patterns = options
         .serialize_version_patterns
         .as_ref()
@@ -491,17 +760,31 @@ pub fn global_cli_config(options: &Options) -> eyre::Result<bumpversion::config:
         .map(bumpversion::f_string::PythonFormatString::parse)
         .transpose()?;
 
+    let tag_conflict = options
+        .tag_conflict
+        .as_deref()
+        .map(str::parse::<bumpversion::config::TagConflict>)
+        .transpose()?;
+
     let commit_message = options
         .commit_message
         .as_deref()
         .map(bumpversion::f_string::PythonFormatString::parse)
         .transpose()?;
 
+    let dev_version = options
+        .dev_version
+        .as_deref()
+        .map(bumpversion::f_string::PythonFormatString::parse)
+        .transpose()?;
+
     let cli_overrides = bumpversion::config::GlobalConfig {
         allow_dirty: options.allow_dirty.or(options.no_allow_dirty.invert()),
+        allow_downgrade: options.allow_downgrade,
         current_version: options.current_version.clone(),
         parse_version_pattern,
         serialize_version_patterns,
+        scheme,
         search,
         replace: options.replace.clone(),
         no_configured_files: options.no_configured_files,
@@ -512,13 +795,25 @@ pub fn global_cli_config(options: &Options) -> eyre::Result<bumpversion::config:
             .ignore_missing_version
             .or(options.no_ignore_missing_version.invert()),
         dry_run: options.dry_run,
+        keep_on_failure: options.keep_on_failure,
         commit: options.commit.or(options.no_commit.invert()),
         tag: options.tag.or(options.no_tag.invert()),
         sign_tags: options.sign_tags.or(options.no_sign_tag.invert()),
         tag_name,
         tag_message,
+        tag_conflict,
         commit_message,
         commit_args: options.commit_args.clone(),
+        push: options.push.or(options.no_push.invert()),
+        push_remote: options.push_remote.clone(),
+        dev_version,
+        provenance: OVERRIDES
+            .iter()
+            .filter_map(|(key, ids)| {
+                let origin = ids.iter().find_map(|id| options.origins.get(*id))?;
+                Some((*key, origin.clone()))
+            })
+            .collect(),
         ..bumpversion::config::GlobalConfig::empty()
     };
     Ok(cli_overrides)
@@ -228,6 +228,318 @@ fn test_config_file_flag_rejects_a_missing_file() -> eyre::Result<()> {
     Ok(())
 }
 
+/// A zero-padded build number keeps its width when bumped and when reset.
+#[test]
+fn test_padded_component_keeps_its_width() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.0099"
+parse = "(?P<major>\\d+)\\.(?P<minor>\\d+)\\.(?P<build>\\d+)"
+serialize = ["{major}.{minor}.{build}"]
+
+[tool.bumpversion.parts.build]
+pad = true
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.0099")?;
+
+    for (component, expected) in [("build", "1.2.0100"), ("minor", "1.3.0000")] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["bump", component, "--allow-dirty"]);
+        cmd.assert().success();
+        assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, expected);
+    }
+    Ok(())
+}
+
+/// The date of a `calver:` scheme is its `date` component, which moves an old version to
+/// today and resets the components after it.
+#[test]
+fn test_calver_scheme_bumps_the_date() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "2020.1.5"
+scheme = "calver:YYYY.MM.patch"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "2020.1.5")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["bump", "date", "--allow-dirty"]);
+    cmd.assert().success();
+    let version = fs::read_to_string(temp.path().join("VERSION"))?;
+    assert!(
+        predicate::str::is_match(r"^\d{4}\.\d{1,2}\.0$")?.eval(&version),
+        "expected today's date with patch 0, got {version:?}"
+    );
+    assert!(
+        !version.starts_with("2020."),
+        "expected a new date, got {version:?}"
+    );
+    Ok(())
+}
+
+/// The semver scheme bumps through pre-release labels without any `parse` or `serialize`.
+#[test]
+fn test_semver_scheme_bumps_prerelease() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3-beta.1"
+scheme = "semver"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3-beta.1")?;
+
+    for (component, expected) in [
+        ("pre_number", "1.2.3-beta.2"),
+        ("pre_label", "1.2.3-rc.0"),
+        ("pre_label", "1.2.3"),
+    ] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["bump", component, "--allow-dirty"]);
+        cmd.assert().success();
+        assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, expected);
+    }
+    Ok(())
+}
+
+/// An unknown `--scheme` is rejected.
+#[test]
+fn test_unknown_scheme_is_rejected() -> eyre::Result<()> {
+    let temp = regex_search_repo()?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["bump", "patch", "--scheme", "romver"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown version scheme"));
+    Ok(())
+}
+
+/// A `.bumpversion.toml` at `1.2.3` with a `VERSION` file.
+fn version_file_repo() -> eyre::Result<tempfile::TempDir> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    Ok(temp)
+}
+
+/// `--new-version` used to accept any version, so a typo could move a release backwards.
+#[test]
+fn test_new_version_must_be_newer() -> eyre::Result<()> {
+    for new_version in ["1.2.2", "1.2.3", "0.9.0"] {
+        let temp = version_file_repo()?;
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["--allow-dirty", "--new-version", new_version]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "new version {new_version} is not newer than current version 1.2.3"
+            )));
+        assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    }
+    Ok(())
+}
+
+/// Releasing a pre-release or dev release is an upgrade, and build metadata does not count.
+#[test]
+fn test_release_of_a_prerelease_is_newer() -> eyre::Result<()> {
+    for (scheme, current_version, new_version) in [
+        ("pep440", "1.2.3.dev4", "1.2.3"),
+        ("semver", "1.0.0-rc.1", "1.0.0"),
+        ("semver", "1.0.0-rc.1", "1.0.0+build.1"),
+    ] {
+        let temp = repo_with(
+            ".bumpversion.toml",
+            &format!(
+                r#"
+[tool.bumpversion]
+current_version = "{current_version}"
+scheme = "{scheme}"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+"#
+            ),
+        )?;
+        fs::write(temp.path().join("VERSION"), current_version)?;
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["--allow-dirty", "--new-version", new_version]);
+        cmd.assert().success();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("VERSION"))?,
+            new_version
+        );
+    }
+
+    let temp = repo_with(
+        ".bumpversion.toml",
+        "[tool.bumpversion]\ncurrent_version = \"1.0.0+build.1\"\nscheme = \"semver\"\n",
+    )?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--new-version", "1.0.0+build.2"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "is not newer than current version",
+    ));
+    Ok(())
+}
+
+/// `--allow-downgrade` lets a lower version through.
+#[test]
+fn test_allow_downgrade_accepts_a_lower_version() -> eyre::Result<()> {
+    let temp = version_file_repo()?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args([
+        "--allow-dirty",
+        "--allow-downgrade",
+        "--new-version",
+        "1.2.2",
+    ]);
+    cmd.assert().success();
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.2");
+    Ok(())
+}
+
+/// A file limited to `major` bumps is left alone by every other bump.
+#[test]
+fn test_include_bumps_limits_a_file_to_some_components() -> eyre::Result<()> {
+    for (component, api_version) in [("patch", "1.2.3"), ("major", "2.0.0")] {
+        let temp = repo_with(
+            ".bumpversion.toml",
+            r#"
+[tool.bumpversion]
+current_version = "1.2.3"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+
+[[tool.bumpversion.files]]
+filename = "API"
+include_bumps = ["major"]
+"#,
+        )?;
+        fs::write(temp.path().join("VERSION"), "1.2.3")?;
+        fs::write(temp.path().join("API"), "1.2.3")?;
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["--allow-dirty", "bump", component]);
+        cmd.assert().success();
+        assert_ne!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+        assert_eq!(fs::read_to_string(temp.path().join("API"))?, api_version);
+    }
+    Ok(())
+}
+
+/// A typo in `exclude_bumps` is reported instead of silently never matching.
+#[test]
+fn test_unknown_component_in_exclude_bumps_is_rejected() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+exclude_bumps = ["pacth"]
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "bump", "patch"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown version component"));
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    Ok(())
+}
+
+/// Every unknown component in `include_bumps` and `exclude_bumps` is reported, not just the first.
+#[test]
+fn test_all_unknown_components_are_reported() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+include_bumps = ["mayor"]
+exclude_bumps = ["pacth"]
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "bump", "patch"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(r#""mayor" in `include_bumps`"#))
+        .stderr(predicate::str::contains(r#""pacth" in `exclude_bumps`"#));
+    Ok(())
+}
+
+/// `include_bumps` may name a component that only the `--parse` pattern on the command line has.
+#[test]
+fn test_include_bumps_accepts_components_of_the_parse_flag() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3.4"
+
+[[tool.bumpversion.files]]
+filename = "VERSION"
+include_bumps = ["build"]
+"#,
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3.4")?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args([
+        "--allow-dirty",
+        "bump",
+        "build",
+        "--parse",
+        r"(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)\.(?P<build>\d+)",
+        "--serialize",
+        "{major}.{minor}.{patch}.{build}",
+    ]);
+    cmd.assert().success();
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3.5");
+    Ok(())
+}
+
 /// A repository whose `VERSION` file needs a regex to match.
 fn regex_search_repo() -> eyre::Result<tempfile::TempDir> {
     let temp = tempfile::tempdir()?;
@@ -365,6 +677,40 @@ fn test_ini_config_updates_current_version() -> eyre::Result<()> {
     Ok(())
 }
 
+/// A `Cargo.toml` with `[package.metadata.bumpversion]` was a discovery
+/// candidate that always parsed to nothing.
+#[test]
+fn test_cargo_toml_config_updates_package_version() -> eyre::Result<()> {
+    let temp = repo_with(
+        "Cargo.toml",
+        indoc! {r#"
+            [package]
+            name = "demo"
+            version = "0.3.1" # managed by bumpversion
+
+            [package.metadata.bumpversion]
+            tag_name = "demo-v{new_version}"
+        "#},
+    )?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag"])
+        .args(["bump", "minor"]);
+    cmd.assert().success();
+
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml"))?;
+    assert!(
+        manifest.contains(r#"version = "0.4.0" # managed by bumpversion"#),
+        "the package version must be updated in place, got:\n{manifest}"
+    );
+    assert!(
+        manifest.contains(r#"tag_name = "demo-v{new_version}""#),
+        "the rest of the file must be preserved, got:\n{manifest}"
+    );
+    Ok(())
+}
+
 /// `RUST_LOG` was passed to `with_env_var`, which expects a variable *name*, so
 /// every setting failed to resolve and fell back to the defaults.
 #[test]
@@ -543,7 +889,8 @@ fn test_finalize_commits_and_tags_an_applied_bump() -> eyre::Result<()> {
     Ok(())
 }
 
-/// A failed pre-commit check prints its output once and ends with recovery instructions.
+/// With `--keep-on-failure`, a failed pre-commit check prints its output once and ends with
+/// instructions to finalize the bump that is left in the working tree.
 #[test]
 fn test_pre_commit_failure_has_recovery_guidance() -> eyre::Result<()> {
     let temp = tempfile::tempdir()?;
@@ -571,7 +918,8 @@ fn test_pre_commit_failure_has_recovery_guidance() -> eyre::Result<()> {
     eyre::ensure!(tag.status.success(), "failed to tag previous version");
 
     let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
-    cmd.current_dir(temp.path()).args(["bump", "minor"]);
+    cmd.current_dir(temp.path())
+        .args(["bump", "minor", "--keep-on-failure"]);
     cmd.assert()
         .failure()
         .stderr(predicate::str::contains(
@@ -595,6 +943,73 @@ fn test_pre_commit_failure_has_recovery_guidance() -> eyre::Result<()> {
     Ok(())
 }
 
+/// A failed pre-commit check restores every rewritten file, including the config file.
+#[test]
+fn test_pre_commit_failure_rolls_back_the_bump() -> eyre::Result<()> {
+    let temp = tempfile::tempdir()?;
+    let config = indoc! {r#"
+        [tool.bumpversion]
+        current_version = "1.2.3"
+        commit = true
+        tag = true
+        pre_commit_hooks = ["exit 7"]
+
+        [[tool.bumpversion.files]]
+        filename = "VERSION"
+    "#};
+    fs::write(temp.path().join(".bumpversion.toml"), config)?;
+    fs::write(temp.path().join("VERSION"), "1.2.3\n")?;
+    git_init(temp.path())?;
+    git_commit_all(temp.path())?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args(["bump", "minor"]);
+    cmd.assert().failure().stderr(predicate::str::ends_with(
+        "The version changes were rolled back.\n\nFix the issue and run the bump again.\n",
+    ));
+
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3\n");
+    assert_eq!(
+        fs::read_to_string(temp.path().join(".bumpversion.toml"))?,
+        config
+    );
+    let status = std::process::Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(temp.path())
+        .output()?;
+    eyre::ensure!(status.status.success(), "failed to inspect status");
+    assert_eq!(String::from_utf8(status.stdout)?, "");
+    Ok(())
+}
+
+/// A commit rejected by the repository's git pre-commit hook leaves neither the bumped
+/// files nor their staged versions behind.
+#[test]
+#[cfg_attr(
+    feature = "gitoxide",
+    ignore = "the gitoxide backend does not run git hooks"
+)]
+fn test_commit_failure_unstages_the_bump() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+    git_commit_all(temp.path())?;
+    let hook = temp.path().join(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\nexit 1\n")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("minor");
+    cmd.assert().failure();
+
+    assert_untouched(temp.path())?;
+    assert_eq!(git(temp.path(), &["diff", "--cached", "--name-only"])?, "");
+    assert_eq!(git(temp.path(), &["status", "--porcelain"])?, "");
+    Ok(())
+}
+
 /// Hook scripts were shlex-split before being handed to `sh -c`, which takes the
 /// whole script as one argument — so only the first word ran and the rest became
 /// positional parameters. Any hook with arguments or a redirect did nothing.
@@ -941,3 +1356,1124 @@ filename = "VERSION"
     );
     Ok(())
 }
+
+/// A search pattern that no longer matched was a silent no-op, so a stale
+/// `search` produced a release that never touched the file.
+#[test]
+fn test_missing_version_fails_with_diagnostic() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+            search = "version: {current_version}"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag"])
+        .args(["bump", "patch"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "did not find `version: 1\\.2\\.3`",
+        ))
+        .stderr(predicate::str::contains("ignore_missing_version"));
+
+    assert!(
+        fs::read_to_string(temp.path().join(".bumpversion.toml"))?
+            .contains(r#"current_version = "1.2.3""#),
+        "the config must not record a bump that failed"
+    );
+    Ok(())
+}
+
+/// Problems used to surface one at a time, and only once the bump got to them,
+/// so all of them are reported together before any file is written.
+#[test]
+fn test_preflight_reports_every_problem_before_writing() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            tag = true
+            tag_message = "Release {new_version} ({build})"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+            search = "version: {current_version}"
+
+            [[tool.bumpversion.files]]
+            filename = "MISSING"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit"])
+        .args(["bump", "patch"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "did not find `version: 1\\.2\\.3`",
+        ))
+        .stderr(predicate::str::contains("MISSING does not exist"))
+        .stderr(predicate::str::contains("cannot render `tag_message`"))
+        .stderr(predicate::str::contains(r#"missing argument "build""#))
+        .stderr(predicate::str::contains(
+            "3 problems found before touching any file",
+        ));
+
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    assert!(
+        fs::read_to_string(temp.path().join(".bumpversion.toml"))?
+            .contains(r#"current_version = "1.2.3""#)
+    );
+    Ok(())
+}
+
+/// With `ignore_missing_version`, the same mismatch is reported but the bump
+/// goes ahead.
+#[test]
+fn test_ignore_missing_version_warns() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+            search = "version: {current_version}"
+            ignore_missing_version = true
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag"])
+        .args(["bump", "patch"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "did not find `version: 1\\.2\\.3`",
+        ))
+        .stderr(predicate::str::contains(
+            "the search pattern of this entry did not match",
+        ));
+
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    assert!(
+        fs::read_to_string(temp.path().join(".bumpversion.toml"))?
+            .contains(r#"current_version = "1.2.4""#)
+    );
+    Ok(())
+}
+
+/// A `key_path` limits the replacement to one value of a data file, leaving a
+/// dependency pinned to the same version untouched.
+#[test]
+fn test_key_path_replaces_only_data_file_value() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "package.json"
+            key_path = "version"
+        "#},
+    )?;
+    let package_json = indoc! {r#"
+        {
+          "name": "demo",
+          "version": "1.2.3",
+          "dependencies": { "other": "1.2.3" }
+        }
+    "#};
+    fs::write(temp.path().join("package.json"), package_json)?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag"])
+        .args(["bump", "minor"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        fs::read_to_string(temp.path().join("package.json"))?,
+        package_json.replace(r#""version": "1.2.3""#, r#""version": "1.3.0""#)
+    );
+    Ok(())
+}
+
+/// `pre <component>`, `pre` and `release` walk a version into and out of a pre-release
+/// without naming the label or number components.
+#[test]
+fn test_prerelease_lifecycle_commands() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            parse = '(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)(-(?P<pre_l>[a-z]+)\.(?P<pre_n>\d+))?'
+            serialize = ["{major}.{minor}.{patch}-{pre_l}.{pre_n}", "{major}.{minor}.{patch}"]
+
+            [tool.bumpversion.parts.pre_l]
+            values = ["rc", "final"]
+            optional_value = "final"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+
+    for (args, expected) in [
+        (&["pre", "minor"][..], "1.3.0-rc.0"),
+        (&["pre"][..], "1.3.0-rc.1"),
+        (&["release"][..], "1.3.0"),
+    ] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path())
+            .args(["--allow-dirty", "--no-commit", "--no-tag"])
+            .args(args);
+        cmd.assert().success();
+        assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, expected);
+    }
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag", "promote"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("version is not a pre-release"));
+    Ok(())
+}
+
+/// A component named like a pre-release lifecycle command is bumped by that name,
+/// as it was before the commands existed.
+#[test]
+fn test_component_named_release_is_bumped() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            parse = '(?P<major>\d+)\.(?P<minor>\d+)\.(?P<release>\d+)'
+            serialize = ["{major}.{minor}.{release}"]
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--allow-dirty", "--no-commit", "--no-tag", "release"]);
+    cmd.assert().success();
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.4");
+    Ok(())
+}
+
+/// `--package` bumps one package of a monorepo on its own version, files and
+/// tag prefix, leaving the other package alone.
+#[test]
+fn test_package_is_bumped_independently() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            commit = true
+            tag = true
+            tag_name = "{package}-v{new_version}"
+            commit_message = "Release {package} {new_version}"
+
+            [tool.bumpversion.packages.core]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.packages.core.files]]
+            filename = "core/VERSION"
+
+            [tool.bumpversion.packages.cli]
+            current_version = "0.4.0"
+
+            [[tool.bumpversion.packages.cli.files]]
+            filename = "cli/VERSION"
+        "#},
+    )?;
+    for (package, version) in [("core", "1.2.3"), ("cli", "0.4.0")] {
+        fs::create_dir(temp.path().join(package))?;
+        fs::write(temp.path().join(package).join("VERSION"), version)?;
+    }
+    git_commit_all(temp.path())?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--package", "cli", "minor"]);
+    cmd.assert().success();
+
+    assert_eq!(
+        fs::read_to_string(temp.path().join("cli/VERSION"))?,
+        "0.5.0"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("core/VERSION"))?,
+        "1.2.3"
+    );
+    let config = fs::read_to_string(temp.path().join(".bumpversion.toml"))?;
+    assert!(config.contains(r#"current_version = "0.5.0""#));
+    assert!(config.contains(r#"current_version = "1.2.3""#));
+
+    let tags = std::process::Command::new("git")
+        .args(["tag", "--points-at", "HEAD"])
+        .current_dir(temp.path())
+        .output()?;
+    assert_eq!(String::from_utf8(tags.stdout)?.trim(), "cli-v0.5.0");
+    let subject = std::process::Command::new("git")
+        .args(["log", "-1", "--format=%s"])
+        .current_dir(temp.path())
+        .output()?;
+    assert_eq!(
+        String::from_utf8(subject.stdout)?.trim(),
+        "Release cli 0.5.0"
+    );
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--package", "missing", "minor"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown package \"missing\""));
+    Ok(())
+}
+
+/// `auto` bumps the most significant component the Conventional Commits since the
+/// latest tag call for, and fails when none of them calls for a release.
+#[test]
+fn test_auto_bumps_from_conventional_commits() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            commit = true
+            tag = true
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    git_commit_all(temp.path())?;
+
+    git(temp.path(), &["tag", "v1.2.3"])?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "docs: explain auto"],
+    )?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "feat(cli): add auto"],
+    )?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "fix: off by one"],
+    )?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("auto");
+    cmd.assert().success();
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.3.0");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("auto");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "no commits since v1.3.0 call for a version bump",
+    ));
+    Ok(())
+}
+
+/// With `changelog` set, a bump adds a section for the new version listing the
+/// commits since the latest tag, and commits it with the version change.
+#[test]
+fn test_changelog_section_is_committed_with_the_bump() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            commit = true
+            tag = true
+            changelog = "CHANGELOG.md"
+            changelog_header = '## {new_version}'
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    fs::write(
+        temp.path().join("CHANGELOG.md"),
+        "# Changelog\n\n## 1.2.3\n\n- initial release\n",
+    )?;
+    git_commit_all(temp.path())?;
+
+    git(temp.path(), &["tag", "v1.2.3"])?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "feat(cli): add auto"],
+    )?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "fix: off by one"],
+    )?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "chore: tidy up"],
+    )?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("minor");
+    cmd.assert().success();
+
+    assert_eq!(
+        fs::read_to_string(temp.path().join("CHANGELOG.md"))?,
+        indoc! {"
+            # Changelog
+
+            ## 1.3.0
+
+            ### Features
+
+            - add auto
+
+            ### Bug fixes
+
+            - off by one
+
+            ## 1.2.3
+
+            - initial release
+        "}
+    );
+    assert_eq!(git(temp.path(), &["status", "--porcelain"])?, "");
+    Ok(())
+}
+
+/// A changelog that does not exist yet is created and committed with the bump.
+#[test]
+fn test_missing_changelog_is_created_and_committed() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            commit = true
+            changelog = "CHANGELOG.md"
+            changelog_header = '## {new_version}'
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    git_commit_all(temp.path())?;
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "fix: off by one"],
+    )?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("patch");
+    cmd.assert().success();
+
+    assert!(
+        fs::read_to_string(temp.path().join("CHANGELOG.md"))?.contains("## 1.2.4"),
+        "expected a section for 1.2.4"
+    );
+    assert_eq!(
+        git(
+            temp.path(),
+            &[
+                "show",
+                "--name-only",
+                "--format=",
+                "HEAD",
+                "--",
+                "CHANGELOG.md"
+            ]
+        )?,
+        "CHANGELOG.md"
+    );
+    assert_eq!(git(temp.path(), &["status", "--porcelain"])?, "");
+    Ok(())
+}
+
+/// The default changelog heading carries the date of the bump.
+#[test]
+fn test_changelog_default_header_has_the_date() -> eyre::Result<()> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            commit = true
+            changelog = "CHANGELOG.md"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#},
+    )?;
+    fs::write(temp.path().join("VERSION"), "1.2.3")?;
+    fs::write(temp.path().join("CHANGELOG.md"), "# Changelog\n")?;
+    git_commit_all(temp.path())?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("patch");
+    cmd.assert().success();
+
+    let changelog = fs::read_to_string(temp.path().join("CHANGELOG.md"))?;
+    assert!(
+        predicate::str::is_match(r"(?m)^## 1\.2\.4 \(\d{4}-\d{2}-\d{2}\)$")?.eval(&changelog),
+        "expected a dated heading, got:\n{changelog}"
+    );
+    Ok(())
+}
+
+/// `--output json` prints the whole bump as one JSON document, with logs kept off stdout.
+#[test]
+fn test_bump_json_output() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--output", "json", "--dry-run", "--allow-dirty", "-vv"])
+        .args(["--log", "info", "minor"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["current_version"]["serialized"], "1.2.3");
+    assert_eq!(report["new_version"]["serialized"], "1.3.0");
+    assert_eq!(
+        report["new_version"]["components"],
+        serde_json::json!({ "major": "1", "minor": "3", "patch": "0" })
+    );
+    let version_file = &report["files"][0];
+    assert!(
+        version_file["path"]
+            .as_str()
+            .is_some_and(|path| path.ends_with("VERSION"))
+    );
+    assert_eq!(version_file["replacements"][0]["replace"], "1.3.0");
+    assert!(
+        version_file["diff"]
+            .as_str()
+            .is_some_and(|diff| diff.contains("-1.2.3") && diff.contains("+1.3.0"))
+    );
+    assert_eq!(report["commit"]["message"], "Bump version: 1.2.3 → 1.3.0");
+    assert_eq!(report["tag"]["name"], "v1.3.0");
+
+    assert_untouched(temp.path())
+}
+
+/// `show-bump` reports both versions with their components.
+#[test]
+fn test_show_bump_json_output() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["show-bump", "major", "--output", "json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "current_version": {
+                "serialized": "1.2.3",
+                "components": { "major": "1", "minor": "2", "patch": "3" },
+            },
+            "new_version": {
+                "serialized": "2.0.0",
+                "components": { "major": "2", "minor": "0", "patch": "0" },
+            },
+        })
+    );
+    Ok(())
+}
+
+/// Run git in `dir` and return its stdout.
+fn git(dir: &Path, args: &[&str]) -> eyre::Result<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    eyre::ensure!(
+        output.status.success(),
+        "failed to run git {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// An `armed_repo` with `push` enabled, committed and pushed to a local bare
+/// repository as `origin`, which is returned second.
+fn repo_with_remote() -> eyre::Result<(tempfile::TempDir, tempfile::TempDir)> {
+    let temp = armed_repo()?;
+    let config_path = temp.path().join(".bumpversion.toml");
+    let config = fs::read_to_string(&config_path)?;
+    fs::write(
+        &config_path,
+        config.replace("tag = true", "tag = true\npush = true"),
+    )?;
+    git_commit_all(temp.path())?;
+
+    let remote = tempfile::tempdir()?;
+    git(remote.path(), &["init", "--bare"])?;
+    let remote_path = remote.path().to_string_lossy().to_string();
+    git(temp.path(), &["remote", "add", "origin", &remote_path])?;
+    git(temp.path(), &["push", "origin", "HEAD"])?;
+    Ok((temp, remote))
+}
+
+/// With `push`, the release commit and its tag reach the remote.
+#[test]
+fn test_push_sends_commit_and_tag_to_the_remote() -> eyre::Result<()> {
+    let (temp, remote) = repo_with_remote()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("minor");
+    cmd.assert().success();
+
+    let branch = git(temp.path(), &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let head = git(temp.path(), &["rev-parse", "HEAD"])?;
+    assert_eq!(
+        git(
+            remote.path(),
+            &["rev-parse", &format!("refs/heads/{branch}")]
+        )?,
+        head
+    );
+    assert_eq!(git(remote.path(), &["rev-parse", "v1.3.0^{commit}"])?, head);
+    Ok(())
+}
+
+/// A rejected push fails the bump with a clear error, and updates neither the
+/// branch nor the tag on the remote.
+#[test]
+fn test_push_rejected_by_the_remote_fails() -> eyre::Result<()> {
+    let (temp, remote) = repo_with_remote()?;
+    let remote_head = git(remote.path(), &["rev-parse", "HEAD"])?;
+    let hook = remote.path().join("hooks").join("pre-receive");
+    fs::write(&hook, "#!/bin/sh\necho 'releases are frozen' >&2\nexit 1\n")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("minor");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(r#"failed to push to "origin""#))
+        .stderr(predicate::str::contains("releases are frozen"));
+
+    assert_eq!(git(remote.path(), &["rev-parse", "HEAD"])?, remote_head);
+    assert_eq!(git(remote.path(), &["tag", "--list"])?, "");
+    Ok(())
+}
+
+/// An armed repository whose next patch release, `v1.2.4`, is already tagged at the
+/// initial commit.
+fn repo_with_next_tag() -> eyre::Result<tempfile::TempDir> {
+    let temp = armed_repo()?;
+    git_commit_all(temp.path())?;
+    git(
+        temp.path(),
+        &["tag", "-a", "v1.2.4", "-m", "premature release"],
+    )?;
+    Ok(temp)
+}
+
+/// With `tag_conflict = "error"`, an existing release tag aborts the bump before
+/// anything is written or committed, rather than committing a release without it.
+#[test]
+fn test_tag_conflict_error_aborts_before_committing() -> eyre::Result<()> {
+    let temp = repo_with_next_tag()?;
+    let head = git(temp.path(), &["rev-parse", "HEAD"])?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--tag-conflict", "error"])
+        .args(["bump", "patch"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(r#"tag "v1.2.4" already exists"#));
+
+    assert_eq!(git(temp.path(), &["rev-parse", "HEAD"])?, head);
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    Ok(())
+}
+
+/// With `tag_conflict = "replace"`, the existing release tag is moved to the new commit.
+#[test]
+fn test_tag_conflict_replace_moves_the_tag() -> eyre::Result<()> {
+    let temp = repo_with_next_tag()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--tag-conflict", "replace", "--output", "json"])
+        .args(["bump", "patch"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""replaced": true"#));
+
+    assert_eq!(
+        git(temp.path(), &["rev-parse", "v1.2.4^{commit}"])?,
+        git(temp.path(), &["rev-parse", "HEAD"])?
+    );
+    assert_eq!(
+        git(
+            temp.path(),
+            &["tag", "-l", "--format=%(contents:subject)", "v1.2.4"]
+        )?,
+        "Bump version: 1.2.3 → 1.2.4"
+    );
+    Ok(())
+}
+
+/// A replaced tag stays annotated, and is force-pushed to the remote that already has it.
+#[test]
+fn test_tag_conflict_replace_force_pushes_the_tag() -> eyre::Result<()> {
+    let (temp, remote) = repo_with_remote()?;
+    git(
+        temp.path(),
+        &["tag", "-a", "v1.2.4", "-m", "premature release"],
+    )?;
+    git(temp.path(), &["push", "origin", "v1.2.4"])?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--tag-conflict", "replace"])
+        .args(["bump", "patch"]);
+    cmd.assert().success();
+
+    let head = git(temp.path(), &["rev-parse", "HEAD"])?;
+    assert_eq!(git(temp.path(), &["cat-file", "-t", "v1.2.4"])?, "tag");
+    assert_eq!(git(remote.path(), &["rev-parse", "v1.2.4^{commit}"])?, head);
+    Ok(())
+}
+
+/// `check` passes silently when every configured file has the current version.
+#[test]
+fn test_check_passes_on_a_consistent_repo() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("check");
+    cmd.assert().success().stdout(predicate::str::is_empty());
+
+    assert_untouched(temp.path())?;
+    Ok(())
+}
+
+/// `check` fails on a file with a stale version, and points at its line.
+#[test]
+fn test_check_reports_a_stale_file() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+    fs::write(temp.path().join("VERSION"), "1.2.2")?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("check");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("did not find `1\\.2\\.3`"))
+        .stderr(predicate::str::contains("found version 1.2.2"))
+        .stderr(predicate::str::contains("1 problem with version 1.2.3"));
+
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.2");
+    Ok(())
+}
+
+/// `check` fails when the current version is not the version of the latest tag.
+#[test]
+fn test_check_reports_a_tag_mismatch() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+    git_commit_all(temp.path())?;
+    git(temp.path(), &["tag", "v1.2.0"])?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--output", "json", "check"]);
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        report["problems"],
+        serde_json::json!([{
+            "kind": "tag_mismatch",
+            "current_version": "1.2.3",
+            "tag": "v1.2.0",
+            "tagged_version": "1.2.0",
+        }])
+    );
+    Ok(())
+}
+
+/// An `armed_repo` committed with `moveable_tags` for the major and minor version.
+fn repo_with_moveable_tags() -> eyre::Result<tempfile::TempDir> {
+    let temp = armed_repo()?;
+    let config_path = temp.path().join(".bumpversion.toml");
+    let config = fs::read_to_string(&config_path)?;
+    fs::write(
+        &config_path,
+        config.replace(
+            "tag = true",
+            "tag = true\nmoveable_tags = [\"v{new_major}\", \"v{new_major}.{new_minor}\"]",
+        ),
+    )?;
+    git_commit_all(temp.path())?;
+    Ok(temp)
+}
+
+/// `moveable_tags` follow every release, and are annotated like the release tag.
+#[test]
+fn test_moveable_tags_follow_each_release() -> eyre::Result<()> {
+    let temp = repo_with_moveable_tags()?;
+
+    for (component, release) in [("minor", "v1.3.0"), ("patch", "v1.3.1")] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path()).arg(component);
+        cmd.assert().success();
+
+        let head = git(temp.path(), &["rev-parse", "HEAD"])?;
+        for tag in [release, "v1", "v1.3"] {
+            assert_eq!(
+                git(temp.path(), &["rev-parse", &format!("{tag}^{{commit}}")])?,
+                head,
+                "{tag} must point at the release commit"
+            );
+            assert_eq!(
+                git(temp.path(), &["cat-file", "-t", tag])?,
+                "tag",
+                "{tag} must be annotated like the release tag"
+            );
+        }
+    }
+    assert_eq!(
+        git(temp.path(), &["tag", "--list"])?,
+        "v1\nv1.3\nv1.3.0\nv1.3.1"
+    );
+    Ok(())
+}
+
+/// A dry run logs every tag it would move, and moves none.
+#[test]
+fn test_moveable_tags_dry_run() -> eyre::Result<()> {
+    let temp = repo_with_moveable_tags()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--dry-run", "-v", "--color", "never", "minor"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("move = v1\n"))
+        .stdout(predicate::str::contains("move = v1.3\n"));
+
+    assert_eq!(git(temp.path(), &["tag", "--list"])?, "");
+    Ok(())
+}
+
+/// With `push`, moved tags are force-pushed, so the remote follows every release.
+#[test]
+fn test_push_force_pushes_moved_tags() -> eyre::Result<()> {
+    let (temp, remote) = repo_with_remote()?;
+    let config_path = temp.path().join(".bumpversion.toml");
+    let config = fs::read_to_string(&config_path)?;
+    fs::write(
+        &config_path,
+        config.replace(
+            "push = true",
+            "push = true\nmoveable_tags = [\"v{new_major}\"]",
+        ),
+    )?;
+    git_commit_all(temp.path())?;
+
+    for component in ["minor", "patch"] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+        cmd.current_dir(temp.path()).arg(component);
+        cmd.assert().success();
+
+        let head = git(temp.path(), &["rev-parse", "HEAD"])?;
+        assert_eq!(git(remote.path(), &["rev-parse", "v1^{commit}"])?, head);
+    }
+    Ok(())
+}
+
+/// A Cargo workspace whose members inherit the version or pin it, and depend on each other.
+fn cargo_workspace_repo() -> eyre::Result<tempfile::TempDir> {
+    let temp = repo_with(
+        ".bumpversion.toml",
+        r#"
+[tool.bumpversion]
+current_version = "1.2.3"
+commit = true
+
+[[tool.bumpversion.files]]
+filename = "Cargo.toml"
+cargo_workspace = true
+"#,
+    )?;
+    let root = temp.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        indoc! {r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.package]
+            version = "1.2.3" # released together
+
+            [workspace.dependencies]
+            core = { path = "crates/core", version = "1.2.3" }
+        "#},
+    )?;
+    fs::create_dir_all(root.join("crates/core"))?;
+    fs::write(
+        root.join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    )?;
+    fs::create_dir_all(root.join("crates/app"))?;
+    fs::write(
+        root.join("crates/app/Cargo.toml"),
+        indoc! {r#"
+            [package]
+            name = "app"
+            version = "1.2.3"
+
+            [dependencies]
+            core = { path = "../core", version = "=1.2.3" }
+            serde = "1.2.3"
+        "#},
+    )?;
+    fs::write(
+        root.join("Cargo.lock"),
+        indoc! {r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "1.2.3"
+
+            [[package]]
+            name = "core"
+            version = "1.2.3"
+
+            [[package]]
+            name = "serde"
+            version = "1.2.3"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+        "#},
+    )?;
+    git_commit_all(root)?;
+    Ok(temp)
+}
+
+/// `cargo_workspace` updates every crate, the requirements between them and `Cargo.lock`, and
+/// commits them all, without a hook running cargo.
+#[test]
+fn test_cargo_workspace_bumps_every_crate() -> eyre::Result<()> {
+    let temp = cargo_workspace_repo()?;
+    let root = temp.path();
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(root).args(["bump", "minor"]);
+    cmd.assert().success();
+
+    let manifest = fs::read_to_string(root.join("Cargo.toml"))?;
+    assert!(manifest.contains(r#"version = "1.3.0" # released together"#));
+    assert!(manifest.contains(r#"core = { path = "crates/core", version = "1.3.0" }"#));
+    let app = fs::read_to_string(root.join("crates/app/Cargo.toml"))?;
+    assert!(app.contains("name = \"app\"\nversion = \"1.3.0\"\n"));
+    assert!(app.contains(r#"core = { path = "../core", version = "=1.3.0" }"#));
+    assert!(app.contains(r#"serde = "1.2.3""#));
+    assert_eq!(
+        fs::read_to_string(root.join("crates/core/Cargo.toml"))?,
+        "[package]\nname = \"core\"\nversion.workspace = true\n"
+    );
+    let lockfile = fs::read_to_string(root.join("Cargo.lock"))?;
+    assert_eq!(lockfile.matches(r#"version = "1.3.0""#).count(), 2);
+    assert!(lockfile.contains("name = \"serde\"\nversion = \"1.2.3\"\n"));
+
+    assert_eq!(git(root, &["status", "--porcelain"])?, "");
+    Ok(())
+}
+
+/// An `armed_repo` with its version tagged, and one more commit on top.
+fn repo_past_tag() -> eyre::Result<tempfile::TempDir> {
+    let temp = armed_repo()?;
+    git_commit_all(temp.path())?;
+    git(temp.path(), &["tag", "v1.2.3"])?;
+    fs::write(temp.path().join("README.md"), "# demo\n")?;
+    git(temp.path(), &["add", "README.md"])?;
+    git(temp.path(), &["commit", "-m", "docs: add readme"])?;
+    Ok(temp)
+}
+
+/// `describe` prints a development version from the distance to the latest tag, and leaves the
+/// tree as it was.
+#[test]
+fn test_describe_prints_dev_version() -> eyre::Result<()> {
+    let temp = repo_past_tag()?;
+    let sha = git(temp.path(), &["rev-parse", "--short=7", "HEAD"])?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("describe");
+    cmd.assert()
+        .success()
+        .stdout(format!("1.2.3.dev1+g{sha}\n"));
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args([
+        "describe",
+        "patch",
+        "--dev-version",
+        "{new_version}-dev.{distance_to_latest_tag}",
+    ]);
+    cmd.assert().success().stdout("1.2.4-dev.1\n");
+
+    assert_eq!(git(temp.path(), &["status", "--porcelain"])?, "");
+    assert_eq!(fs::read_to_string(temp.path().join("VERSION"))?, "1.2.3");
+    Ok(())
+}
+
+/// On a clean checkout of the latest tag, `describe` prints the released version.
+#[test]
+fn test_describe_on_tag_prints_release_version() -> eyre::Result<()> {
+    let temp = repo_past_tag()?;
+    git(temp.path(), &["checkout", "-q", "v1.2.3"])?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("describe");
+    cmd.assert().success().stdout("1.2.3\n");
+
+    fs::write(temp.path().join("VERSION"), "changed")?;
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("describe");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("1.2.3.dev0+g"));
+    Ok(())
+}
+
+/// Without a tag there is no distance to count from, so `describe` fails instead of guessing.
+#[test]
+fn test_describe_requires_a_tag() -> eyre::Result<()> {
+    let temp = armed_repo()?;
+    git_commit_all(temp.path())?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).arg("describe");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "cannot describe without a tagged version",
+    ));
+    Ok(())
+}
+
+/// A config with one global setting and one file section, for `explain`.
+fn explain_repo() -> eyre::Result<tempfile::TempDir> {
+    repo_with(
+        ".bumpversion.toml",
+        indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            commit = true
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+            search = "v{current_version}"
+        "#},
+    )
+}
+
+/// A setting from the config file is explained with the line that sets it.
+#[test]
+fn test_explain_points_at_the_config_file() -> eyre::Result<()> {
+    let temp = explain_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--color", "never", "explain", "commit"]);
+    cmd.assert()
+        .success()
+        .stdout("commit = true  # config file\n")
+        .stderr(predicate::str::contains(".bumpversion.toml:3:1"))
+        .stderr(predicate::str::contains("sets `commit`"));
+    Ok(())
+}
+
+/// An override names the flag or environment variable that won over the config file.
+#[test]
+fn test_explain_names_the_flag_or_variable() -> eyre::Result<()> {
+    let temp = explain_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["explain", "commit", "--no-commit"]);
+    cmd.assert()
+        .success()
+        .stdout("commit = false  # command line flag `--no-commit`\n");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .env("BUMPVERSION_TAG", "true")
+        .args(["explain", "tag"]);
+    cmd.assert()
+        .success()
+        .stdout("tag = true  # environment variable `BUMPVERSION_TAG`\n");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args(["explain", "push"]);
+    cmd.assert().success().stdout("push = false  # default\n");
+    Ok(())
+}
+
+/// The settings of a file tell its own values from the ones it falls back to.
+#[test]
+fn test_explain_file_settings() -> eyre::Result<()> {
+    let temp = explain_repo()?;
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path())
+        .args(["--output", "json", "explain", "--file", "VERSION"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let settings: serde_json::Value = serde_json::from_slice(&output)?;
+    let origin = |key: &str| {
+        settings
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|setting| setting["key"] == key)
+            .map(|setting| setting["origin"]["source"].clone())
+    };
+    assert_eq!(origin("search"), Some("file_section".into()));
+    assert_eq!(origin("replace"), Some("default".into()));
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bumpversion"));
+    cmd.current_dir(temp.path()).args(["explain", "comit"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown setting \"comit\""));
+    Ok(())
+}
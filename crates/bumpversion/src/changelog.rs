@@ -0,0 +1,201 @@
+//! Changelog generation from the commits of a release.
+//!
+//! Groups the Conventional Commits since the latest tag by type and renders them as a
+//! new section at the top of the changelog.
+use crate::{
+    conventional_commits::{BREAKING, ConventionalCommit},
+    f_string::{FormatError, PythonFormatString},
+};
+use std::collections::HashMap;
+
+/// The type of the first of `sections` a commit belongs to.
+///
+/// A breaking change belongs to the [`BREAKING`] section if there is one.
+fn section_of<'s>(
+    commit: &ConventionalCommit<'_>,
+    sections: &'s [(String, String)],
+) -> Option<&'s str> {
+    let find = |key: &str| {
+        sections
+            .iter()
+            .find(|(commit_type, _)| commit_type.eq_ignore_ascii_case(key))
+            .map(|(commit_type, _)| commit_type.as_str())
+    };
+    commit
+        .breaking
+        .then(|| find(BREAKING))
+        .flatten()
+        .or_else(|| find(commit.commit_type))
+}
+
+/// Render the changelog section for a release from the commit `messages`.
+///
+/// The section starts with `header`, rendered with `ctx`. Below it, commits are grouped under a
+/// sub-heading per entry of `sections`, in that order, and each is rendered with `entry` and its
+/// `type`, `scope` and `description` in addition to `ctx`. Commits that are not Conventional
+/// Commits, or whose type has no section, are left out.
+///
+/// # Errors
+///
+/// Returns [`FormatError`] if a template references an unknown placeholder.
+pub fn render_section<S>(
+    messages: impl IntoIterator<Item = impl AsRef<str>>,
+    sections: &[(String, String)],
+    header: &PythonFormatString,
+    entry: &PythonFormatString,
+    ctx: &HashMap<String, String, S>,
+) -> Result<String, FormatError>
+where
+    S: std::hash::BuildHasher,
+{
+    let messages: Vec<_> = messages.into_iter().collect();
+    let commits: Vec<_> = messages
+        .iter()
+        .filter_map(|message| ConventionalCommit::parse(message.as_ref()))
+        .collect();
+
+    let mut entry_ctx: HashMap<&str, &str> = ctx
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let mut section = header.format(&entry_ctx, true)?;
+    section.push('\n');
+    let level = section.chars().take_while(|c| *c == '#').count().max(2);
+    let sub_heading = "#".repeat(level + 1);
+
+    for (commit_type, title) in sections {
+        let mut entries = vec![];
+        for commit in &commits {
+            if section_of(commit, sections) != Some(commit_type.as_str()) {
+                continue;
+            }
+            entry_ctx.insert("type", commit.commit_type);
+            entry_ctx.insert("scope", commit.scope.unwrap_or_default());
+            entry_ctx.insert("description", commit.description);
+            entries.push(entry.format(&entry_ctx, true)?);
+        }
+        if entries.is_empty() {
+            continue;
+        }
+        section.push('\n');
+        section.push_str(&sub_heading);
+        section.push(' ');
+        section.push_str(title);
+        section.push_str("\n\n");
+        for entry in entries {
+            section.push_str(&entry);
+            section.push('\n');
+        }
+    }
+    Ok(section)
+}
+
+/// Insert a rendered `section` into the `changelog`.
+///
+/// The section goes before the first existing heading of the same level, so a title and
+/// introduction at the top of the file stay in place. Without one, it is appended.
+#[must_use]
+pub fn insert_section(changelog: &str, section: &str) -> String {
+    let level = section.chars().take_while(|c| *c == '#').count();
+    let heading = format!("{} ", "#".repeat(level));
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if level > 0 && line.starts_with(&heading) {
+            let (before, after) = changelog.split_at(offset);
+            return format!("{before}{section}\n{after}");
+        }
+        offset += line.len();
+    }
+    if changelog.trim().is_empty() {
+        section.to_string()
+    } else {
+        format!("{}\n\n{section}", changelog.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::GlobalConfigFinalized;
+    use color_eyre::eyre;
+    use indoc::indoc;
+    use similar_asserts::assert_eq as sim_assert_eq;
+    use std::collections::HashMap;
+
+    /// Commits are grouped by section in config order, breaking changes first, and commits
+    /// without a section are dropped.
+    #[test]
+    fn test_render_section() -> eyre::Result<()> {
+        crate::tests::init();
+        let config = GlobalConfigFinalized::default();
+        let ctx: HashMap<String, String> = [("new_version", "1.3.0"), ("date", "2026-10-16")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        let section = super::render_section(
+            [
+                "fix: off by one",
+                "docs: explain auto",
+                "feat(cli): add auto",
+                "feat!: drop the old flag",
+                "Merge branch 'main'",
+            ],
+            &config.changelog_sections,
+            &config.changelog_header,
+            &config.changelog_entry,
+            &ctx,
+        )?;
+        sim_assert_eq!(
+            section,
+            indoc! {"
+                ## 1.3.0 (2026-10-16)
+
+                ### Breaking changes
+
+                - drop the old flag
+
+                ### Features
+
+                - add auto
+
+                ### Bug fixes
+
+                - off by one
+            "}
+        );
+        Ok(())
+    }
+
+    /// A new section goes above the previous release, below the title.
+    #[test]
+    fn test_insert_section() {
+        crate::tests::init();
+        let changelog = indoc! {"
+            # Changelog
+
+            ## 1.2.3
+
+            - initial release
+        "};
+        sim_assert_eq!(
+            super::insert_section(changelog, "## 1.3.0\n\n- add auto\n"),
+            indoc! {"
+                # Changelog
+
+                ## 1.3.0
+
+                - add auto
+
+                ## 1.2.3
+
+                - initial release
+            "}
+        );
+        sim_assert_eq!(
+            super::insert_section("# Changelog\n", "## 1.3.0\n"),
+            "# Changelog\n\n## 1.3.0\n"
+        );
+        sim_assert_eq!(super::insert_section("", "## 1.3.0\n"), "## 1.3.0\n");
+    }
+}
@@ -0,0 +1,511 @@
+//! Consistency check of the configured files against the current version.
+//!
+//! Finds configured files that do not contain `current_version`, and a `current_version` that
+//! disagrees with the latest tag, without modifying anything.
+//!
+//! The same problems, along with templates that cannot be rendered and a tag that already
+//! exists, are collected by the preflight of a bump before any file is written.
+use crate::{
+    config::{FileChange, TagConflict},
+    diagnostics::{FileId, Location, Printer, Span},
+    files::{self, ReplaceVersionError},
+    version::Version,
+};
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Stands in for the version when rendering the search pattern of a change, so it can be
+/// replaced with the parse pattern.
+const VERSION_PLACEHOLDER: &str = "BUMPVERSIONCHECKVERSIONPLACEHOLDER";
+
+/// Matches the opening of a named capture group.
+static NAMED_GROUP_REGEX: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+    #[expect(
+        clippy::expect_used,
+        reason = "static regex is a compile-time literal and known to be valid"
+    )]
+    let regex = regex::Regex::new(r"\(\?P?<[A-Za-z_][A-Za-z0-9_]*>")
+        .expect("static named group regex must be valid");
+    regex
+});
+
+/// A version found in a file where the current version was expected.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Occurrence {
+    /// The 1-based line of the version.
+    pub line: usize,
+    /// Byte range of the version in the file.
+    pub span: Span,
+    /// The version found instead.
+    pub version: String,
+}
+
+/// A disagreement with the current version.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// A configured file does not exist.
+    MissingFile {
+        /// Path of the missing file.
+        path: PathBuf,
+        /// Where the file entry was defined in the config file.
+        #[serde(skip)]
+        location: Location,
+    },
+    /// The search pattern of a configured file did not match.
+    VersionNotFound {
+        /// Path of the file that was searched.
+        path: PathBuf,
+        /// The search regex that did not match.
+        search: String,
+        /// Where the search pattern would have matched with another version.
+        occurrences: Vec<Occurrence>,
+        /// Contents of the file.
+        #[serde(skip)]
+        contents: String,
+        /// Where the file entry was defined in the config file.
+        #[serde(skip)]
+        location: Location,
+    },
+    /// The current version is not the version of the latest tag.
+    TagMismatch {
+        /// The configured current version.
+        current_version: String,
+        /// The latest tag.
+        tag: String,
+        /// The version of the latest tag.
+        tagged_version: String,
+    },
+    /// A template cannot be rendered, e.g. because it uses an unknown argument.
+    InvalidTemplate {
+        /// The setting holding the template, such as `tag_name` or `search`.
+        setting: String,
+        /// Path of the file whose `search` or `replace` is invalid, if any.
+        path: Option<PathBuf>,
+        /// Why the template cannot be rendered.
+        message: String,
+        /// Where the file entry was defined in the config file.
+        #[serde(skip)]
+        location: Location,
+    },
+    /// The tag of the new version already exists.
+    TagExists {
+        /// The existing tag.
+        tag: String,
+        /// What the bump does about it, from `tag_conflict`.
+        conflict: TagConflict,
+    },
+}
+
+impl Problem {
+    /// Whether this problem fails a bump, rather than only being worth a warning.
+    ///
+    /// An existing tag is only worth a warning here: with `tag_conflict = "error"`, the bump
+    /// fails when planning the tag.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::TagExists { .. })
+    }
+
+    /// A diagnostic pointing at the offending lines.
+    ///
+    /// The contents of a file with stale versions are added to the sources of `printer`.
+    #[must_use]
+    pub fn to_diagnostic<W>(&self, printer: &Printer<W>) -> Diagnostic<FileId> {
+        let entry_label = |location: &Location| {
+            location.get().map(|(file_id, span)| {
+                Label::secondary(file_id, span).with_message("configured by this entry")
+            })
+        };
+        match self {
+            Self::MissingFile { path, location } => Diagnostic::error()
+                .with_message(format!("{} does not exist", path.display()))
+                .with_labels(entry_label(location).into_iter().collect())
+                .with_notes(vec![
+                    "set `ignore_missing_file = true` to allow a missing file".to_string(),
+                ]),
+            Self::VersionNotFound {
+                path,
+                search,
+                occurrences,
+                contents,
+                location,
+            } => {
+                let mut labels = vec![];
+                if !occurrences.is_empty() {
+                    let file_id = printer.add_source_file(path, contents.clone());
+                    labels.extend(occurrences.iter().map(|occurrence| {
+                        Label::primary(file_id, occurrence.span.clone())
+                            .with_message(format!("found version {}", occurrence.version))
+                    }));
+                }
+                labels.extend(entry_label(location));
+                Diagnostic::error()
+                    .with_message(format!("did not find `{search}` in {}", path.display()))
+                    .with_labels(labels)
+                    .with_notes(vec![
+                        "set `ignore_missing_version = true` to allow files without the version"
+                            .to_string(),
+                    ])
+            }
+            Self::TagMismatch {
+                current_version,
+                tag,
+                tagged_version,
+            } => Diagnostic::error()
+                .with_message(format!(
+                    "version {current_version} does not match the latest tag {tag}"
+                ))
+                .with_notes(vec![format!(
+                    "{tag} is version {tagged_version}; tag version {current_version}, or set `current_version` to {tagged_version}"
+                )]),
+            Self::InvalidTemplate {
+                setting,
+                path,
+                message,
+                location,
+            } => Diagnostic::error()
+                .with_message(match path {
+                    Some(path) => format!("cannot render `{setting}` of {}", path.display()),
+                    None => format!("cannot render `{setting}`"),
+                })
+                .with_labels(entry_label(location).into_iter().collect())
+                .with_notes(vec![message.clone()]),
+            Self::TagExists { tag, conflict } => {
+                let (diagnostic, note) = match conflict {
+                    TagConflict::Warn => (
+                        Diagnostic::warning(),
+                        "the existing tag is left alone, and no tag is created for this bump",
+                    ),
+                    TagConflict::Error => (
+                        Diagnostic::error(),
+                        "set `tag_conflict = \"replace\"` to move the tag to the new commit",
+                    ),
+                    TagConflict::Replace => (
+                        Diagnostic::warning(),
+                        "the existing tag is moved to the new commit",
+                    ),
+                };
+                diagnostic
+                    .with_message(format!("tag {tag} already exists"))
+                    .with_notes(vec![note.to_string()])
+            }
+        }
+    }
+}
+
+/// The result of checking a repository against its current version.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CheckReport {
+    /// The configured current version.
+    pub current_version: String,
+    /// Every configured file that was checked.
+    pub files: Vec<PathBuf>,
+    /// Every disagreement with the current version.
+    pub problems: Vec<Problem>,
+}
+
+impl CheckReport {
+    /// Whether everything agrees with the current version.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// The number of `problems` that are errors, as in "2 problems".
+#[must_use]
+pub fn count_problems(problems: &[Problem]) -> String {
+    match problems.iter().filter(|problem| problem.is_error()).count() {
+        1 => "1 problem".to_string(),
+        count => format!("{count} problems"),
+    }
+}
+
+/// Check that every change of the file at `path` finds `current_version` in `contents`.
+///
+/// A change that ignores a missing version never fails the check.
+///
+/// # Errors
+///
+/// Returns [`ReplaceVersionError`] if a search pattern cannot be rendered or a data file cannot
+/// be parsed.
+pub fn check_file<S>(
+    path: &Path,
+    contents: &str,
+    changes: &[FileChange],
+    current_version: &Version,
+    ctx: &HashMap<String, String, S>,
+) -> Result<Vec<Problem>, ReplaceVersionError>
+where
+    S: std::hash::BuildHasher,
+{
+    let mut problems = vec![];
+    for change in changes {
+        // Replacing the current version with itself fails exactly where a bump would.
+        let result = files::replace_version(
+            path,
+            contents.to_string(),
+            std::slice::from_ref(change),
+            current_version,
+            current_version,
+            ctx,
+        );
+        match result {
+            Ok(_) => {}
+            Err(ReplaceVersionError::VersionNotFound {
+                path,
+                search,
+                location,
+            }) => problems.push(Problem::VersionNotFound {
+                path,
+                search,
+                occurrences: occurrences(contents, change, ctx),
+                contents: contents.to_string(),
+                location,
+            }),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(problems)
+}
+
+/// Check that the changes of the file at `path` can bump `current_version` to `new_version`
+/// in `contents`.
+///
+/// The changes are applied in order, as a bump would, and every search pattern that does not
+/// match and every `search` or `replace` template that cannot be rendered is reported, rather
+/// than only the first.
+///
+/// # Errors
+///
+/// Returns [`ReplaceVersionError`] if a version cannot be serialized or a data file cannot be
+/// parsed.
+pub fn preflight_file<S>(
+    path: &Path,
+    contents: &str,
+    changes: &[FileChange],
+    current_version: &Version,
+    new_version: &Version,
+    ctx: &HashMap<String, String, S>,
+) -> Result<Vec<Problem>, ReplaceVersionError>
+where
+    S: std::hash::BuildHasher,
+{
+    let mut problems = vec![];
+    let mut contents = contents.to_string();
+    for change in changes {
+        let result = files::replace_version(
+            path,
+            contents.clone(),
+            std::slice::from_ref(change),
+            current_version,
+            new_version,
+            ctx,
+        );
+        let invalid_template = |setting: &str, message: String| Problem::InvalidTemplate {
+            setting: setting.to_string(),
+            path: Some(path.to_path_buf()),
+            message,
+            location: change.location.clone(),
+        };
+        match result {
+            Ok(modification) => contents = modification.after,
+            Err(ReplaceVersionError::VersionNotFound {
+                path,
+                search,
+                location,
+            }) => problems.push(Problem::VersionNotFound {
+                path,
+                search,
+                occurrences: occurrences(&contents, change, ctx),
+                contents: contents.clone(),
+                location,
+            }),
+            Err(ReplaceVersionError::RegexTemplate(err)) => {
+                problems.push(invalid_template("search", err.to_string()));
+            }
+            Err(ReplaceVersionError::Format(err)) => {
+                problems.push(invalid_template("replace", err.to_string()));
+            }
+            Err(ReplaceVersionError::InvalidFormatString(err)) => {
+                problems.push(invalid_template("replace", err.to_string()));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(problems)
+}
+
+/// Find where the search pattern of `change` matches with any version in place of the current
+/// one.
+fn occurrences<S>(
+    contents: &str,
+    change: &FileChange,
+    ctx: &HashMap<String, String, S>,
+) -> Vec<Occurrence>
+where
+    S: std::hash::BuildHasher,
+{
+    let ctx: HashMap<&str, &str> = ctx
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .chain([
+            ("current_version", VERSION_PLACEHOLDER),
+            ("new_version", VERSION_PLACEHOLDER),
+        ])
+        .collect();
+    let Ok(search) = change.search.format(&ctx, true) else {
+        return vec![];
+    };
+    let version_pattern =
+        NAMED_GROUP_REGEX.replace_all(change.parse_version_pattern.as_str(), "(?:");
+    let pattern = search
+        .as_str()
+        .replace(VERSION_PLACEHOLDER, &format!("({version_pattern})"));
+    let Ok(regex) = regex::RegexBuilder::new(&pattern).multi_line(true).build() else {
+        return vec![];
+    };
+    regex
+        .captures_iter(contents)
+        .filter_map(|captures| {
+            let version = captures.get(1)?;
+            let line = contents.get(..version.start())?.matches('\n').count() + 1;
+            Some(Occurrence {
+                line,
+                span: version.range(),
+                version: version.as_str().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Occurrence, Problem, check_file, preflight_file};
+    use crate::{
+        config::{
+            self, FileChange,
+            file::FinalizedFileConfig,
+            regex::RegexTemplate,
+            version::{VersionComponentConfigs, version_component_configs},
+        },
+        f_string::PythonFormatString,
+        version::{Version, VersionSpec},
+    };
+    use color_eyre::eyre;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn change(search: &str, components: &VersionComponentConfigs) -> eyre::Result<FileChange> {
+        let file_config = FinalizedFileConfig {
+            search: RegexTemplate::Escaped(PythonFormatString::parse(search)?),
+            ..FinalizedFileConfig::default()
+        };
+        Ok(FileChange::new(file_config, components))
+    }
+
+    fn version(version: &str, components: &VersionComponentConfigs) -> eyre::Result<Version> {
+        Version::parse(
+            version,
+            &config::file::PARSE_VERSION_REGEX,
+            &VersionSpec::from_components(components.clone()),
+        )
+        .ok_or_else(|| eyre::eyre!("failed to parse {version}"))
+    }
+
+    /// A file containing the current version passes the check.
+    #[test]
+    fn test_check_file_finds_the_current_version() -> eyre::Result<()> {
+        let components = version_component_configs(&config::Config::default().finalize());
+        let changes = [change("version = \"{current_version}\"", &components)?];
+        let problems = check_file(
+            Path::new("Cargo.toml"),
+            "[package]\nversion = \"1.2.3\"\n",
+            &changes,
+            &version("1.2.3", &components)?,
+            &HashMap::<String, String>::new(),
+        )?;
+        assert_eq!(problems, vec![]);
+        Ok(())
+    }
+
+    /// A stale version is reported at the line where the search pattern would have matched,
+    /// not at other versions in the file.
+    #[test]
+    fn test_check_file_points_at_the_stale_version() -> eyre::Result<()> {
+        let components = version_component_configs(&config::Config::default().finalize());
+        let changes = [change("version = \"{current_version}\"", &components)?];
+        let contents = "[package]\nversion = \"1.2.2\"\n\n[dependencies]\nserde = \"1.0.0\"\n";
+        let problems = check_file(
+            Path::new("Cargo.toml"),
+            contents,
+            &changes,
+            &version("1.2.3", &components)?,
+            &HashMap::<String, String>::new(),
+        )?;
+        let [Problem::VersionNotFound { occurrences, .. }] = problems.as_slice() else {
+            eyre::bail!("expected a single missing version, got {problems:?}");
+        };
+        assert_eq!(
+            occurrences,
+            &vec![Occurrence {
+                line: 2,
+                span: 21..26,
+                version: "1.2.2".to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    /// A change that ignores a missing version never fails the check.
+    #[test]
+    fn test_check_file_honors_ignore_missing_version() -> eyre::Result<()> {
+        let components = version_component_configs(&config::Config::default().finalize());
+        let mut change = change("{current_version}", &components)?;
+        change.ignore_missing_version = true;
+        let problems = check_file(
+            Path::new("VERSION"),
+            "1.2.2",
+            &[change],
+            &version("1.2.3", &components)?,
+            &HashMap::<String, String>::new(),
+        )?;
+        assert_eq!(problems, vec![]);
+        Ok(())
+    }
+
+    /// The preflight reports every change that would fail, not only the first.
+    #[test]
+    fn test_preflight_file_reports_every_problem() -> eyre::Result<()> {
+        let components = version_component_configs(&config::Config::default().finalize());
+        let mut invalid_replace = change("{current_version}", &components)?;
+        invalid_replace.replace = "{new_version}-{unknown}".to_string();
+        let changes = [
+            change("version: {current_version}", &components)?,
+            invalid_replace,
+        ];
+        let problems = preflight_file(
+            Path::new("VERSION"),
+            "1.2.3",
+            &changes,
+            &version("1.2.3", &components)?,
+            &version("1.2.4", &components)?,
+            &HashMap::<String, String>::new(),
+        )?;
+        let [
+            Problem::VersionNotFound { search, .. },
+            Problem::InvalidTemplate {
+                setting, message, ..
+            },
+        ] = problems.as_slice()
+        else {
+            eyre::bail!("expected a missing version and an invalid template, got {problems:?}");
+        };
+        assert_eq!(search, "version: 1\\.2\\.3");
+        assert_eq!(setting, "replace");
+        assert_eq!(message, "missing argument \"unknown\"");
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{
+    config::{Config, pyproject_toml::ParseError},
+    diagnostics::FileId,
+};
+use codespan_reporting::diagnostic::Diagnostic;
+use toml_span as toml;
+
+/// Key paths of the bumpversion tables in a `Cargo.toml`, in order of precedence.
+const CONFIG_KEY_PATHS: [&[&str]; 2] = [
+    &["package", "metadata", "bumpversion"],
+    &["workspace", "metadata", "bumpversion"],
+];
+
+/// Key paths of the package version in a `Cargo.toml`, in order of precedence.
+const PACKAGE_VERSION_KEY_PATHS: [&[&str]; 2] = [
+    &["package", "version"],
+    &["workspace", "package", "version"],
+];
+
+/// Get the value at `key_path` in a nested TOML table.
+fn get<'a, 'de>(value: &'a toml::Value<'de>, key_path: &[&str]) -> Option<&'a toml::Value<'de>> {
+    key_path
+        .iter()
+        .try_fold(value, |value, key| value.as_table()?.get(*key))
+}
+
+/// Key paths that may hold the current version in the `Cargo.toml` `config`, in order of
+/// precedence, or that of the monorepo package `package`.
+///
+/// Only the bumpversion table the configuration is read from is considered, so the version is
+/// written back where it was read, even if the other table has a `current_version` too. Without
+/// an explicit `current_version`, it is the package version.
+pub(crate) fn current_version_key_paths<'a>(
+    config: &toml::Value,
+    package: Option<&'a str>,
+) -> Vec<Vec<&'a str>> {
+    let Some(table_key_path) = CONFIG_KEY_PATHS
+        .iter()
+        .find(|key_path| get(config, key_path).is_some())
+    else {
+        return vec![];
+    };
+    match package {
+        Some(name) => vec![[*table_key_path, &["packages", name, "current_version"]].concat()],
+        None => std::iter::once([*table_key_path, &["current_version"]].concat())
+            .chain(
+                PACKAGE_VERSION_KEY_PATHS
+                    .iter()
+                    .map(|key_path| key_path.to_vec()),
+            )
+            .collect(),
+    }
+}
+
+impl Config {
+    /// Parse bumpversion configuration from a `Cargo.toml` `toml_span` value tree.
+    ///
+    /// The configuration is read from `[package.metadata.bumpversion]`, or from
+    /// `[workspace.metadata.bumpversion]` in a workspace root. Without an explicit
+    /// `current_version`, it defaults to `package.version` or `workspace.package.version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if the bumpversion table contains an invalid configuration value.
+    pub fn from_cargo_value(
+        config: &toml::Value,
+        file_id: FileId,
+        _strict: bool,
+        _diagnostics: &mut [Diagnostic<FileId>],
+    ) -> Result<Option<Self>, ParseError> {
+        let Some(table) = CONFIG_KEY_PATHS
+            .iter()
+            .find_map(|key_path| get(config, key_path))
+        else {
+            return Ok(None);
+        };
+
+        let mut parsed = Self::from_bumpversion_table(table, file_id)?;
+        if parsed.global.current_version.is_none() {
+            // a version inherited via `version.workspace = true` is a table and skipped
+            parsed.global.current_version = current_version_key_paths(config, None)
+                .iter()
+                .find_map(|key_path| get(config, key_path).and_then(toml::Value::as_str))
+                .map(ToString::to_string);
+        }
+        Ok(Some(parsed))
+    }
+
+    /// Parse bumpversion configuration from a `Cargo.toml` string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if the TOML document or bumpversion configuration is invalid.
+    pub fn from_cargo_toml(
+        config: &str,
+        file_id: FileId,
+        strict: bool,
+        diagnostics: &mut [Diagnostic<FileId>],
+    ) -> Result<Option<Self>, ParseError> {
+        let config = toml_span::parse(config).map_err(|source| ParseError::Toml { source })?;
+        Self::from_cargo_value(&config, file_id, strict, diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{Config, toml::replace_version},
+        diagnostics::BufferedPrinter,
+    };
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+    use std::collections::HashMap;
+
+    fn key_paths(config: &str) -> eyre::Result<Vec<Vec<&'static str>>> {
+        let config = toml_span::parse(config)?;
+        Ok(super::current_version_key_paths(&config, None))
+    }
+
+    fn parse_cargo_toml(config: &str) -> eyre::Result<Option<Config>> {
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("Cargo.toml".to_string(), config.to_string());
+        let mut diagnostics = vec![];
+        let config = Config::from_cargo_toml(config, file_id, true, &mut diagnostics)?;
+        Ok(config)
+    }
+
+    #[test]
+    fn parse_cargo_toml_package_metadata() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let cargo_toml = indoc::indoc! {r#"
+            [package]
+            name = "demo"
+            version = "0.1.0"
+
+            [package.metadata.bumpversion]
+            commit = true
+            tag_name = "demo-v{new_version}"
+
+            [[package.metadata.bumpversion.files]]
+            filename = "README.md"
+
+            [package.metadata.bumpversion.parts.patch]
+            first_value = "1"
+        "#};
+
+        let config = parse_cargo_toml(cargo_toml)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        sim_assert_eq!(config.global.current_version.as_deref(), Some("0.1.0"));
+        sim_assert_eq!(config.global.commit, Some(true));
+        sim_assert_eq!(config.files.len(), 1);
+        sim_assert_eq!(
+            config
+                .components
+                .get("patch")
+                .and_then(|part| part.first_value.as_deref()),
+            Some("1")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cargo_toml_workspace_metadata() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let cargo_toml = indoc::indoc! {r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.package]
+            version = "1.2.3"
+
+            [workspace.metadata.bumpversion]
+        "#};
+
+        let config = parse_cargo_toml(cargo_toml)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        sim_assert_eq!(config.global.current_version.as_deref(), Some("1.2.3"));
+
+        let cargo_toml = indoc::indoc! {r#"
+            [workspace.package]
+            version = "1.2.3"
+
+            [workspace.metadata.bumpversion]
+            current_version = "1.2.4"
+        "#};
+
+        let config = parse_cargo_toml(cargo_toml)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        sim_assert_eq!(config.global.current_version.as_deref(), Some("1.2.4"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cargo_toml_without_config() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let cargo_toml = indoc::indoc! {r#"
+            [package]
+            name = "demo"
+            version = "0.1.0"
+        "#};
+
+        assert!(parse_cargo_toml(cargo_toml)?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replace_package_version() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let cargo_toml = indoc::indoc! {r#"
+            [package]
+            name = "demo"
+            version = "0.1.0" # keep this comment
+
+            [package.metadata.bumpversion]
+            commit = true
+        "#};
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("Cargo.toml");
+        tokio::fs::write(&path, cargo_toml).await?;
+
+        let config = parse_cargo_toml(cargo_toml)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .finalize();
+        let ctx: HashMap<&str, &str> = [("current_version", "0.1.0"), ("new_version", "0.2.0")]
+            .into_iter()
+            .collect();
+        replace_version(&path, &key_paths(cargo_toml)?, &config, &ctx, false).await?;
+
+        sim_assert_eq!(
+            tokio::fs::read_to_string(&path).await?,
+            cargo_toml.replace(r#""0.1.0""#, r#""0.2.0""#)
+        );
+        Ok(())
+    }
+
+    /// The version is written back to the package version it was read from, not to the
+    /// `current_version` of the workspace table that is not used.
+    #[tokio::test]
+    async fn test_replace_version_where_it_was_read() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let cargo_toml = indoc::indoc! {r#"
+            [package]
+            name = "demo"
+            version = "0.1.0"
+
+            [package.metadata.bumpversion]
+            commit = true
+
+            [workspace.metadata.bumpversion]
+            current_version = "0.1.0"
+        "#};
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("Cargo.toml");
+        tokio::fs::write(&path, cargo_toml).await?;
+
+        let config = parse_cargo_toml(cargo_toml)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .finalize();
+        sim_assert_eq!(config.global.current_version.as_deref(), Some("0.1.0"));
+        let ctx: HashMap<&str, &str> = [("current_version", "0.1.0"), ("new_version", "0.2.0")]
+            .into_iter()
+            .collect();
+        replace_version(&path, &key_paths(cargo_toml)?, &config, &ctx, false).await?;
+
+        sim_assert_eq!(
+            tokio::fs::read_to_string(&path).await?,
+            cargo_toml.replacen(r#""0.1.0""#, r#""0.2.0""#, 1)
+        );
+        Ok(())
+    }
+}
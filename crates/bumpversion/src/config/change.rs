@@ -1,5 +1,5 @@
 use super::{file, regex::RegexTemplate};
-use crate::f_string::PythonFormatString;
+use crate::{diagnostics::Location, f_string::PythonFormatString};
 
 /// A change to make to a file
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -16,12 +16,19 @@ pub struct FileChange {
     pub ignore_missing_version: bool,
     /// Whether to ignore missing files.
     pub ignore_missing_file: bool,
-    // If specified, and has an appropriate extension, will be treated as a data file
-    // pub key_path: Option<String>,
+    /// If specified, and the file has a data file extension, only the value at this dotted
+    /// key path is updated.
+    pub key_path: Option<String>,
+    /// If the file is the manifest of a Cargo workspace, the names of its crates.
+    ///
+    /// The names are filled in when the workspace is resolved.
+    pub cargo_workspace: Option<Vec<String>>,
     /// Optional allow-list of version components this change will bump.
     pub include_bumps: Option<Vec<String>>,
     /// Optional deny-list of version components this change will bump.
     pub exclude_bumps: Option<Vec<String>>,
+    /// Where the file entry was defined in the config file.
+    pub location: Location,
 }
 
 impl FileChange {
@@ -43,9 +50,15 @@ impl FileChange {
             // .unwrap_or(defaults::IGNORE_MISSING_VERSION),
             ignore_missing_file: file_config.ignore_missing_file,
             // .unwrap_or(defaults::IGNORE_MISSING_FILES),
-            include_bumps: Some(components.keys().cloned().collect()),
-            // key_path: None,
-            exclude_bumps: None,
+            include_bumps: Some(
+                file_config
+                    .include_bumps
+                    .unwrap_or_else(|| components.keys().cloned().collect()),
+            ),
+            key_path: file_config.key_path,
+            cargo_workspace: file_config.cargo_workspace.then(Vec::new),
+            exclude_bumps: file_config.exclude_bumps,
+            location: file_config.location,
         }
     }
 
@@ -7,17 +7,17 @@
 //
 // pub static SERIALIZE_VERSION_PATTERNS: Lazy<Vec<PythonFormatString>> = Lazy::new(|| {
 //     vec![PythonFormatString(vec![
-//         Value::Argument("major".to_string()),
+//         Value::argument("major"),
 //         Value::String(".".to_string()),
-//         Value::Argument("minor".to_string()),
+//         Value::argument("minor"),
 //         Value::String(".".to_string()),
-//         Value::Argument("patch".to_string()),
+//         Value::argument("patch"),
 //     ])]
 // });
 //
 // pub const SEARCH: Lazy<super::regex::RegexTemplate> = Lazy::new(|| {
 //     super::regex::RegexTemplate::Escaped(
-//         [Value::Argument("current_version".to_string())]
+//         [Value::argument("current_version")]
 //             .into_iter()
 //             .collect(),
 //     )
@@ -27,7 +27,7 @@
 // pub static TAG_NAME: Lazy<PythonFormatString> = Lazy::new(|| {
 //     [
 //         Value::String(String::from("v")),
-//         Value::Argument("new_version".to_string()),
+//         Value::argument("new_version"),
 //     ]
 //     .into_iter()
 //     .collect()
@@ -36,9 +36,9 @@
 // pub static TAG_MESSAGE: Lazy<PythonFormatString> = Lazy::new(|| {
 //     PythonFormatString(vec![
 //         Value::String("Bump version: ".to_string()),
-//         Value::Argument("current_version".to_string()),
+//         Value::argument("current_version"),
 //         Value::String(" → ".to_string()),
-//         Value::Argument("new_version".to_string()),
+//         Value::argument("new_version"),
 //     ])
 // });
 
@@ -52,8 +52,8 @@
 // pub static COMMIT_MESSAGE: Lazy<PythonFormatString> = Lazy::new(|| {
 //     PythonFormatString(vec![
 //         Value::String("Bump version: ".to_string()),
-//         Value::Argument("current_version".to_string()),
+//         Value::argument("current_version"),
 //         Value::String(" → ".to_string()),
-//         Value::Argument("new_version".to_string()),
+//         Value::argument("new_version"),
 //     ])
 // });
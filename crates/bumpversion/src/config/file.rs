@@ -1,8 +1,9 @@
 use super::{
     global,
+    provenance::{self, Provenance, Setting},
     regex::{Regex, RegexTemplate},
 };
-use crate::f_string::PythonFormatString;
+use crate::{diagnostics::Location, f_string::PythonFormatString};
 
 /// Per-file configuration options as parsed from configuration sources.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -19,6 +20,18 @@ pub struct FileConfig {
     pub ignore_missing_file: Option<bool>,
     /// Ignore any missing version when searching and replacing version
     pub ignore_missing_version: Option<bool>,
+    /// Dotted path of the value to update in a TOML, JSON, YAML or INI data file
+    pub key_path: Option<String>,
+    /// Treat the file as the manifest of a Cargo workspace and update all of its crates
+    pub cargo_workspace: Option<bool>,
+    /// Version components whose bumps update this file, or all if unset
+    pub include_bumps: Option<Vec<String>>,
+    /// Version components whose bumps leave this file unchanged
+    pub exclude_bumps: Option<Vec<String>>,
+    /// Where the file entry was defined in the config file
+    pub location: Location,
+    /// Where each value came from
+    pub provenance: Provenance,
 }
 
 /// Per-file configuration options with defaults applied.
@@ -36,6 +49,18 @@ pub struct FinalizedFileConfig {
     pub ignore_missing_file: bool,
     /// Ignore any missing version when searching and replacing version
     pub ignore_missing_version: bool,
+    /// Dotted path of the value to update in a TOML, JSON, YAML or INI data file
+    pub key_path: Option<String>,
+    /// Treat the file as the manifest of a Cargo workspace and update all of its crates
+    pub cargo_workspace: bool,
+    /// Version components whose bumps update this file, or all if unset
+    pub include_bumps: Option<Vec<String>>,
+    /// Version components whose bumps leave this file unchanged
+    pub exclude_bumps: Option<Vec<String>>,
+    /// Where the file entry was defined in the config file
+    pub location: Location,
+    /// Where each value came from
+    pub provenance: Provenance,
 }
 
 impl FileConfig {
@@ -49,6 +74,12 @@ impl FileConfig {
             replace: None,
             ignore_missing_file: None,
             ignore_missing_version: None,
+            key_path: None,
+            cargo_workspace: None,
+            include_bumps: None,
+            exclude_bumps: None,
+            location: Location::default(),
+            provenance: Provenance::default(),
         }
     }
 }
@@ -69,16 +100,14 @@ impl Default for FinalizedFileConfig {
     fn default() -> Self {
         use crate::f_string::Value;
         let search = super::regex::RegexTemplate::Escaped(
-            [Value::Argument("current_version".to_string())]
-                .into_iter()
-                .collect(),
+            [Value::argument("current_version")].into_iter().collect(),
         );
         let serialize_version_patterns = vec![PythonFormatString(vec![
-            Value::Argument("major".to_string()),
+            Value::argument("major"),
             Value::String(".".to_string()),
-            Value::Argument("minor".to_string()),
+            Value::argument("minor"),
             Value::String(".".to_string()),
-            Value::Argument("patch".to_string()),
+            Value::argument("patch"),
         ])];
 
         Self {
@@ -88,6 +117,12 @@ impl Default for FinalizedFileConfig {
             replace: "{new_version}".to_string(),
             ignore_missing_version: false,
             ignore_missing_file: false,
+            key_path: None,
+            cargo_workspace: false,
+            include_bumps: None,
+            exclude_bumps: None,
+            location: Location::default(),
+            provenance: Provenance::default(),
         }
     }
 }
@@ -102,6 +137,12 @@ impl Default for FileConfig {
             replace: Some(default.replace),
             ignore_missing_version: Some(default.ignore_missing_version),
             ignore_missing_file: Some(default.ignore_missing_file),
+            key_path: default.key_path,
+            cargo_workspace: Some(default.cargo_workspace),
+            include_bumps: default.include_bumps,
+            exclude_bumps: default.exclude_bumps,
+            location: default.location,
+            provenance: default.provenance,
         }
     }
 }
@@ -128,10 +169,48 @@ impl FileConfig {
             ignore_missing_file: self
                 .ignore_missing_file
                 .unwrap_or(default.ignore_missing_file),
+            key_path: self.key_path,
+            cargo_workspace: self.cargo_workspace.unwrap_or(default.cargo_workspace),
+            include_bumps: self.include_bumps,
+            exclude_bumps: self.exclude_bumps,
+            location: self.location,
+            provenance: self.provenance,
         }
     }
 }
 
+impl FinalizedFileConfig {
+    /// The effective value of every setting, along with where it came from.
+    #[must_use]
+    pub fn settings(&self) -> Vec<Setting> {
+        use provenance::{optional, string, strings};
+        [
+            ("parse", string(&self.parse_version_pattern)),
+            ("serialize", strings(&self.serialize_version_patterns)),
+            ("search", string(&self.search)),
+            ("replace", string(&self.replace)),
+            ("ignore_missing_files", self.ignore_missing_file.to_string()),
+            (
+                "ignore_missing_version",
+                self.ignore_missing_version.to_string(),
+            ),
+            ("key_path", optional(self.key_path.as_ref().map(string))),
+            ("cargo_workspace", self.cargo_workspace.to_string()),
+            (
+                "include_bumps",
+                optional(self.include_bumps.as_ref().map(strings)),
+            ),
+            (
+                "exclude_bumps",
+                optional(self.exclude_bumps.as_ref().map(strings)),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| Setting::new(key, value, &self.provenance))
+        .collect()
+    }
+}
+
 impl<'a> super::MergeWith<&'a global::GlobalConfig> for FileConfig {
     fn merge_with(&mut self, other: &'a global::GlobalConfig) {
         self.parse_version_pattern
@@ -144,5 +223,7 @@ impl<'a> super::MergeWith<&'a global::GlobalConfig> for FileConfig {
             .merge_with(other.ignore_missing_files.as_ref());
         self.ignore_missing_version
             .merge_with(other.ignore_missing_version.as_ref());
+        self.provenance
+            .inherit(&other.provenance, provenance::INHERITED_FILE_KEYS);
     }
 }
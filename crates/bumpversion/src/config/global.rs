@@ -1,18 +1,65 @@
+use super::provenance::{self, Provenance, Setting};
 use super::regex::{Regex, RegexTemplate};
+use super::scheme::Scheme;
 use crate::f_string::PythonFormatString;
 use std::path::PathBuf;
 
+/// What to do when the tag of the new version already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagConflict {
+    /// Leave the existing tag alone, and bump without creating one.
+    #[default]
+    Warn,
+    /// Abort the bump before anything is written or committed.
+    Error,
+    /// Move the existing tag to the new commit.
+    Replace,
+}
+
+/// The tag conflict policy is none of `warn`, `error` or `replace`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown tag conflict policy {0:?}, expected `warn`, `error` or `replace`")]
+pub struct UnknownTagConflict(pub String);
+
+impl std::str::FromStr for TagConflict {
+    type Err = UnknownTagConflict;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "warn" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            "replace" => Ok(Self::Replace),
+            _ => Err(UnknownTagConflict(value.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for TagConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::Error => write!(f, "error"),
+            Self::Replace => write!(f, "replace"),
+        }
+    }
+}
+
 /// Global configuration options as parsed from configuration sources.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GlobalConfig {
     /// Don't abort if working directory is dirty
     pub allow_dirty: Option<bool>,
+    /// Don't abort if the new version is not newer than the current version
+    pub allow_downgrade: Option<bool>,
     /// Version that needs to be updated
     pub current_version: Option<String>,
     /// Regex parsing the version string
     pub parse_version_pattern: Option<Regex>,
     /// How to serialize back to a version
     pub serialize_version_patterns: Option<Vec<PythonFormatString>>,
+    /// Built-in scheme providing the defaults of `parse`, `serialize` and the components
+    pub scheme: Option<Scheme>,
     /// Template for complete string to search
     pub search: Option<RegexTemplate>,
     /// Template for complete string to replace
@@ -27,6 +74,8 @@ pub struct GlobalConfig {
     pub ignore_missing_version: Option<bool>,
     /// Don't write any files, just pretend
     pub dry_run: Option<bool>,
+    /// Keep the written files if a later step of the bump fails, instead of restoring them
+    pub keep_on_failure: Option<bool>,
     /// Commit to version control
     pub commit: Option<bool>,
     /// Create a tag in version control
@@ -37,10 +86,18 @@ pub struct GlobalConfig {
     pub tag_name: Option<PythonFormatString>,
     /// Tag message
     pub tag_message: Option<PythonFormatString>,
+    /// What to do when the tag of the new version already exists
+    pub tag_conflict: Option<TagConflict>,
     /// Commit message
     pub commit_message: Option<PythonFormatString>,
     /// Extra arguments to commit command
     pub commit_args: Option<String>,
+    /// Push the commit and tag to a remote
+    pub push: Option<bool>,
+    /// Remote to push to
+    pub push_remote: Option<String>,
+    /// Tags moved to each release, such as `v{new_major}`
+    pub moveable_tags: Option<Vec<PythonFormatString>>,
 
     /// Setup hooks
     pub setup_hooks: Option<Vec<String>>,
@@ -57,6 +114,23 @@ pub struct GlobalConfig {
     /// This is useful for files such as lockfiles, which should be regenerated after the version
     /// bump in a pre-commit hook.
     pub additional_files: Option<Vec<PathBuf>>,
+    /// Version component to bump for each Conventional Commit type
+    ///
+    /// The `breaking` key is used for breaking changes.
+    pub conventional_commits: Option<Vec<(String, String)>>,
+    /// Changelog to add a section for the new version to
+    pub changelog: Option<PathBuf>,
+    /// Changelog section title for each Conventional Commit type
+    pub changelog_sections: Option<Vec<(String, String)>>,
+    /// Heading of the changelog section for the new version
+    pub changelog_header: Option<PythonFormatString>,
+    /// Changelog entry for a commit
+    pub changelog_entry: Option<PythonFormatString>,
+    /// Development version printed by `describe` for a commit that is not a clean checkout of
+    /// the latest tag
+    pub dev_version: Option<PythonFormatString>,
+    /// Where each value came from
+    pub provenance: Provenance,
 }
 
 /// Global configuration with defaults applied.
@@ -64,12 +138,16 @@ pub struct GlobalConfig {
 pub struct GlobalConfigFinalized {
     /// Don't abort if working directory is dirty
     pub allow_dirty: bool,
+    /// Don't abort if the new version is not newer than the current version
+    pub allow_downgrade: bool,
     /// Version that needs to be updated
     pub current_version: Option<String>,
     /// Regex parsing the version string
     pub parse_version_pattern: Regex,
     /// How to serialize back to a version
     pub serialize_version_patterns: Vec<PythonFormatString>,
+    /// Built-in scheme providing the defaults of `parse`, `serialize` and the components
+    pub scheme: Option<Scheme>,
     /// Template for complete string to search
     pub search: RegexTemplate,
     /// Template for complete string to replace
@@ -84,6 +162,8 @@ pub struct GlobalConfigFinalized {
     pub ignore_missing_version: bool,
     /// Don't write any files, just pretend
     pub dry_run: bool,
+    /// Keep the written files if a later step of the bump fails, instead of restoring them
+    pub keep_on_failure: bool,
     /// Commit to version control
     pub commit: bool,
     /// Create a tag in version control
@@ -94,10 +174,18 @@ pub struct GlobalConfigFinalized {
     pub tag_name: PythonFormatString,
     /// Tag message
     pub tag_message: PythonFormatString,
+    /// What to do when the tag of the new version already exists
+    pub tag_conflict: TagConflict,
     /// Commit message
     pub commit_message: PythonFormatString,
     /// Extra arguments to commit command
     pub commit_args: Option<String>,
+    /// Push the commit and tag to a remote
+    pub push: bool,
+    /// Remote to push to
+    pub push_remote: String,
+    /// Tags moved to each release, such as `v{new_major}`
+    pub moveable_tags: Vec<PythonFormatString>,
 
     // extra stuff
     /// Setup hooks
@@ -115,6 +203,23 @@ pub struct GlobalConfigFinalized {
     /// This is useful for files such as lockfiles, which should be regenerated after the version
     /// bump in a pre-commit hook.
     pub additional_files: Option<Vec<PathBuf>>,
+    /// Version component to bump for each Conventional Commit type
+    ///
+    /// The `breaking` key is used for breaking changes.
+    pub conventional_commits: Vec<(String, String)>,
+    /// Changelog to add a section for the new version to
+    pub changelog: Option<PathBuf>,
+    /// Changelog section title for each Conventional Commit type
+    pub changelog_sections: Vec<(String, String)>,
+    /// Heading of the changelog section for the new version
+    pub changelog_header: PythonFormatString,
+    /// Changelog entry for a commit
+    pub changelog_entry: PythonFormatString,
+    /// Development version printed by `describe` for a commit that is not a clean checkout of
+    /// the latest tag
+    pub dev_version: PythonFormatString,
+    /// Where each value came from
+    pub provenance: Provenance,
 }
 
 impl GlobalConfig {
@@ -123,79 +228,142 @@ impl GlobalConfig {
     pub fn empty() -> Self {
         Self {
             allow_dirty: None,
+            allow_downgrade: None,
             current_version: None,
             parse_version_pattern: None,
             serialize_version_patterns: None,
+            scheme: None,
             search: None,
             replace: None,
             no_configured_files: None,
             ignore_missing_files: None,
             ignore_missing_version: None,
             dry_run: None,
+            keep_on_failure: None,
             commit: None,
             tag: None,
             sign_tags: None,
             tag_name: None,
             tag_message: None,
+            tag_conflict: None,
             commit_message: None,
             commit_args: None,
+            push: None,
+            push_remote: None,
+            moveable_tags: None,
             setup_hooks: None,
             pre_commit_hooks: None,
             post_commit_hooks: None,
             included_paths: None,
             excluded_paths: None,
             additional_files: None,
+            conventional_commits: None,
+            changelog: None,
+            changelog_sections: None,
+            changelog_header: None,
+            changelog_entry: None,
+            dev_version: None,
+            provenance: Provenance::default(),
         }
     }
 }
 
 impl Default for GlobalConfigFinalized {
     fn default() -> Self {
-        use crate::f_string::Value;
+        use crate::f_string::{Argument, Value};
         let file_config = super::file::FinalizedFileConfig::default();
         let tag_name = [
             Value::String(String::from("v")),
-            Value::Argument("new_version".to_string()),
+            Value::argument("new_version"),
         ]
         .into_iter()
         .collect();
 
         let tag_message = PythonFormatString(vec![
             Value::String("Bump version: ".to_string()),
-            Value::Argument("current_version".to_string()),
+            Value::argument("current_version"),
             Value::String(" → ".to_string()),
-            Value::Argument("new_version".to_string()),
+            Value::argument("new_version"),
+        ]);
+        let changelog_header = PythonFormatString(vec![
+            Value::String("## ".to_string()),
+            Value::argument("new_version"),
+            Value::String(" (".to_string()),
+            Value::argument("date"),
+            Value::String(")".to_string()),
+        ]);
+        let changelog_entry = PythonFormatString(vec![
+            Value::String("- ".to_string()),
+            Value::argument("description"),
+        ]);
+        let dev_version = PythonFormatString(vec![
+            Value::argument("current_version"),
+            Value::String(".dev".to_string()),
+            Value::argument("distance_to_latest_tag"),
+            Value::String("+g".to_string()),
+            Value::Argument(Argument {
+                format_spec: Some(".7".to_string()),
+                ..Argument::new("commit_sha")
+            }),
         ]);
         let commit_message = PythonFormatString(vec![
             Value::String("Bump version: ".to_string()),
-            Value::Argument("current_version".to_string()),
+            Value::argument("current_version"),
             Value::String(" → ".to_string()),
-            Value::Argument("new_version".to_string()),
+            Value::argument("new_version"),
         ]);
         Self {
             allow_dirty: false,
+            allow_downgrade: false,
             current_version: None,
             parse_version_pattern: file_config.parse_version_pattern,
             serialize_version_patterns: file_config.serialize_version_patterns,
+            scheme: None,
             search: file_config.search,
             replace: file_config.replace,
             no_configured_files: false,
             ignore_missing_version: file_config.ignore_missing_version,
             ignore_missing_files: file_config.ignore_missing_file,
             dry_run: false,
+            keep_on_failure: false,
             commit: false,
             tag: false,
             sign_tags: false,
             tag_name,
             tag_message,
+            tag_conflict: TagConflict::default(),
             commit_message,
             commit_args: None,
+            push: false,
+            push_remote: "origin".to_string(),
+            moveable_tags: vec![],
             setup_hooks: vec![],
             pre_commit_hooks: vec![],
             post_commit_hooks: vec![],
             included_paths: None,
             excluded_paths: None,
             additional_files: None,
+            conventional_commits: [
+                (crate::conventional_commits::BREAKING, "major"),
+                ("feat", "minor"),
+                ("fix", "patch"),
+            ]
+            .into_iter()
+            .map(|(commit_type, component)| (commit_type.to_string(), component.to_string()))
+            .collect(),
+            changelog: None,
+            changelog_sections: [
+                (crate::conventional_commits::BREAKING, "Breaking changes"),
+                ("feat", "Features"),
+                ("fix", "Bug fixes"),
+            ]
+            .into_iter()
+            .map(|(commit_type, title)| (commit_type.to_string(), title.to_string()))
+            .collect(),
+            changelog_header,
+            changelog_entry,
+            dev_version,
+            provenance: Provenance::default(),
         }
     }
 }
@@ -205,28 +373,42 @@ impl Default for GlobalConfig {
         let default = GlobalConfigFinalized::default();
         Self {
             allow_dirty: Some(default.allow_dirty),
+            allow_downgrade: Some(default.allow_downgrade),
             current_version: default.current_version,
             parse_version_pattern: Some(default.parse_version_pattern),
             serialize_version_patterns: Some(default.serialize_version_patterns),
+            scheme: default.scheme,
             search: Some(default.search),
             replace: Some(default.replace),
             no_configured_files: Some(default.no_configured_files),
             ignore_missing_files: Some(default.ignore_missing_files),
             ignore_missing_version: Some(default.ignore_missing_version),
             dry_run: Some(default.dry_run),
+            keep_on_failure: Some(default.keep_on_failure),
             commit: Some(default.commit),
             tag: Some(default.tag),
             sign_tags: Some(default.sign_tags),
             tag_name: Some(default.tag_name),
             tag_message: Some(default.tag_message),
+            tag_conflict: Some(default.tag_conflict),
             commit_message: Some(default.commit_message),
             commit_args: default.commit_args,
+            push: Some(default.push),
+            push_remote: Some(default.push_remote),
+            moveable_tags: Some(default.moveable_tags),
             setup_hooks: Some(default.setup_hooks),
             pre_commit_hooks: Some(default.pre_commit_hooks),
             post_commit_hooks: Some(default.post_commit_hooks),
             included_paths: default.included_paths,
             excluded_paths: default.excluded_paths,
             additional_files: default.additional_files,
+            conventional_commits: Some(default.conventional_commits),
+            changelog: default.changelog,
+            changelog_sections: Some(default.changelog_sections),
+            changelog_header: Some(default.changelog_header),
+            changelog_entry: Some(default.changelog_entry),
+            dev_version: Some(default.dev_version),
+            provenance: default.provenance,
         }
     }
 }
@@ -240,6 +422,7 @@ impl GlobalConfig {
         let default = GlobalConfigFinalized::default();
         GlobalConfigFinalized {
             allow_dirty: self.allow_dirty.unwrap_or(default.allow_dirty),
+            allow_downgrade: self.allow_downgrade.unwrap_or(default.allow_downgrade),
             current_version: self.current_version.or(default.current_version),
             parse_version_pattern: self
                 .parse_version_pattern
@@ -247,6 +430,7 @@ impl GlobalConfig {
             serialize_version_patterns: self
                 .serialize_version_patterns
                 .unwrap_or(default.serialize_version_patterns),
+            scheme: self.scheme.or(default.scheme),
             search: self.search.unwrap_or(default.search),
             replace: self.replace.unwrap_or(default.replace),
             no_configured_files: self
@@ -259,32 +443,122 @@ impl GlobalConfig {
                 .ignore_missing_version
                 .unwrap_or(default.ignore_missing_version),
             dry_run: self.dry_run.unwrap_or(default.dry_run),
+            keep_on_failure: self.keep_on_failure.unwrap_or(default.keep_on_failure),
             commit: self.commit.unwrap_or(default.commit),
             tag: self.tag.unwrap_or(default.tag),
             sign_tags: self.sign_tags.unwrap_or(default.sign_tags),
             tag_name: self.tag_name.unwrap_or(default.tag_name),
             tag_message: self.tag_message.unwrap_or(default.tag_message),
+            tag_conflict: self.tag_conflict.unwrap_or(default.tag_conflict),
             commit_message: self.commit_message.unwrap_or(default.commit_message),
             commit_args: self.commit_args.or(default.commit_args),
+            push: self.push.unwrap_or(default.push),
+            push_remote: self.push_remote.unwrap_or(default.push_remote),
+            moveable_tags: self.moveable_tags.unwrap_or(default.moveable_tags),
             setup_hooks: self.setup_hooks.unwrap_or(default.setup_hooks),
             pre_commit_hooks: self.pre_commit_hooks.unwrap_or(default.pre_commit_hooks),
             post_commit_hooks: self.post_commit_hooks.unwrap_or(default.post_commit_hooks),
             included_paths: self.included_paths.or(default.included_paths),
             excluded_paths: self.excluded_paths.or(default.excluded_paths),
             additional_files: self.additional_files.or(default.additional_files),
+            conventional_commits: self
+                .conventional_commits
+                .unwrap_or(default.conventional_commits),
+            changelog: self.changelog.or(default.changelog),
+            changelog_sections: self
+                .changelog_sections
+                .unwrap_or(default.changelog_sections),
+            changelog_header: self.changelog_header.unwrap_or(default.changelog_header),
+            changelog_entry: self.changelog_entry.unwrap_or(default.changelog_entry),
+            dev_version: self.dev_version.unwrap_or(default.dev_version),
+            provenance: self.provenance,
         }
     }
 }
 
+impl GlobalConfigFinalized {
+    /// The effective value of every setting, along with where it came from.
+    #[must_use]
+    pub fn settings(&self) -> Vec<Setting> {
+        use provenance::{optional, string, strings, table};
+        let paths = |paths: &Option<Vec<PathBuf>>| {
+            optional(
+                paths
+                    .as_ref()
+                    .map(|paths| strings(paths.iter().map(|path| path.display()))),
+            )
+        };
+        [
+            (
+                "current_version",
+                optional(self.current_version.as_ref().map(string)),
+            ),
+            ("parse", string(&self.parse_version_pattern)),
+            ("serialize", strings(&self.serialize_version_patterns)),
+            ("scheme", optional(self.scheme.as_ref().map(string))),
+            ("search", string(&self.search)),
+            ("replace", string(&self.replace)),
+            ("allow_dirty", self.allow_dirty.to_string()),
+            ("allow_downgrade", self.allow_downgrade.to_string()),
+            ("no_configured_files", self.no_configured_files.to_string()),
+            (
+                "ignore_missing_files",
+                self.ignore_missing_files.to_string(),
+            ),
+            (
+                "ignore_missing_version",
+                self.ignore_missing_version.to_string(),
+            ),
+            ("dry_run", self.dry_run.to_string()),
+            ("keep_on_failure", self.keep_on_failure.to_string()),
+            ("commit", self.commit.to_string()),
+            ("commit_message", string(&self.commit_message)),
+            (
+                "commit_args",
+                optional(self.commit_args.as_ref().map(string)),
+            ),
+            ("tag", self.tag.to_string()),
+            ("sign_tags", self.sign_tags.to_string()),
+            ("tag_name", string(&self.tag_name)),
+            ("tag_message", string(&self.tag_message)),
+            ("tag_conflict", string(self.tag_conflict)),
+            ("moveable_tags", strings(&self.moveable_tags)),
+            ("push", self.push.to_string()),
+            ("push_remote", string(&self.push_remote)),
+            ("setup_hooks", strings(&self.setup_hooks)),
+            ("pre_commit_hooks", strings(&self.pre_commit_hooks)),
+            ("post_commit_hooks", strings(&self.post_commit_hooks)),
+            ("included_paths", paths(&self.included_paths)),
+            ("excluded_paths", paths(&self.excluded_paths)),
+            ("additional_files", paths(&self.additional_files)),
+            ("conventional_commits", table(&self.conventional_commits)),
+            (
+                "changelog",
+                optional(self.changelog.as_ref().map(|path| string(path.display()))),
+            ),
+            ("changelog_sections", table(&self.changelog_sections)),
+            ("changelog_header", string(&self.changelog_header)),
+            ("changelog_entry", string(&self.changelog_entry)),
+            ("dev_version", string(&self.dev_version)),
+        ]
+        .into_iter()
+        .map(|(key, value)| Setting::new(key, value, &self.provenance))
+        .collect()
+    }
+}
+
 impl<'a> super::MergeWith<&'a GlobalConfig> for GlobalConfig {
     fn merge_with(&mut self, other: &'a GlobalConfig) {
         self.allow_dirty.merge_with(other.allow_dirty.as_ref());
+        self.allow_downgrade
+            .merge_with(other.allow_downgrade.as_ref());
         self.current_version
             .merge_with(other.current_version.as_ref());
         self.parse_version_pattern
             .merge_with(other.parse_version_pattern.as_ref());
         self.serialize_version_patterns
             .merge_with(other.serialize_version_patterns.as_ref());
+        self.scheme.merge_with(other.scheme.as_ref());
         self.search.merge_with(other.search.as_ref());
         self.replace.merge_with(other.replace.as_ref());
         self.no_configured_files
@@ -294,14 +568,20 @@ impl<'a> super::MergeWith<&'a GlobalConfig> for GlobalConfig {
         self.ignore_missing_version
             .merge_with(other.ignore_missing_version.as_ref());
         self.dry_run.merge_with(other.dry_run.as_ref());
+        self.keep_on_failure
+            .merge_with(other.keep_on_failure.as_ref());
         self.commit.merge_with(other.commit.as_ref());
         self.tag.merge_with(other.tag.as_ref());
         self.sign_tags.merge_with(other.sign_tags.as_ref());
         self.tag_name.merge_with(other.tag_name.as_ref());
         self.tag_message.merge_with(other.tag_message.as_ref());
+        self.tag_conflict.merge_with(other.tag_conflict.as_ref());
         self.commit_message
             .merge_with(other.commit_message.as_ref());
         self.commit_args.merge_with(other.commit_args.as_ref());
+        self.push.merge_with(other.push.as_ref());
+        self.push_remote.merge_with(other.push_remote.as_ref());
+        self.moveable_tags.merge_with(other.moveable_tags.as_ref());
         self.setup_hooks.merge_with(other.setup_hooks.as_ref());
         self.pre_commit_hooks
             .merge_with(other.pre_commit_hooks.as_ref());
@@ -313,5 +593,16 @@ impl<'a> super::MergeWith<&'a GlobalConfig> for GlobalConfig {
             .merge_with(other.excluded_paths.as_ref());
         self.additional_files
             .merge_with(other.additional_files.as_ref());
+        self.conventional_commits
+            .merge_with(other.conventional_commits.as_ref());
+        self.changelog.merge_with(other.changelog.as_ref());
+        self.changelog_sections
+            .merge_with(other.changelog_sections.as_ref());
+        self.changelog_header
+            .merge_with(other.changelog_header.as_ref());
+        self.changelog_entry
+            .merge_with(other.changelog_entry.as_ref());
+        self.dev_version.merge_with(other.dev_version.as_ref());
+        self.provenance.merge_with(&other.provenance);
     }
 }
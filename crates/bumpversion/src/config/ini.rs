@@ -1,9 +1,9 @@
 use crate::{
     config::{
-        self, FileConfig, GlobalConfig, InputFile, RegexTemplate, VersionComponentSpec,
-        pyproject_toml::ValueKind,
+        self, FileConfig, GlobalConfig, InputFile, Origin, Provenance, RegexTemplate,
+        VersionComponentSpec, provenance, pyproject_toml::ValueKind,
     },
-    diagnostics::{DiagnosticExt, FileId, Span},
+    diagnostics::{DiagnosticExt, FileId, Location, Span},
     f_string::{self, PythonFormatString},
     files::{self, IoError},
 };
@@ -48,6 +48,42 @@ pub enum ParseError {
         /// Source span of the offending value.
         span: Span,
     },
+    #[error("{source}")]
+    /// A version scheme could not be parsed.
+    InvalidScheme {
+        #[source]
+        /// Underlying scheme error.
+        source: config::scheme::Error,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A `CalVer` format could not be parsed or names an unknown token.
+    InvalidCalVerFormat {
+        #[source]
+        /// Underlying `CalVer` error.
+        source: Box<crate::version::calver::Error>,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A tag conflict policy could not be parsed.
+    InvalidTagConflict {
+        #[source]
+        /// Underlying tag conflict error.
+        source: config::global::UnknownTagConflict,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A component order could not be parsed.
+    InvalidComponentOrder {
+        #[source]
+        /// Underlying component order error.
+        source: config::version::UnknownComponentOrder,
+        /// Source span of the offending value.
+        span: Span,
+    },
     #[error("{message}")]
     /// A regex string could not be compiled.
     InvalidRegex {
@@ -76,6 +112,34 @@ mod diagnostics {
     impl ToDiagnostics for super::ParseError {
         fn to_diagnostics<F: Copy + PartialEq>(&self, file_id: F) -> Vec<Diagnostic<F>> {
             match self {
+                Self::InvalidScheme { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid version scheme".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidCalVerFormat { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid calver format".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidTagConflict { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid tag conflict policy".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidComponentOrder { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid component order".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
                 Self::InvalidRegex {
                     source,
                     message,
@@ -162,6 +226,24 @@ pub fn as_bool(value: &ini::Spanned<String>) -> Result<bool, ParseError> {
     }
 }
 
+#[inline]
+/// Parse a non-negative integer from an INI value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::UnexpectedType`] if the value is not a non-negative integer.
+pub fn as_usize(value: &ini::Spanned<String>) -> Result<usize, ParseError> {
+    value
+        .as_ref()
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::UnexpectedType {
+            message: "expected a non-negative integer".to_string(),
+            expected: vec![ValueKind::Integer],
+            span: value.span.clone(),
+        })
+}
+
 #[inline]
 /// Parse a [`PythonFormatString`] from an INI value.
 ///
@@ -196,6 +278,64 @@ pub fn as_regex(value: ini::Spanned<String>) -> Result<config::Regex, ParseError
         })
 }
 
+#[inline]
+/// Parse a [`config::Scheme`] from an INI value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidScheme`] if the value is not a known version scheme.
+pub fn as_scheme(value: ini::Spanned<String>) -> Result<config::Scheme, ParseError> {
+    let ini::Spanned { inner, span } = value;
+    inner
+        .parse()
+        .map_err(|source| ParseError::InvalidScheme { source, span })
+}
+
+#[inline]
+/// Parse a `CalVer` format from an INI value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidCalVerFormat`] if the value is not a valid `CalVer` format.
+pub fn as_calver_format(value: ini::Spanned<String>) -> Result<String, ParseError> {
+    let ini::Spanned { inner, span } = value;
+    crate::version::calver::CalVerFunction::new(&inner)
+        .bump()
+        .map_err(|source| ParseError::InvalidCalVerFormat {
+            source: Box::new(source),
+            span,
+        })?;
+    Ok(inner)
+}
+
+#[inline]
+/// Parse a [`config::version::ComponentOrder`] from an INI value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidComponentOrder`] if the value is not a known component order.
+pub fn as_component_order(
+    value: ini::Spanned<String>,
+) -> Result<config::version::ComponentOrder, ParseError> {
+    let ini::Spanned { inner, span } = value;
+    inner
+        .parse()
+        .map_err(|source| ParseError::InvalidComponentOrder { source, span })
+}
+
+#[inline]
+/// Parse a [`config::TagConflict`] from an INI value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidTagConflict`] if the value is not a known tag conflict policy.
+pub fn as_tag_conflict(value: ini::Spanned<String>) -> Result<config::TagConflict, ParseError> {
+    let ini::Spanned { inner, span } = value;
+    inner
+        .parse()
+        .map_err(|source| ParseError::InvalidTagConflict { source, span })
+}
+
 #[inline]
 /// Parse a list of strings from an INI value while preserving the input span.
 ///
@@ -304,11 +444,30 @@ pub(crate) fn parse_part_config(
     let calver_format = value
         .remove_option("calver_format")
         .and_then(as_optional)
-        .map(ini::Spanned::into_inner);
+        .map(as_calver_format)
+        .transpose()?;
     let depends_on = value
         .remove_option("depends_on")
         .and_then(as_optional)
         .map(ini::Spanned::into_inner);
+    let width = value
+        .remove_option("width")
+        .and_then(as_optional)
+        .as_ref()
+        .map(as_usize)
+        .transpose()?;
+    let pad = value
+        .remove_option("pad")
+        .and_then(as_optional)
+        .as_ref()
+        .map(as_bool)
+        .transpose()?
+        .unwrap_or_default();
+    let order = value
+        .remove_option("order")
+        .and_then(as_optional)
+        .map(as_component_order)
+        .transpose()?;
 
     Ok(VersionComponentSpec {
         independent,
@@ -318,6 +477,9 @@ pub(crate) fn parse_part_config(
         always_increment,
         calver_format,
         depends_on,
+        width,
+        pad,
+        order,
     })
 }
 
@@ -379,6 +541,28 @@ fn remove_optional_path_array(
         .map(|values| values.into_iter().map(PathBuf::from).collect()))
 }
 
+/// Parse `key = value` entries, one per line or separated by commas.
+fn as_string_pairs(value: ini::Spanned<String>) -> Result<Vec<(String, String)>, ParseError> {
+    let span = value.span.clone();
+    as_string_array(value, true)?
+        .iter()
+        .map(|entry| {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| ParseError::UnexpectedType {
+                    message: "expected entries of the form `key = value`".to_string(),
+                    expected: vec![ValueKind::Table],
+                    span: span.clone(),
+                })?;
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+#[expect(
+    clippy::too_many_lines,
+    reason = "parses one global key after another, and splitting them up would not make it clearer"
+)]
 pub(crate) fn parse_global_config(
     mut value: ini::SectionProxyMut<'_>,
 ) -> Result<(Option<bool>, GlobalConfig), ParseError> {
@@ -390,7 +574,13 @@ pub(crate) fn parse_global_config(
     let (search_is_regex_compat, search) = parse_search_pattern(&mut value, None)?;
 
     let allow_dirty = remove_optional_bool(&mut value, "allow_dirty")?;
+    let allow_downgrade = remove_optional_bool(&mut value, "allow_downgrade")?;
     let parse_version_pattern = value.remove_option("parse").map(as_regex).transpose()?;
+    let scheme = value
+        .remove_option("scheme")
+        .and_then(as_optional)
+        .map(as_scheme)
+        .transpose()?;
 
     let serialize_version_patterns = value
         .remove_option("serialize")
@@ -414,6 +604,7 @@ pub(crate) fn parse_global_config(
     let ignore_missing_files = remove_optional_bool(&mut value, "ignore_missing_files")?;
     let ignore_missing_version = remove_optional_bool(&mut value, "ignore_missing_version")?;
     let dry_run = remove_optional_bool(&mut value, "dry_run")?;
+    let keep_on_failure = remove_optional_bool(&mut value, "keep_on_failure")?;
     let commit = remove_optional_bool(&mut value, "commit")?;
     let tag = remove_optional_bool(&mut value, "tag")?;
     let sign_tags = value
@@ -433,6 +624,11 @@ pub(crate) fn parse_global_config(
         .and_then(as_optional)
         .map(as_format_string)
         .transpose()?;
+    let tag_conflict = value
+        .remove_option("tag_conflict")
+        .and_then(as_optional)
+        .map(as_tag_conflict)
+        .transpose()?;
     let commit_message = value
         .remove_option("commit_message")
         .and_then(as_optional)
@@ -443,6 +639,22 @@ pub(crate) fn parse_global_config(
         .remove_option("commit_args")
         .and_then(as_optional)
         .map(ini::Spanned::into_inner);
+    let push = remove_optional_bool(&mut value, "push")?;
+    let push_remote = value
+        .remove_option("push_remote")
+        .and_then(as_optional)
+        .map(ini::Spanned::into_inner);
+    let moveable_tags = value
+        .remove_option("moveable_tags")
+        .and_then(as_optional)
+        .map(|value| as_spanned_string_array(value, true))
+        .transpose()?
+        .map(|tags| {
+            tags.into_iter()
+                .map(as_format_string)
+                .collect::<Result<_, _>>()
+        })
+        .transpose()?;
 
     // extra stuff
     let setup_hooks = remove_optional_string_array(&mut value, "setup_hooks")?;
@@ -451,27 +663,63 @@ pub(crate) fn parse_global_config(
     let included_paths = remove_optional_path_array(&mut value, "included_paths")?;
     let excluded_paths = remove_optional_path_array(&mut value, "excluded_paths")?;
     let additional_files = remove_optional_path_array(&mut value, "additional_files")?;
+    let conventional_commits = value
+        .remove_option("conventional_commits")
+        .and_then(as_optional)
+        .map(as_string_pairs)
+        .transpose()?;
+    let changelog = value
+        .remove_option("changelog")
+        .and_then(as_optional)
+        .map(|value| PathBuf::from(value.into_inner()));
+    let changelog_sections = value
+        .remove_option("changelog_sections")
+        .and_then(as_optional)
+        .map(as_string_pairs)
+        .transpose()?;
+    let changelog_header = value
+        .remove_option("changelog_header")
+        .and_then(as_optional)
+        .map(as_format_string)
+        .transpose()?;
+    let changelog_entry = value
+        .remove_option("changelog_entry")
+        .and_then(as_optional)
+        .map(as_format_string)
+        .transpose()?;
+    let dev_version = value
+        .remove_option("dev_version")
+        .and_then(as_optional)
+        .map(as_format_string)
+        .transpose()?;
 
     Ok((
         search_is_regex_compat,
         GlobalConfig {
             allow_dirty,
+            allow_downgrade,
             current_version,
             parse_version_pattern,
             serialize_version_patterns,
+            scheme,
             search,
             replace,
             no_configured_files,
             ignore_missing_files,
             ignore_missing_version,
             dry_run,
+            keep_on_failure,
             commit,
             tag,
             sign_tags,
             tag_name,
             tag_message,
+            tag_conflict,
             commit_message,
             commit_args,
+            push,
+            push_remote,
+            moveable_tags,
             // extra stuff
             setup_hooks,
             pre_commit_hooks,
@@ -479,10 +727,38 @@ pub(crate) fn parse_global_config(
             included_paths,
             excluded_paths,
             additional_files,
+            conventional_commits,
+            changelog,
+            changelog_sections,
+            changelog_header,
+            changelog_entry,
+            dev_version,
+            provenance: Provenance::default(),
         },
     ))
 }
 
+/// Parse the list of version components under `key`, such as `include_bumps = major, minor`.
+fn parse_component_names(
+    value: &mut ini::SectionProxyMut<'_>,
+    key: &str,
+) -> Result<Option<Vec<String>>, ParseError> {
+    let names = value
+        .remove_option(key)
+        .and_then(as_optional)
+        .map(|value| as_spanned_string_array(value, true))
+        .transpose()?
+        .map(|names| {
+            names
+                .into_iter()
+                .map(|name| name.inner.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        });
+    Ok(names)
+}
+
+/// Parse a file config.
 pub(crate) fn parse_file_config(
     mut value: ini::SectionProxyMut<'_>,
     search_is_regex_compat: Option<bool>,
@@ -520,15 +796,54 @@ pub(crate) fn parse_file_config(
         .as_ref()
         .map(as_bool)
         .transpose()?;
+    let key_path = value
+        .remove_option("key_path")
+        .and_then(as_optional)
+        .map(ini::Spanned::into_inner);
+    let cargo_workspace = value
+        .remove_option("cargo_workspace")
+        .and_then(as_optional)
+        .as_ref()
+        .map(as_bool)
+        .transpose()?;
+    let include_bumps = parse_component_names(&mut value, "include_bumps")?;
+    let exclude_bumps = parse_component_names(&mut value, "exclude_bumps")?;
 
-    Ok(FileConfig {
+    let file_config = FileConfig {
         parse_version_pattern,
         serialize_version_patterns,
         search,
         replace,
         ignore_missing_file,
         ignore_missing_version,
-    })
+        key_path,
+        cargo_workspace,
+        include_bumps,
+        exclude_bumps,
+        location: Location::default(),
+        provenance: Provenance::default(),
+    };
+    Ok(file_config)
+}
+
+/// Where the keys of `section` that `key` recognizes are set, as built by `origin`.
+///
+/// Keys set to `None` are unset, and have no origin.
+fn section_provenance(
+    section: &ini::SectionProxyMut<'_>,
+    file_id: FileId,
+    key: fn(&str) -> Option<&'static str>,
+    origin: fn(Location) -> Origin,
+) -> Provenance {
+    section
+        .section()
+        .iter()
+        .filter(|(_, value)| value.as_ref() != "None")
+        .filter_map(|(name, _)| {
+            let location = Location::new(file_id, name.span.clone());
+            Some((key(name.as_ref())?, origin(location)))
+        })
+        .collect()
 }
 
 impl config::Config {
@@ -566,7 +881,7 @@ impl config::Config {
             let Some(section) = config.section_mut(&section_name) else {
                 continue;
             };
-            let span = section.span();
+            let span = section.span().clone();
 
             if !section_name.starts_with("bumpversion") {
                 if !allow_unknown {
@@ -585,21 +900,33 @@ impl config::Config {
 
             match section_parts[..] {
                 ["bumpversion"] => {
+                    let provenance =
+                        section_provenance(&section, file_id, provenance::global_key, |location| {
+                            Origin::ConfigFile { location }
+                        });
                     (search_is_regex_compat, out.global) = parse_global_config(section)?;
+                    out.global.provenance = provenance;
                 }
                 ["bumpversion", prefix, value] => {
-                    if prefix.starts_with("file") {
-                        let config = parse_file_config(section, search_is_regex_compat)?;
-                        out.files.push((InputFile::Path(value.into()), config));
-                    } else if prefix.starts_with("glob") {
-                        let config = parse_file_config(section, search_is_regex_compat)?;
-                        out.files.push((
+                    if prefix.starts_with("file") || prefix.starts_with("glob") {
+                        let input_file = if prefix.starts_with("file") {
+                            InputFile::Path(value.into())
+                        } else {
                             InputFile::GlobPattern {
                                 pattern: value.into(),
                                 exclude_patterns: None,
-                            },
-                            config,
-                        ));
+                            }
+                        };
+                        let provenance = section_provenance(
+                            &section,
+                            file_id,
+                            provenance::file_key,
+                            |location| Origin::FileSection { location },
+                        );
+                        let mut config = parse_file_config(section, search_is_regex_compat)?;
+                        config.location = Location::new(file_id, span.clone());
+                        config.provenance = provenance;
+                        out.files.push((input_file, config));
                     } else if prefix.starts_with("part") {
                         let config = parse_part_config(section)?;
                         out.components.insert(value.into(), config);
@@ -629,7 +956,10 @@ impl config::Config {
             }
         }
 
-        if found { Ok(Some(out)) } else { Ok(None) }
+        if !found {
+            return Ok(None);
+        }
+        Ok(Some(out))
     }
 
     /// Parse bumpversion configuration from an INI string.
@@ -683,7 +1013,7 @@ impl config::Config {
 const GLOBAL_SECTION: &str = "bumpversion";
 
 /// Return the section name of an INI section header line, if it is one.
-fn section_name(line: &str) -> Option<&str> {
+pub(crate) fn section_name(line: &str) -> Option<&str> {
     let trimmed = line.trim();
     let inner = trimmed.strip_prefix('[')?;
     let end = inner.find(']')?;
@@ -694,7 +1024,7 @@ fn section_name(line: &str) -> Option<&str> {
 ///
 /// Both `=` and `:` are accepted as assignment delimiters, matching the parser,
 /// and full-line comments are skipped.
-fn assignment_key(line: &str) -> Option<&str> {
+pub(crate) fn assignment_key(line: &str) -> Option<&str> {
     let trimmed = line.trim_start();
     if trimmed.starts_with('#') || trimmed.starts_with(';') {
         return None;
@@ -780,20 +1110,9 @@ where
     };
 
     if !dry_run {
-        use tokio::io::AsyncWriteExt;
-        let file = tokio::fs::OpenOptions::new()
-            .write(true)
-            .create(false)
-            .truncate(true)
-            .open(path)
-            .await
-            .map_err(as_io_error)?;
-        let mut writer = tokio::io::BufWriter::new(file);
-        writer
-            .write_all(after.as_bytes())
+        files::write_atomically(path, &after)
             .await
             .map_err(as_io_error)?;
-        writer.flush().await.map_err(as_io_error)?;
     }
     let modification = files::Modification {
         before,
@@ -803,6 +1122,8 @@ where
             search: search_regex.as_str().to_string(),
             replace_pattern: replace_pattern.clone(),
             replace: replacement,
+            matches: 1,
+            ignored_missing_version: None,
         }],
     };
     Ok(Some(modification))
@@ -812,7 +1133,8 @@ where
 mod tests {
     use crate::{
         config::{
-            Config, FileConfig, GlobalConfig, InputFile, RegexTemplate, VersionComponentSpec,
+            Config, FileConfig, GlobalConfig, InputFile, Origin, RegexTemplate,
+            VersionComponentSpec,
         },
         diagnostics::{BufferedPrinter, ToDiagnostics},
         f_string::{PythonFormatString, Value},
@@ -892,7 +1214,7 @@ mod tests {
                     FileConfig {
                         search: Some(RegexTemplate::Escaped(
                             [
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                                 Value::String("...HEAD".to_string()),
                             ]
                             .into_iter()
@@ -904,8 +1226,9 @@ mod tests {
                 ),
             ],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        similar_asserts::assert_eq!(config, Some(expected));
+        similar_asserts::assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -983,7 +1306,7 @@ mod tests {
                         search: Some(RegexTemplate::Escaped(
                             [
                                 Value::String(r#"version = ""#.to_string()),
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                                 Value::String(r#"""#.to_string()),
                             ]
                             .into_iter()
@@ -999,7 +1322,7 @@ mod tests {
                         search: Some(RegexTemplate::Escaped(
                             vec![
                                 Value::String(r#"__version__ = ""#.to_string()),
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                                 Value::String(r#"""#.to_string()),
                             ]
                             .into_iter()
@@ -1015,7 +1338,7 @@ mod tests {
                         search: Some(RegexTemplate::Escaped(
                             [
                                 Value::String("dots: ".to_string()),
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                             ]
                             .into_iter()
                             .collect(),
@@ -1030,7 +1353,7 @@ mod tests {
                         search: Some(RegexTemplate::Escaped(
                             [
                                 Value::String("dashes: ".to_string()),
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                             ]
                             .into_iter()
                             .collect(),
@@ -1042,11 +1365,11 @@ mod tests {
                         ),
                         serialize_version_patterns: Some(vec![
                             [
-                                Value::Argument("major".to_string()),
+                                Value::argument("major"),
                                 Value::String("-".to_string()),
-                                Value::Argument("minor".to_string()),
+                                Value::argument("minor"),
                                 Value::String("-".to_string()),
-                                Value::Argument("patch".to_string()),
+                                Value::argument("patch"),
                             ]
                             .into_iter()
                             .collect(),
@@ -1056,8 +1379,9 @@ mod tests {
                 ),
             ],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        similar_asserts::assert_eq!(config, Some(expected));
+        similar_asserts::assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1125,22 +1449,22 @@ mod tests {
                 ),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String("-".to_string()),
-                        Value::Argument("release".to_string()),
+                        Value::argument("release"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
@@ -1184,8 +1508,9 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
-        similar_asserts::assert_eq!(config, Some(expected));
+        similar_asserts::assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1222,7 +1547,7 @@ mod tests {
                     search: Some(RegexTemplate::Escaped(
                         [
                             Value::String("**unreleased**\n**v".to_string()),
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                             Value::String("**".to_string()),
                         ]
                         .into_iter()
@@ -1233,8 +1558,9 @@ mod tests {
                 },
             )],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        similar_asserts::assert_eq!(config, Some(expected));
+        similar_asserts::assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1271,7 +1597,7 @@ mod tests {
                     search: Some(RegexTemplate::Escaped(
                         [
                             Value::String("**unreleased**,\n**v".to_string()),
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                             Value::String("**,".to_string()),
                         ]
                         .into_iter()
@@ -1282,8 +1608,197 @@ mod tests {
                 },
             )],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        similar_asserts::assert_eq!(config, Some(expected));
+        similar_asserts::assert_eq!(config.map(Config::strip_origins), Some(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cfg_ini_conventional_commits() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_cfg = indoc::indoc! {r"
+            [bumpversion]
+            conventional_commits =
+                breaking = major
+                feat = minor
+                perf = patch
+        "};
+
+        let config = parse_ini(
+            bumpversion_cfg,
+            Options::default(),
+            &BufferedPrinter::default(),
+        )?
+        .0?
+        .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+
+        let expected: Vec<(String, String)> =
+            [("breaking", "major"), ("feat", "minor"), ("perf", "patch")]
+                .into_iter()
+                .map(|(commit_type, component)| (commit_type.to_string(), component.to_string()))
+                .collect();
+        similar_asserts::assert_eq!(config.global.conventional_commits, Some(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cfg_ini_include_and_exclude_bumps() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_cfg = indoc::indoc! {r"
+            [bumpversion:file:api.txt]
+            include_bumps = major
+
+            [bumpversion:file:docs.txt]
+            exclude_bumps = patch, build
+
+            [bumpversion]
+            parse = (?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)
+
+            [bumpversion:part:build]
+        "};
+
+        let config = parse_ini(
+            bumpversion_cfg,
+            Options::default(),
+            &BufferedPrinter::default(),
+        )?
+        .0?
+        .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+
+        let bumps: Vec<_> = config
+            .files
+            .iter()
+            .map(|(_, file)| (file.include_bumps.clone(), file.exclude_bumps.clone()))
+            .collect();
+        similar_asserts::assert_eq!(
+            bumps,
+            vec![
+                (Some(vec!["major".to_string()]), None),
+                (None, Some(vec!["patch".to_string(), "build".to_string()])),
+            ]
+        );
+
+        let unknown = indoc::indoc! {r"
+            [bumpversion:file:api.txt]
+            include_bumps = major, pacth
+            exclude_bumps = mayor
+        "};
+        let config = parse_ini(unknown, Options::default(), &BufferedPrinter::default())?
+            .0?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        let unknown: Vec<_> = config
+            .unknown_components()
+            .into_iter()
+            .map(|component| (component.key, component.component))
+            .collect();
+        similar_asserts::assert_eq!(
+            unknown,
+            vec![
+                ("include_bumps", "pacth".to_string()),
+                ("exclude_bumps", "mayor".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cfg_ini_component_width_and_pad() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_cfg = indoc::indoc! {r"
+            [bumpversion]
+            current_version = 1.2.0099
+
+            [bumpversion:part:build]
+            pad = True
+
+            [bumpversion:part:code]
+            width = 6
+            pad = None
+        "};
+
+        let config = parse_ini(
+            bumpversion_cfg,
+            Options::default(),
+            &BufferedPrinter::default(),
+        )?
+        .0?;
+        similar_asserts::assert_eq!(
+            config.map(|config| config.components),
+            Some(
+                [
+                    (
+                        "build".to_string(),
+                        VersionComponentSpec {
+                            pad: true,
+                            ..VersionComponentSpec::default()
+                        },
+                    ),
+                    (
+                        "code".to_string(),
+                        VersionComponentSpec {
+                            width: Some(6),
+                            ..VersionComponentSpec::default()
+                        },
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cfg_ini_provenance() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_cfg = indoc::indoc! {r"
+            [bumpversion]
+            current_version = 1.2.3
+            message = Release {new_version}
+            sign_tags = None
+
+            [bumpversion:file:VERSION]
+            ignore_missing_file = true
+        "};
+
+        let config = parse_ini(
+            bumpversion_cfg,
+            Options::default(),
+            &BufferedPrinter::default(),
+        )?
+        .0?
+        .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+        .finalize();
+
+        similar_asserts::assert_eq!(
+            config
+                .global
+                .provenance
+                .origin("commit_message")
+                .to_string(),
+            "config file"
+        );
+        similar_asserts::assert_eq!(
+            config.global.provenance.origin("sign_tags"),
+            Origin::Default
+        );
+        let (_, file) = config
+            .files
+            .first()
+            .ok_or_else(|| eyre::eyre!("expected a file"))?;
+        similar_asserts::assert_eq!(
+            file.provenance.origin("ignore_missing_files").to_string(),
+            "file section"
+        );
+        similar_asserts::assert_eq!(
+            file.provenance.origin("ignore_missing_version"),
+            Origin::Default
+        );
         Ok(())
     }
 }
@@ -2,6 +2,8 @@
 //!
 //! Provides support for reading bumpversion configuration from various file formats (TOML, INI),
 //! applying defaults, and finalizing settings for version bump operations.
+/// `Cargo.toml` parsing.
+pub mod cargo_toml;
 /// Configuration change-set types.
 pub mod change;
 /// Default values and helpers.
@@ -12,10 +14,14 @@ pub mod file;
 pub mod global;
 /// INI parser and compatibility handling.
 pub mod ini;
+/// Origins of configuration values.
+pub mod provenance;
 /// `pyproject.toml` parsing.
 pub mod pyproject_toml;
 /// Regex wrapper types and templates.
 pub mod regex;
+/// Built-in version schemes.
+pub mod scheme;
 /// `.bumpversion.toml` parsing.
 pub mod toml;
 /// Version component specifications.
@@ -23,11 +29,16 @@ pub mod version;
 
 pub use change::FileChange;
 pub use file::{FileConfig, FinalizedFileConfig};
-pub use global::{GlobalConfig, GlobalConfigFinalized};
+pub use global::{GlobalConfig, GlobalConfigFinalized, TagConflict};
+pub use provenance::{Origin, Provenance, Setting};
 pub use regex::{Regex, RegexTemplate};
-pub use version::{VersionComponentConfigs, VersionComponentSpec};
+pub use scheme::Scheme;
+pub use version::{ComponentOrder, VersionComponentConfigs, VersionComponentSpec};
 
+use crate::diagnostics::FileId;
 use crate::files::IoError;
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indexmap::{IndexMap, IndexSet};
 use std::path::{Path, PathBuf};
 
 #[derive(thiserror::Error, Debug)]
@@ -54,13 +65,47 @@ pub enum Error {
         /// Underlying parse error.
         source: ini::ParseError,
     },
-    /// Cargo.toml parsing not yet supported or failed.
+    /// Cargo.toml parsing error for a config file.
     #[error("failed to parse {path:?}")]
     CargoToml {
         /// Path to the Cargo.toml file.
         path: PathBuf,
-        // #[source]
-        // source: ini::ParseError,
+        #[source]
+        /// Underlying parse error.
+        source: pyproject_toml::ParseError,
+    },
+    /// The selected package is not configured.
+    #[error("unknown package {name:?}, expected one of {available:?}")]
+    UnknownPackage {
+        /// Name of the selected package.
+        name: String,
+        /// Names of the configured packages.
+        available: Vec<String>,
+    },
+    /// The selected package does not set its own `current_version`.
+    #[error("package {name:?} does not set its own current_version")]
+    MissingPackageVersion {
+        /// Name of the selected package.
+        name: String,
+    },
+    /// The tag name of the selected package does not contain the `{package}` placeholder.
+    #[error("tag_name {tag_name:?} of package {name:?} does not contain {{package}}")]
+    PackageTagName {
+        /// Name of the selected package.
+        name: String,
+        /// The configured tag name.
+        tag_name: String,
+    },
+    /// Files name version components that do not exist in `include_bumps` or `exclude_bumps`.
+    #[error(
+        "unknown version components {}, expected one of {available:?}",
+        .components.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    UnknownComponents {
+        /// Every name that is not a version component.
+        components: Vec<UnknownComponent>,
+        /// The version components.
+        available: Vec<String>,
     },
     /// Background task join error.
     #[error("failed to join spawned task")]
@@ -70,6 +115,46 @@ pub enum Error {
     Diagnostics(#[from] crate::diagnostics::Error),
 }
 
+/// A name in the `include_bumps` or `exclude_bumps` of a file that is not a version component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownComponent {
+    /// The unknown component.
+    pub component: String,
+    /// The key naming it, `include_bumps` or `exclude_bumps`.
+    pub key: &'static str,
+    /// Where the key is set.
+    pub origin: Origin,
+}
+
+impl UnknownComponent {
+    /// An error pointing at where the component is named, if it is named in the config file.
+    #[must_use]
+    pub fn to_diagnostic(&self, available: &[String]) -> Option<Diagnostic<FileId>> {
+        let (file_id, span) = self.origin.location()?.get()?;
+        let diagnostic = Diagnostic::error()
+            .with_message(format!("unknown version component in `{}`", self.key))
+            .with_labels(vec![Label::primary(file_id, span).with_message(format!(
+                "{:?} is not a version component",
+                self.component
+            ))])
+            .with_notes(vec![format!(
+                "the version components are {}",
+                available
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )]);
+        Some(diagnostic)
+    }
+}
+
+impl std::fmt::Display for UnknownComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} in `{}`", self.component, self.key)
+    }
+}
+
 /// Enumeration of recognized configuration file types for bumpversion.
 /// Supported configuration file types and their paths.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -199,6 +284,8 @@ pub struct Config {
     pub files: Vec<(InputFile, file::FileConfig)>,
     /// Version components to parse and serialize.
     pub components: version::VersionComponentConfigs,
+    /// Independently versioned packages, keyed by name.
+    pub packages: IndexMap<String, Config>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -210,6 +297,8 @@ pub struct FinalizedConfig {
     pub files: Vec<(InputFile, file::FinalizedFileConfig)>,
     /// Version component specifications.
     pub components: version::VersionComponentConfigs,
+    /// Name of the selected package, if any.
+    pub package: Option<String>,
 }
 
 impl Default for Config {
@@ -218,6 +307,7 @@ impl Default for Config {
             global: global::GlobalConfig::empty(),
             files: Vec::new(),
             components: version::VersionComponentConfigs::default(),
+            packages: IndexMap::new(),
         }
     }
 }
@@ -230,6 +320,137 @@ impl Config {
         }
     }
 
+    /// The configuration of the package `name`, falling back to the top-level configuration.
+    ///
+    /// The package's own settings and components take precedence. Only the package's files are
+    /// included, since the top-level files carry a different version.
+    ///
+    /// A package must set its own `current_version`, and its `tag_name` must contain
+    /// `{package}`, so that its tags are told apart from those of other packages. Without a
+    /// `tag_name`, a package is tagged as `{package}-v{new_version}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package is not configured, does not set a `current_version`, or
+    /// its `tag_name` does not contain `{package}`.
+    pub fn package(&self, name: &str) -> Result<Self, Error> {
+        let package = self
+            .packages
+            .get(name)
+            .ok_or_else(|| Error::UnknownPackage {
+                name: name.to_string(),
+                available: self.packages.keys().cloned().collect(),
+            })?;
+        if package.global.current_version.is_none() {
+            return Err(Error::MissingPackageVersion {
+                name: name.to_string(),
+            });
+        }
+        let mut global = package.global.clone();
+        global.merge_with(&self.global);
+        let tag_name = global.tag_name.get_or_insert_with(|| {
+            use crate::f_string::Value;
+            [
+                Value::argument("package"),
+                Value::String(String::from("-v")),
+                Value::argument("new_version"),
+            ]
+            .into_iter()
+            .collect()
+        });
+        if !tag_name
+            .named_arguments()
+            .any(|argument| argument == "package")
+        {
+            return Err(Error::PackageTagName {
+                name: name.to_string(),
+                tag_name: tag_name.to_string(),
+            });
+        }
+        let mut components = self.components.clone();
+        components.extend(package.components.clone());
+        Ok(Self {
+            global,
+            files: package.files.clone(),
+            components,
+            packages: IndexMap::new(),
+        })
+    }
+
+    /// The names of the version components: the groups of the `parse` pattern, or of the
+    /// pattern of the scheme or the default one, and the configured parts.
+    #[must_use]
+    pub fn component_names(&self) -> IndexSet<String> {
+        let parse_version_pattern = self
+            .global
+            .parse_version_pattern
+            .clone()
+            .or_else(|| {
+                self.global
+                    .scheme
+                    .as_ref()
+                    .map(Scheme::parse_version_pattern)
+            })
+            .unwrap_or_else(|| file::PARSE_VERSION_REGEX.clone());
+        parse_version_pattern
+            .capture_names()
+            .flatten()
+            .map(ToString::to_string)
+            .chain(self.components.keys().cloned())
+            .collect()
+    }
+
+    /// The names in the `include_bumps` and `exclude_bumps` of the files that are not
+    /// [`Self::component_names`].
+    ///
+    /// Check this once the command line overrides are merged, since `--parse` and `--scheme`
+    /// change the components.
+    #[must_use]
+    pub fn unknown_components(&self) -> Vec<UnknownComponent> {
+        let available = self.component_names();
+        let mut unknown = vec![];
+        for (_, file) in &self.files {
+            for (key, names) in [
+                ("include_bumps", &file.include_bumps),
+                ("exclude_bumps", &file.exclude_bumps),
+            ] {
+                unknown.extend(
+                    names
+                        .iter()
+                        .flatten()
+                        .filter(|name| !available.contains(*name))
+                        .map(|name| UnknownComponent {
+                            component: name.clone(),
+                            key,
+                            origin: file.provenance.origin(key),
+                        }),
+                );
+            }
+        }
+        unknown
+    }
+
+    /// Fill in the `parse` and `serialize` patterns and the components of the configured
+    /// scheme, keeping everything that is configured explicitly.
+    pub fn apply_scheme(&mut self) {
+        let Some(scheme) = self.global.scheme.clone() else {
+            return;
+        };
+        // patterns filled in from the scheme are explained by where the scheme is set
+        let origin = self.global.provenance.origin("scheme");
+        if self.global.parse_version_pattern.is_none() {
+            self.global.parse_version_pattern = Some(scheme.parse_version_pattern());
+            self.global.provenance.insert("parse", origin.clone());
+        }
+        if self.global.serialize_version_patterns.is_none() {
+            self.global.serialize_version_patterns = Some(scheme.serialize_version_patterns());
+            self.global.provenance.insert("serialize", origin);
+        }
+        for (name, spec) in scheme.components() {
+            self.components.entry(name).or_default().merge_with(&spec);
+        }
+    }
+
     // /// Apply defaults.
     // pub fn apply_defaults(&mut self, defaults: &global::GlobalConfig) {
     //     self.global.merge_with(defaults);
@@ -243,6 +464,7 @@ impl Config {
     /// Unset values are filled with defaults from global settings.
     #[must_use]
     pub fn finalize(mut self) -> FinalizedConfig {
+        self.apply_scheme();
         self.merge_file_configs_with_global_config();
         FinalizedConfig {
             global: self.global.finalize(),
@@ -252,16 +474,53 @@ impl Config {
                 .map(|(path, config)| (path, config.finalize()))
                 .collect(),
             components: self.components,
+            package: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Config {
+    /// Forget where the file entries and the values were defined, to compare a parsed
+    /// configuration with one written out by hand.
+    pub(crate) fn strip_origins(mut self) -> Self {
+        self.global.provenance = Provenance::default();
+        for (_, file_config) in &mut self.files {
+            file_config.location = crate::diagnostics::Location::default();
+            file_config.provenance = Provenance::default();
         }
+        self.packages = self
+            .packages
+            .into_iter()
+            .map(|(name, package)| (name, package.strip_origins()))
+            .collect();
+        self
+    }
+}
+
+impl FinalizedConfig {
+    /// Mark this configuration as the one of package `name`.
+    ///
+    /// Fills in the `{package}` placeholder of the tag and commit templates, so that tags of
+    /// different packages can be told apart.
+    #[must_use]
+    pub fn for_package(mut self, name: &str) -> Self {
+        let global = &mut self.global;
+        global.tag_name = global.tag_name.with_argument("package", name);
+        global.tag_message = global.tag_message.with_argument("package", name);
+        global.commit_message = global.commit_message.with_argument("package", name);
+        self.package = Some(name.to_string());
+        self
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        Config, global,
+        Config, Error, global,
         version::{self, VersionComponentConfigs, VersionComponentSpec},
     };
+    use crate::diagnostics::BufferedPrinter;
     use color_eyre::eyre;
     use indexmap::IndexMap;
     use similar_asserts::assert_eq as sim_assert_eq;
@@ -278,6 +537,7 @@ mod tests {
             },
             files: vec![],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
         let config = config.finalize();
         let component_configs = version::version_component_configs(&config);
@@ -344,6 +604,7 @@ mod tests {
             ]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
         let config = config.finalize();
         let component_configs = version::version_component_configs(&config);
@@ -380,4 +641,90 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_package_config() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            commit = true
+            tag_name = "{package}-v{new_version}"
+
+            [[tool.bumpversion.files]]
+            filename = "README.md"
+
+            [tool.bumpversion.packages.cli]
+            current_version = "0.4.0"
+            commit = false
+
+            [[tool.bumpversion.packages.cli.files]]
+            filename = "cli/VERSION"
+
+            [tool.bumpversion.packages.cli.parts.patch]
+            first_value = "1"
+        "#};
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("pyproject.toml".to_string(), toml.to_string());
+        let mut diagnostics = vec![];
+        let config = Config::from_pyproject_toml(toml, file_id, true, &mut diagnostics)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        sim_assert_eq!(config.packages.keys().collect::<Vec<_>>(), ["cli"]);
+        assert!(matches!(
+            config.package("missing"),
+            Err(Error::UnknownPackage { .. })
+        ));
+
+        let package = config.package("cli")?.finalize().for_package("cli");
+        sim_assert_eq!(package.global.current_version.as_deref(), Some("0.4.0"));
+        assert!(!package.global.commit);
+        sim_assert_eq!(package.global.tag_name.to_string(), "cli-v{new_version}");
+        sim_assert_eq!(package.files.len(), 1);
+        sim_assert_eq!(
+            package
+                .components
+                .get("patch")
+                .and_then(|part| part.first_value.as_deref()),
+            Some("1")
+        );
+        sim_assert_eq!(package.package.as_deref(), Some("cli"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_config_requires_own_version_and_tag() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [tool.bumpversion.packages.core]
+            current_version = "0.4.0"
+
+            [tool.bumpversion.packages.cli]
+            commit = true
+
+            [tool.bumpversion.packages.docs]
+            current_version = "2.0.0"
+            tag_name = "v{new_version}"
+        "#};
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("pyproject.toml".to_string(), toml.to_string());
+        let mut diagnostics = vec![];
+        let config = Config::from_pyproject_toml(toml, file_id, true, &mut diagnostics)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+
+        let core = config.package("core")?.finalize().for_package("core");
+        sim_assert_eq!(core.global.tag_name.to_string(), "core-v{new_version}");
+        assert!(matches!(
+            config.package("cli"),
+            Err(Error::MissingPackageVersion { name }) if name == "cli"
+        ));
+        assert!(matches!(
+            config.package("docs"),
+            Err(Error::PackageTagName { name, .. }) if name == "docs"
+        ));
+        Ok(())
+    }
 }
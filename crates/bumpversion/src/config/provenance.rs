@@ -0,0 +1,306 @@
+//! Where each configuration value came from.
+//!
+//! Settings are layered from the command line, the config file, the sections of its files and
+//! the defaults. The parsers and [`super::MergeWith`] record the origin of every value they set,
+//! so that `explain` can tell which layer won.
+use crate::diagnostics::{FileId, Location};
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use std::collections::BTreeMap;
+
+/// The keys of the global configuration, in the order they are explained.
+pub const GLOBAL_KEYS: &[&str] = &[
+    "current_version",
+    "parse",
+    "serialize",
+    "scheme",
+    "search",
+    "replace",
+    "allow_dirty",
+    "allow_downgrade",
+    "no_configured_files",
+    "ignore_missing_files",
+    "ignore_missing_version",
+    "dry_run",
+    "keep_on_failure",
+    "commit",
+    "commit_message",
+    "commit_args",
+    "tag",
+    "sign_tags",
+    "tag_name",
+    "tag_message",
+    "tag_conflict",
+    "moveable_tags",
+    "push",
+    "push_remote",
+    "setup_hooks",
+    "pre_commit_hooks",
+    "post_commit_hooks",
+    "included_paths",
+    "excluded_paths",
+    "additional_files",
+    "conventional_commits",
+    "changelog",
+    "changelog_sections",
+    "changelog_header",
+    "changelog_entry",
+    "dev_version",
+];
+
+/// The keys of a file section, in the order they are explained.
+pub const FILE_KEYS: &[&str] = &[
+    "parse",
+    "serialize",
+    "search",
+    "replace",
+    "ignore_missing_files",
+    "ignore_missing_version",
+    "key_path",
+    "cargo_workspace",
+    "include_bumps",
+    "exclude_bumps",
+];
+
+/// The keys of a file section that fall back to the global configuration.
+pub const INHERITED_FILE_KEYS: &[&str] = &[
+    "parse",
+    "serialize",
+    "search",
+    "replace",
+    "ignore_missing_files",
+    "ignore_missing_version",
+];
+
+/// Alternative spellings of keys, along with the key they stand for.
+const ALIASES: &[(&str, &str)] = &[
+    ("message", "commit_message"),
+    ("sign_tag", "sign_tags"),
+    ("ignore_missing_file", "ignore_missing_files"),
+];
+
+/// The key in `keys` that `name` spells, resolving aliases.
+fn canonical_key(name: &str, keys: &[&'static str]) -> Option<&'static str> {
+    let name = ALIASES
+        .iter()
+        .find_map(|(alias, key)| (*alias == name).then_some(*key))
+        .unwrap_or(name);
+    keys.iter().copied().find(|key| *key == name)
+}
+
+/// The global key that `name` spells, if any.
+#[must_use]
+pub fn global_key(name: &str) -> Option<&'static str> {
+    canonical_key(name, GLOBAL_KEYS)
+}
+
+/// The file section key that `name` spells, if any.
+#[must_use]
+pub fn file_key(name: &str) -> Option<&'static str> {
+    canonical_key(name, FILE_KEYS)
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum Origin {
+    /// A command line flag, such as `--commit`.
+    CommandLine {
+        /// The flag that set the value.
+        flag: String,
+    },
+    /// An environment variable, such as `BUMPVERSION_COMMIT`.
+    Environment {
+        /// The variable that set the value.
+        variable: String,
+    },
+    /// The global section of the config file.
+    ConfigFile {
+        /// Where the key is set.
+        #[serde(skip)]
+        location: Location,
+    },
+    /// The section of a file in the config file.
+    FileSection {
+        /// Where the key is set.
+        #[serde(skip)]
+        location: Location,
+    },
+    /// The built-in default.
+    #[default]
+    Default,
+}
+
+impl Origin {
+    /// Where the value is set in the config file, if it is set there.
+    #[must_use]
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Self::ConfigFile { location } | Self::FileSection { location } => Some(location),
+            Self::CommandLine { .. } | Self::Environment { .. } | Self::Default => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommandLine { flag } => write!(f, "command line flag `{flag}`"),
+            Self::Environment { variable } => write!(f, "environment variable `{variable}`"),
+            Self::ConfigFile { .. } => write!(f, "config file"),
+            Self::FileSection { .. } => write!(f, "file section"),
+            Self::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// The origin of every configured value, by key.
+///
+/// A key without an origin has its default value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Provenance(BTreeMap<&'static str, Origin>);
+
+impl Provenance {
+    /// Record that the value of `key` came from `origin`.
+    pub fn insert(&mut self, key: &'static str, origin: Origin) {
+        self.0.insert(key, origin);
+    }
+
+    /// Where the value of `key` came from.
+    #[must_use]
+    pub fn origin(&self, key: &str) -> Origin {
+        self.0.get(key).cloned().unwrap_or_default()
+    }
+
+    /// Take the origins of the `keys` of `other` that have no origin yet.
+    pub fn inherit(&mut self, other: &Self, keys: &[&'static str]) {
+        for key in keys {
+            if let Some(origin) = other.0.get(key) {
+                self.0.entry(key).or_insert_with(|| origin.clone());
+            }
+        }
+    }
+}
+
+impl FromIterator<(&'static str, Origin)> for Provenance {
+    fn from_iter<I: IntoIterator<Item = (&'static str, Origin)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> super::MergeWith<&'a Provenance> for Provenance {
+    fn merge_with(&mut self, other: &'a Provenance) {
+        for (key, origin) in &other.0 {
+            self.0.entry(key).or_insert_with(|| origin.clone());
+        }
+    }
+}
+
+/// An effective configuration value and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Setting {
+    /// Name of the setting, as written in the config file.
+    pub key: &'static str,
+    /// The value, written the way it would be in a `.bumpversion.toml`.
+    pub value: String,
+    /// Where the value came from.
+    pub origin: Origin,
+}
+
+impl Setting {
+    /// Look up the origin of the `value` of `key` in `provenance`.
+    #[must_use]
+    pub fn new(key: &'static str, value: String, provenance: &Provenance) -> Self {
+        Self {
+            key,
+            value,
+            origin: provenance.origin(key),
+        }
+    }
+
+    /// A note pointing at where the setting is set in the config file, if it is set there.
+    #[must_use]
+    pub fn to_diagnostic(&self) -> Option<Diagnostic<FileId>> {
+        let (file_id, span) = self.origin.location()?.get()?;
+        let diagnostic = Diagnostic::note()
+            .with_message(format!("`{}` is set in the {}", self.key, self.origin))
+            .with_labels(vec![
+                Label::primary(file_id, span).with_message(format!("sets `{}`", self.key)),
+            ]);
+        Some(diagnostic)
+    }
+}
+
+/// Format `value` as a TOML string.
+pub(crate) fn string(value: impl std::fmt::Display) -> String {
+    format!("{:?}", value.to_string())
+}
+
+/// Format `values` as a TOML array of strings.
+pub(crate) fn strings<T: std::fmt::Display>(values: impl IntoIterator<Item = T>) -> String {
+    let values: Vec<_> = values.into_iter().map(string).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// Format `pairs` as an inline TOML table of strings.
+pub(crate) fn table(pairs: &[(String, String)]) -> String {
+    let entries: Vec<_> = pairs
+        .iter()
+        .map(|(key, value)| format!("{key} = {}", string(value)))
+        .collect();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+/// Format an optional value, which is `unset` if it has none.
+pub(crate) fn optional(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "unset".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Origin, Provenance, file_key, global_key};
+    use crate::config::MergeWith;
+    use similar_asserts::assert_eq as sim_assert_eq;
+
+    /// Aliases are recorded under the key they stand for.
+    #[test]
+    fn test_canonical_keys() {
+        sim_assert_eq!(global_key("message"), Some("commit_message"));
+        sim_assert_eq!(global_key("sign_tag"), Some("sign_tags"));
+        sim_assert_eq!(global_key("commit"), Some("commit"));
+        sim_assert_eq!(global_key("files"), None);
+        sim_assert_eq!(
+            file_key("ignore_missing_file"),
+            Some("ignore_missing_files")
+        );
+        sim_assert_eq!(file_key("commit"), None);
+    }
+
+    /// Merging keeps the origins that are already known.
+    #[test]
+    fn test_merge_keeps_first_origin() {
+        let flag = Origin::CommandLine {
+            flag: "--commit".to_string(),
+        };
+        let mut provenance: Provenance = [("commit", flag.clone())].into_iter().collect();
+        let config_file: Provenance = [
+            (
+                "commit",
+                Origin::ConfigFile {
+                    location: crate::diagnostics::Location::default(),
+                },
+            ),
+            (
+                "tag",
+                Origin::ConfigFile {
+                    location: crate::diagnostics::Location::default(),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        provenance.merge_with(&config_file);
+        sim_assert_eq!(provenance.origin("commit"), flag);
+        sim_assert_eq!(provenance.origin("tag").to_string(), "config file");
+        sim_assert_eq!(provenance.origin("push"), Origin::Default);
+    }
+}
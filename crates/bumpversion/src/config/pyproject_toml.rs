@@ -1,9 +1,13 @@
 use crate::{
     config::{
-        self, Config, InputFile, file::FileConfig, global::GlobalConfig, regex::RegexTemplate,
+        self, Config, InputFile,
+        file::FileConfig,
+        global::GlobalConfig,
+        provenance::{self, Origin, Provenance},
+        regex::RegexTemplate,
         version::VersionComponentSpec,
     },
-    diagnostics::{FileId, Span},
+    diagnostics::{FileId, Location, Span},
     f_string::PythonFormatString,
 };
 use codespan_reporting::diagnostic::Diagnostic;
@@ -65,6 +69,42 @@ pub enum ParseError {
         /// Source span of the offending value.
         span: Span,
     },
+    #[error("{source}")]
+    /// A version scheme could not be parsed.
+    InvalidScheme {
+        #[source]
+        /// Underlying scheme error.
+        source: config::scheme::Error,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A `CalVer` format could not be parsed or names an unknown token.
+    InvalidCalVerFormat {
+        #[source]
+        /// Underlying `CalVer` error.
+        source: Box<crate::version::calver::Error>,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A tag conflict policy could not be parsed.
+    InvalidTagConflict {
+        #[source]
+        /// Underlying tag conflict error.
+        source: config::global::UnknownTagConflict,
+        /// Source span of the offending value.
+        span: Span,
+    },
+    #[error("{source}")]
+    /// A component order could not be parsed.
+    InvalidComponentOrder {
+        #[source]
+        /// Underlying component order error.
+        source: config::version::UnknownComponentOrder,
+        /// Source span of the offending value.
+        span: Span,
+    },
     #[error("{message}")]
     /// A regex string could not be compiled.
     InvalidRegex {
@@ -90,6 +130,10 @@ mod diagnostics {
     use codespan_reporting::diagnostic::{Diagnostic, Label};
 
     impl ToDiagnostics for super::ParseError {
+        #[expect(
+            clippy::too_many_lines,
+            reason = "one diagnostic per error variant, and splitting them up would not make it clearer"
+        )]
         fn to_diagnostics<F: Copy + PartialEq>(&self, file_id: F) -> Vec<Diagnostic<F>> {
             match self {
                 Self::InvalidFormatString {
@@ -105,6 +149,34 @@ mod diagnostics {
                             Label::secondary(file_id, span.clone()).with_message(message),
                         ]),
                 ],
+                Self::InvalidScheme { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid version scheme".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidCalVerFormat { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid calver format".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidTagConflict { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid tag conflict policy".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
+                Self::InvalidComponentOrder { source, span } => vec![
+                    Diagnostic::error()
+                        .with_message("invalid component order".to_string())
+                        .with_labels(vec![
+                            Label::primary(file_id, span.clone()).with_message(source.to_string()),
+                        ]),
+                ],
                 Self::InvalidRegex {
                     source,
                     message,
@@ -297,6 +369,74 @@ pub fn as_regex<'de>(value: &'de toml::Value<'de>) -> Result<config::regex::Rege
     })
 }
 
+#[inline]
+/// Parse a [`config::Scheme`] from a TOML value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidScheme`] if the value is not a known version scheme.
+pub fn as_scheme<'de>(value: &'de toml::Value<'de>) -> Result<config::Scheme, ParseError> {
+    as_str(value).and_then(|s| {
+        s.parse().map_err(|source| ParseError::InvalidScheme {
+            source,
+            span: value.span.into(),
+        })
+    })
+}
+
+#[inline]
+/// Parse a `CalVer` format from a TOML value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidCalVerFormat`] if the value is not a valid `CalVer` format.
+pub fn as_calver_format<'de>(value: &'de toml::Value<'de>) -> Result<String, ParseError> {
+    as_str(value).and_then(|s| {
+        crate::version::calver::CalVerFunction::new(s)
+            .bump()
+            .map_err(|source| ParseError::InvalidCalVerFormat {
+                source: Box::new(source),
+                span: value.span.into(),
+            })?;
+        Ok(s.to_string())
+    })
+}
+
+#[inline]
+/// Parse a [`config::version::ComponentOrder`] from a TOML value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidComponentOrder`] if the value is not a known component order.
+pub fn as_component_order<'de>(
+    value: &'de toml::Value<'de>,
+) -> Result<config::version::ComponentOrder, ParseError> {
+    as_str(value).and_then(|s| {
+        s.parse()
+            .map_err(|source| ParseError::InvalidComponentOrder {
+                source,
+                span: value.span.into(),
+            })
+    })
+}
+
+#[inline]
+/// Parse a [`config::TagConflict`] from a TOML value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::InvalidTagConflict`] if the value is not a known tag conflict policy.
+pub fn as_tag_conflict<'de>(
+    value: &'de toml::Value<'de>,
+) -> Result<config::TagConflict, ParseError> {
+    as_str(value).and_then(|s| {
+        s.parse().map_err(|source| ParseError::InvalidTagConflict {
+            source,
+            span: value.span.into(),
+        })
+    })
+}
+
 #[inline]
 /// Parse a owned string from a TOML value.
 ///
@@ -348,6 +488,24 @@ pub fn as_component_value<'de>(value: &'de toml::Value<'de>) -> Result<String, P
         })
 }
 
+#[inline]
+/// Parse a non-negative integer from a TOML value.
+///
+/// # Errors
+///
+/// Returns [`ParseError::UnexpectedType`] if the value is not a non-negative integer.
+pub fn as_usize<'de>(value: &'de toml::Value<'de>) -> Result<usize, ParseError> {
+    value
+        .as_integer()
+        .and_then(|value| usize::try_from(value).ok())
+        .ok_or_else(|| ParseError::UnexpectedType {
+            message: "expected a non-negative integer".to_string(),
+            expected: vec![ValueKind::Integer],
+            found: value.into(),
+            span: value.span.into(),
+        })
+}
+
 #[inline]
 /// Parse a boolean from a TOML value.
 ///
@@ -365,6 +523,7 @@ pub fn as_bool<'de>(value: &'de toml::Value<'de>) -> Result<bool, ParseError> {
 
 pub(crate) fn parse_file<'de>(
     value: &'de toml::Value<'de>,
+    file_id: FileId,
     search_is_regex: Option<bool>,
 ) -> Result<(InputFile, FileConfig), ParseError> {
     let table = value.as_table().ok_or_else(|| ParseError::UnexpectedType {
@@ -396,7 +555,11 @@ pub(crate) fn parse_file<'de>(
         }
     }?;
 
-    let file_config = parse_file_config(table, search_is_regex)?;
+    let mut file_config = parse_file_config(table, search_is_regex)?;
+    file_config.location = Location::new(file_id, value.span);
+    file_config.provenance = table_provenance(table, file_id, provenance::file_key, |location| {
+        Origin::FileSection { location }
+    });
     Ok((input_file, file_config))
 }
 
@@ -428,8 +591,18 @@ pub(crate) fn parse_part_config<'de>(
         .map(as_bool)
         .transpose()?
         .unwrap_or_default();
-    let calver_format = table.get("calver_format").map(as_string).transpose()?;
+    let calver_format = table
+        .get("calver_format")
+        .map(as_calver_format)
+        .transpose()?;
     let depends_on = table.get("depends_on").map(as_string).transpose()?;
+    let width = table.get("width").map(as_usize).transpose()?;
+    let pad = table
+        .get("pad")
+        .map(as_bool)
+        .transpose()?
+        .unwrap_or_default();
+    let order = table.get("order").map(as_component_order).transpose()?;
 
     Ok(VersionComponentSpec {
         independent,
@@ -439,6 +612,9 @@ pub(crate) fn parse_part_config<'de>(
         always_increment,
         calver_format,
         depends_on,
+        width,
+        pad,
+        order,
     })
 }
 
@@ -462,6 +638,29 @@ fn parse_search_pattern<'de>(
     Ok((search_is_regex_compat, search))
 }
 
+/// Parse a table of string values into `(key, value)` pairs, in the order they are written.
+fn as_string_pairs<'de>(
+    value: &'de toml::Value<'de>,
+    name: &str,
+) -> Result<Vec<(String, String)>, ParseError> {
+    let table = value.as_table().ok_or_else(|| ParseError::UnexpectedType {
+        message: format!("{name} must be a table"),
+        expected: vec![ValueKind::Table],
+        found: value.into(),
+        span: value.span.into(),
+    })?;
+    let mut entries: Vec<_> = table.iter().collect();
+    entries.sort_by_key(|(key, _)| key.span.start);
+    entries
+        .into_iter()
+        .map(|(key, value)| Ok((key.name.to_string(), as_string(value)?)))
+        .collect()
+}
+
+#[expect(
+    clippy::too_many_lines,
+    reason = "parses one global key after another, and splitting them up would not make it clearer"
+)]
 pub(crate) fn parse_global_config<'de>(
     table: &'de toml::value::Table<'de>,
 ) -> Result<(Option<bool>, GlobalConfig), ParseError> {
@@ -470,7 +669,9 @@ pub(crate) fn parse_global_config<'de>(
     let (is_regex, search) = parse_search_pattern(table, None)?;
 
     let allow_dirty = table.get("allow_dirty").map(as_bool).transpose()?;
+    let allow_downgrade = table.get("allow_downgrade").map(as_bool).transpose()?;
     let parse_version_pattern = table.get("parse").map(as_regex).transpose()?;
+    let scheme = table.get("scheme").map(as_scheme).transpose()?;
     let serialize_version_patterns = table
         .get("serialize")
         .map(as_array)
@@ -490,6 +691,7 @@ pub(crate) fn parse_global_config<'de>(
         .map(as_bool)
         .transpose()?;
     let dry_run = table.get("dry_run").map(as_bool).transpose()?;
+    let keep_on_failure = table.get("keep_on_failure").map(as_bool).transpose()?;
     let commit = table.get("commit").map(as_bool).transpose()?;
     let tag = table.get("tag").map(as_bool).transpose()?;
     let sign_tags = table
@@ -499,12 +701,24 @@ pub(crate) fn parse_global_config<'de>(
         .transpose()?;
     let tag_name = table.get("tag_name").map(as_format_string).transpose()?;
     let tag_message = table.get("tag_message").map(as_format_string).transpose()?;
+    let tag_conflict = table.get("tag_conflict").map(as_tag_conflict).transpose()?;
     let commit_message = table
         .get("commit_message")
         .or(table.get("message"))
         .map(as_format_string)
         .transpose()?;
     let commit_args = table.get("commit_args").map(as_string).transpose()?;
+    let push = table.get("push").map(as_bool).transpose()?;
+    let push_remote = table.get("push_remote").map(as_string).transpose()?;
+    let moveable_tags = table
+        .get("moveable_tags")
+        .map(as_array)
+        .map(|tags| {
+            tags.into_iter()
+                .map(as_format_string)
+                .collect::<Result<_, _>>()
+        })
+        .transpose()?;
 
     // extra stuff
     let setup_hooks = table.get("setup_hooks").map(as_string_array).transpose()?;
@@ -531,27 +745,56 @@ pub(crate) fn parse_global_config<'de>(
         .map(as_string_array)
         .transpose()?
         .map(|values| values.into_iter().map(PathBuf::from).collect());
+    let conventional_commits = table
+        .get("conventional_commits")
+        .map(|value| as_string_pairs(value, "conventional_commits"))
+        .transpose()?;
+    let changelog = table
+        .get("changelog")
+        .map(as_string)
+        .transpose()?
+        .map(PathBuf::from);
+    let changelog_sections = table
+        .get("changelog_sections")
+        .map(|value| as_string_pairs(value, "changelog_sections"))
+        .transpose()?;
+    let changelog_header = table
+        .get("changelog_header")
+        .map(as_format_string)
+        .transpose()?;
+    let changelog_entry = table
+        .get("changelog_entry")
+        .map(as_format_string)
+        .transpose()?;
+    let dev_version = table.get("dev_version").map(as_format_string).transpose()?;
 
     Ok((
         is_regex,
         GlobalConfig {
             allow_dirty,
+            allow_downgrade,
             current_version,
             parse_version_pattern,
             serialize_version_patterns,
+            scheme,
             search,
             replace,
             no_configured_files,
             ignore_missing_files,
             ignore_missing_version,
             dry_run,
+            keep_on_failure,
             commit,
             tag,
             sign_tags,
             tag_name,
             tag_message,
+            tag_conflict,
             commit_message,
             commit_args,
+            push,
+            push_remote,
+            moveable_tags,
             // extra stuff
             setup_hooks,
             pre_commit_hooks,
@@ -559,6 +802,13 @@ pub(crate) fn parse_global_config<'de>(
             included_paths,
             excluded_paths,
             additional_files,
+            conventional_commits,
+            changelog,
+            changelog_sections,
+            changelog_header,
+            changelog_entry,
+            dev_version,
+            provenance: Provenance::default(),
         },
     ))
 }
@@ -589,6 +839,16 @@ pub(crate) fn parse_file_config<'de>(
         .get("ignore_missing_version")
         .map(as_bool)
         .transpose()?;
+    let key_path = table.get("key_path").map(as_string).transpose()?;
+    let cargo_workspace = table.get("cargo_workspace").map(as_bool).transpose()?;
+    let include_bumps = table
+        .get("include_bumps")
+        .map(as_string_array)
+        .transpose()?;
+    let exclude_bumps = table
+        .get("exclude_bumps")
+        .map(as_string_array)
+        .transpose()?;
 
     Ok(FileConfig {
         parse_version_pattern,
@@ -597,9 +857,31 @@ pub(crate) fn parse_file_config<'de>(
         replace,
         ignore_missing_file,
         ignore_missing_version,
+        key_path,
+        cargo_workspace,
+        include_bumps,
+        exclude_bumps,
+        location: Location::default(),
+        provenance: Provenance::default(),
     })
 }
 
+/// Where the keys of `table` that `key` recognizes are set, as built by `origin`.
+fn table_provenance<'de>(
+    table: &toml::value::Table<'de>,
+    file_id: FileId,
+    key: fn(&str) -> Option<&'static str>,
+    origin: fn(Location) -> Origin,
+) -> Provenance {
+    table
+        .keys()
+        .filter_map(|name| {
+            let location = Location::new(file_id, name.span);
+            Some((key(&name.name)?, origin(location)))
+        })
+        .collect()
+}
+
 impl Config {
     /// Parse bumpversion configuration from a `toml_span` value tree.
     ///
@@ -608,7 +890,7 @@ impl Config {
     /// Returns [`ParseError`] if the bumpversion table contains an invalid configuration value.
     pub fn from_pyproject_value(
         config: &toml::Value,
-        _file_id: FileId,
+        file_id: FileId,
         _strict: bool,
         _diagnostics: &mut [Diagnostic<FileId>],
     ) -> Result<Option<Self>, ParseError> {
@@ -621,6 +903,25 @@ impl Config {
             return Ok(None);
         };
 
+        if config.as_table().is_some_and(toml::value::Table::is_empty) {
+            return Ok(None);
+        }
+
+        Self::from_bumpversion_table(config, file_id).map(Some)
+    }
+
+    /// Parse bumpversion configuration from the value of a bumpversion table.
+    ///
+    /// The table is `[tool.bumpversion]` in `pyproject.toml`, or one of the metadata tables in
+    /// `Cargo.toml`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if the bumpversion table contains an invalid configuration value.
+    pub(crate) fn from_bumpversion_table(
+        config: &toml::Value,
+        file_id: FileId,
+    ) -> Result<Self, ParseError> {
         let table = config
             .as_table()
             .ok_or_else(|| ParseError::UnexpectedType {
@@ -630,18 +931,18 @@ impl Config {
                 span: config.span.into(),
             })?;
 
-        if table.is_empty() {
-            return Ok(None);
-        }
-
-        let (is_regex_compat, global_file_config) = parse_global_config(table)?;
+        let (is_regex_compat, mut global_file_config) = parse_global_config(table)?;
+        global_file_config.provenance =
+            table_provenance(table, file_id, provenance::global_key, |location| {
+                Origin::ConfigFile { location }
+            });
 
         let files = match table.get("files") {
             None => vec![],
             Some(value) => match value.as_ref() {
                 toml::value::ValueInner::Array(array) => array
                     .iter()
-                    .map(|value| parse_file(value, is_regex_compat))
+                    .map(|value| parse_file(value, file_id, is_regex_compat))
                     .collect::<Result<Vec<(InputFile, FileConfig)>, _>>()?,
                 _ => {
                     return Err(ParseError::UnexpectedType {
@@ -677,11 +978,33 @@ impl Config {
             },
         };
 
-        Ok(Some(Self {
+        let packages = match table.get("packages") {
+            None => IndexMap::new(),
+            Some(value) => match value.as_ref() {
+                toml::value::ValueInner::Table(table) => table
+                    .iter()
+                    .map(|(key, value)| {
+                        let package = Self::from_bumpversion_table(value, file_id)?;
+                        Ok((key.name.to_string(), package))
+                    })
+                    .collect::<Result<IndexMap<String, Self>, _>>()?,
+                _ => {
+                    return Err(ParseError::UnexpectedType {
+                        message: "packages must be a table".to_string(),
+                        expected: vec![ValueKind::Table],
+                        found: value.into(),
+                        span: value.span.into(),
+                    });
+                }
+            },
+        };
+
+        Ok(Self {
             global: global_file_config,
             files,
             components,
-        }))
+            packages,
+        })
     }
 
     /// Parse bumpversion configuration from a `pyproject.toml` string.
@@ -708,7 +1031,7 @@ pub mod tests {
             self, Config, InputFile, change::FileChange, file::FileConfig, global::GlobalConfig,
             regex::RegexTemplate, version::VersionComponentSpec,
         },
-        diagnostics::{BufferedPrinter, ToDiagnostics},
+        diagnostics::{BufferedPrinter, Location, ToDiagnostics},
         f_string::{PythonFormatString, Value},
     };
     use codespan_reporting::diagnostic::Diagnostic;
@@ -791,7 +1114,7 @@ pub mod tests {
                     search: Some(RegexTemplate::Escaped(
                         [
                             Value::String("[myproject]\nversion=".to_string()),
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                         ]
                         .into_iter()
                         .collect(),
@@ -808,8 +1131,9 @@ pub mod tests {
             .into_iter()
             .collect(),
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1141,35 +1465,35 @@ pub mod tests {
                 commit: Some(true),
                 commit_args: Some("--no-verify".to_string()),
                 tag: Some(true),
-                tag_name: Some(PythonFormatString(vec![Value::Argument("new_version".to_string())])),
+                tag_name: Some(PythonFormatString(vec![Value::argument("new_version")])),
                 allow_dirty: Some(true),
                 parse_version_pattern: Some(regex::Regex::new("(?P<major>\\d+)\\.(?P<minor>\\d+)\\.(?P<patch>\\d+)(\\.(?P<dev>post)\\d+\\.dev\\d+)?")?.into()),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String(".".to_string()),
-                        Value::Argument("dev".to_string()),
-                        Value::Argument("$PR_NUMBER".to_string()),
+                        Value::argument("dev"),
+                        Value::argument("$PR_NUMBER"),
                         Value::String(".dev".to_string()),
-                        Value::Argument("distance_to_latest_tag".to_string()),
+                        Value::argument("distance_to_latest_tag"),
                     ].into_iter().collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ].into_iter().collect(),
                 ]),
                 commit_message: Some(PythonFormatString(vec![
                     Value::String("Version updated from ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" to ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 ..GlobalConfig::empty()
             },
@@ -1191,7 +1515,7 @@ pub mod tests {
                     InputFile::Path("CHANGELOG.md".into()),
                     FileConfig {
                         search: Some(RegexTemplate::Escaped([
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                             Value::String("...HEAD".to_string()),
                         ].into_iter().collect())),
                         replace: Some("{current_version}...{new_version}".to_string()),
@@ -1203,7 +1527,7 @@ pub mod tests {
                     FileConfig {
                         search: Some(RegexTemplate::Escaped([
                             Value::String("bump-my-version==".to_string()),
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                         ].into_iter().collect())),
                         replace: Some("bump-my-version=={new_version}".to_string()),
                         ..FileConfig::empty()
@@ -1236,8 +1560,9 @@ pub mod tests {
                     }
                 )
             ].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1269,22 +1594,22 @@ pub mod tests {
                 ),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String("-".to_string()),
-                        Value::Argument("release".to_string()),
+                        Value::argument("release"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
@@ -1295,7 +1620,7 @@ pub mod tests {
         };
 
         let config = parse_toml(pyproject_toml, &BufferedPrinter::default())?.0?;
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
 
         let pyproject_toml = indoc::indoc! {r#"
             [tool.pytest.ini_options]
@@ -1363,22 +1688,22 @@ pub mod tests {
                 ),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String("-".to_string()),
-                        Value::Argument("release".to_string()),
+                        Value::argument("release"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
@@ -1422,10 +1747,11 @@ pub mod tests {
             ]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
 
         let config = parse_toml(pyproject_toml, &BufferedPrinter::default())?.0?;
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
 
         Ok(())
     }
@@ -1566,7 +1892,7 @@ pub mod tests {
             ..Config::default()
         };
         let config = parse_toml(bumpversion_toml, &BufferedPrinter::default())?.0?;
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
 
         Ok(())
     }
@@ -1621,24 +1947,24 @@ pub mod tests {
                 ),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String("-".to_string()),
-                        Value::Argument("pre_label".to_string()),
+                        Value::argument("pre_label"),
                         Value::String("-".to_string()),
-                        Value::Argument("pre_n".to_string()),
+                        Value::argument("pre_n"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
@@ -1660,8 +1986,9 @@ pub mod tests {
             )]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1677,7 +2004,8 @@ pub mod tests {
         let pyproject_toml = include_str!("../../test-data/bump-my-version.pyproject.toml");
         let mut config = parse_toml(pyproject_toml, &BufferedPrinter::default())?
             .0?
-            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .strip_origins();
 
         let parse_regex: config::regex::Regex = regex::Regex::new(
             r"(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)(\.(?P<dev>post)\d+\.dev\d+)?",
@@ -1685,25 +2013,25 @@ pub mod tests {
         .into();
         let serialize = vec![
             [
-                Value::Argument("major".to_string()),
+                Value::argument("major"),
                 Value::String(".".to_string()),
-                Value::Argument("minor".to_string()),
+                Value::argument("minor"),
                 Value::String(".".to_string()),
-                Value::Argument("patch".to_string()),
+                Value::argument("patch"),
                 Value::String(".".to_string()),
-                Value::Argument("dev".to_string()),
-                Value::Argument("$PR_NUMBER".to_string()),
+                Value::argument("dev"),
+                Value::argument("$PR_NUMBER"),
                 Value::String(".dev".to_string()),
-                Value::Argument("distance_to_latest_tag".to_string()),
+                Value::argument("distance_to_latest_tag"),
             ]
             .into_iter()
             .collect(),
             [
-                Value::Argument("major".to_string()),
+                Value::argument("major"),
                 Value::String(".".to_string()),
-                Value::Argument("minor".to_string()),
+                Value::argument("minor"),
                 Value::String(".".to_string()),
-                Value::Argument("patch".to_string()),
+                Value::argument("patch"),
             ]
             .into_iter()
             .collect(),
@@ -1714,17 +2042,15 @@ pub mod tests {
                 commit: Some(true),
                 commit_args: Some("--no-verify".to_string()),
                 tag: Some(true),
-                tag_name: Some(PythonFormatString(vec![Value::Argument(
-                    "new_version".to_string(),
-                )])),
+                tag_name: Some(PythonFormatString(vec![Value::argument("new_version")])),
                 allow_dirty: Some(true),
                 parse_version_pattern: Some(parse_regex.clone()),
                 serialize_version_patterns: Some(serialize.clone()),
                 commit_message: Some(PythonFormatString(vec![
                     Value::String("Version updated from ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" to ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 pre_commit_hooks: Some(vec![
                     "uv sync --upgrade".to_string(),
@@ -1753,7 +2079,7 @@ pub mod tests {
                     FileConfig {
                         search: Some(RegexTemplate::Escaped(
                             [
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                                 Value::String("...HEAD".to_string()),
                             ]
                             .into_iter()
@@ -1769,7 +2095,7 @@ pub mod tests {
                         search: Some(RegexTemplate::Escaped(
                             [
                                 Value::String("bump-my-version==".to_string()),
-                                Value::Argument("current_version".to_string()),
+                                Value::argument("current_version"),
                             ]
                             .into_iter()
                             .collect(),
@@ -1806,6 +2132,7 @@ pub mod tests {
             )]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
         sim_assert_eq!(&config, &expected);
 
@@ -1818,17 +2145,14 @@ pub mod tests {
             &config.global,
             &GlobalConfig {
                 allow_dirty: Some(true),
+                allow_downgrade: Some(false),
                 tag: Some(true),
                 sign_tags: Some(false),
                 search: Some(RegexTemplate::Escaped(
-                    [Value::Argument("current_version".to_string()),]
-                        .into_iter()
-                        .collect()
+                    [Value::argument("current_version"),].into_iter().collect()
                 )),
                 replace: Some("{new_version}".to_string()),
-                tag_name: Some(PythonFormatString(vec![Value::Argument(
-                    "new_version".to_string()
-                )])),
+                tag_name: Some(PythonFormatString(vec![Value::argument("new_version")])),
                 commit: Some(true),
                 commit_args: Some("--no-verify".to_string()),
                 current_version: Some("0.29.0".to_string()),
@@ -1836,16 +2160,17 @@ pub mod tests {
                 ignore_missing_version: Some(false),
                 commit_message: Some(PythonFormatString(vec![
                     Value::String("Version updated from ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" to ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 tag_message: Some(PythonFormatString(vec![
                     Value::String("Bump version: ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" → ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
+                tag_conflict: GlobalConfig::default().tag_conflict,
                 parse_version_pattern: Some(parse_regex.clone()),
                 serialize_version_patterns: Some(serialize.clone()),
                 pre_commit_hooks: Some(vec![
@@ -1856,7 +2181,16 @@ pub mod tests {
                 setup_hooks: Some(vec![]),
                 post_commit_hooks: Some(vec![]),
                 dry_run: Some(false),
+                keep_on_failure: Some(false),
+                push: Some(false),
+                push_remote: GlobalConfig::default().push_remote,
+                moveable_tags: GlobalConfig::default().moveable_tags,
                 no_configured_files: Some(false),
+                conventional_commits: GlobalConfig::default().conventional_commits,
+                changelog_sections: GlobalConfig::default().changelog_sections,
+                changelog_header: GlobalConfig::default().changelog_header,
+                changelog_entry: GlobalConfig::default().changelog_entry,
+                dev_version: GlobalConfig::default().dev_version,
                 ..GlobalConfig::empty()
             },
         );
@@ -1925,15 +2259,16 @@ pub mod tests {
                         parse_version_pattern: parse_regex.clone(),
                         serialize_version_patterns: serialize.clone(),
                         search: RegexTemplate::Escaped(
-                            [Value::Argument("current_version".to_string()),]
-                                .into_iter()
-                                .collect()
+                            [Value::argument("current_version"),].into_iter().collect()
                         ),
                         replace: "{new_version}".to_string(),
                         ignore_missing_version: false,
                         ignore_missing_file: false,
                         include_bumps: Some(include_bumps.clone()),
                         exclude_bumps: None,
+                        key_path: None,
+                        cargo_workspace: None,
+                        location: Location::default(),
                     }]
                 ),
                 (
@@ -1952,13 +2287,16 @@ pub mod tests {
                             ignore_missing_file: false,
                             include_bumps: Some(include_bumps.clone()),
                             exclude_bumps: None,
+                            key_path: None,
+                            cargo_workspace: None,
+                            location: Location::default(),
                         },
                         FileChange {
                             parse_version_pattern: parse_regex.clone(),
                             serialize_version_patterns: serialize.clone(),
                             search: RegexTemplate::Escaped(
                                 [
-                                    Value::Argument("current_version".to_string()),
+                                    Value::argument("current_version"),
                                     Value::String("...HEAD".to_string())
                                 ]
                                 .into_iter()
@@ -1969,6 +2307,9 @@ pub mod tests {
                             ignore_missing_file: false,
                             include_bumps: Some(include_bumps.clone()),
                             exclude_bumps: None,
+                            key_path: None,
+                            cargo_workspace: None,
+                            location: Location::default(),
                         },
                     ],
                 ),
@@ -1980,7 +2321,7 @@ pub mod tests {
                         search: RegexTemplate::Escaped(
                             [
                                 Value::String("bump-my-version==".to_string()),
-                                Value::Argument("current_version".to_string())
+                                Value::argument("current_version")
                             ]
                             .into_iter()
                             .collect()
@@ -1990,6 +2331,9 @@ pub mod tests {
                         ignore_missing_file: false,
                         include_bumps: Some(include_bumps.clone()),
                         exclude_bumps: None,
+                        key_path: None,
+                        cargo_workspace: None,
+                        location: Location::default(),
                     },],
                 ),
                 (
@@ -2010,20 +2354,24 @@ pub mod tests {
                             ignore_missing_file: false,
                             include_bumps: Some(include_bumps.clone()),
                             exclude_bumps: None,
+                            key_path: None,
+                            cargo_workspace: None,
+                            location: Location::default(),
                         },
                         FileChange {
                             parse_version_pattern: parse_regex.clone(),
                             serialize_version_patterns: serialize.clone(),
                             search: RegexTemplate::Escaped(
-                                [Value::Argument("current_version".to_string()),]
-                                    .into_iter()
-                                    .collect()
+                                [Value::argument("current_version"),].into_iter().collect()
                             ),
                             replace: "{new_version}".to_string(),
                             ignore_missing_version: false,
                             ignore_missing_file: false,
                             include_bumps: Some(include_bumps.clone()),
                             exclude_bumps: None,
+                            key_path: None,
+                            cargo_workspace: None,
+                            location: Location::default(),
                         },
                     ]
                 ),
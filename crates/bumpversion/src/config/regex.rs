@@ -1,4 +1,4 @@
-use crate::f_string::{MissingArgumentError, PythonFormatString};
+use crate::f_string::{FormatError, PythonFormatString};
 use std::collections::HashMap;
 
 /// Wrapper around [`regex::Regex`] with stable ordering and hashing.
@@ -61,8 +61,8 @@ impl From<regex::Regex> for Regex {
 /// Errors that can occur when formatting a [`RegexTemplate`].
 pub enum RegexTemplateError {
     #[error(transparent)]
-    /// Required template argument is missing.
-    MissingArgument(#[from] MissingArgumentError),
+    /// Template argument is missing or cannot be formatted.
+    Format(#[from] FormatError),
     #[error(transparent)]
     /// Regex compilation error.
     Regex(#[from] regex::Error),
@@ -0,0 +1,635 @@
+//! Built-in version schemes.
+//!
+//! A [`Scheme`] expands into a `parse` pattern, `serialize` patterns and component specs, so a
+//! project following semver, PEP 440 or `CalVer` does not have to write them by hand. Each of
+//! them can still be overridden in the configuration.
+use super::{ComponentOrder, Regex, VersionComponentConfigs, VersionComponentSpec};
+use crate::f_string::{PythonFormatString, Value};
+
+/// Name of the component holding the date of a `CalVer` scheme.
+pub const CALVER_COMPONENT: &str = "date";
+
+/// Separators between the tokens of a `CalVer` scheme.
+const CALVER_SEPARATORS: [char; 2] = ['.', '-'];
+
+/// Semver with a pre-release of dot-separated identifiers, the last of which is its number.
+static SEMVER_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    #[expect(
+        clippy::expect_used,
+        reason = "static regex is a compile-time literal and known to be valid"
+    )]
+    let regex = regex::RegexBuilder::new(concat!(
+        r"(?P<major>0|[1-9]\d*)\.(?P<minor>0|[1-9]\d*)\.(?P<patch>0|[1-9]\d*)",
+        r"(?:-(?P<pre_label>[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)\.(?P<pre_number>0|[1-9]\d*))?",
+        r"(?:\+(?P<build>[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*))?",
+    ))
+    .build()
+    .expect("static semver regex must be valid");
+    regex.into()
+});
+
+/// PEP 440 with exactly three release segments, since each of them is a component.
+static PEP440_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    #[expect(
+        clippy::expect_used,
+        reason = "static regex is a compile-time literal and known to be valid"
+    )]
+    let regex = regex::RegexBuilder::new(concat!(
+        r"(?:(?P<epoch>\d+)!)?(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)",
+        r"(?:(?P<pre_label>a|b|rc)(?P<pre_number>\d+))?",
+        r"(?:\.post(?P<post>\d+))?(?:\.dev(?P<dev>\d+))?",
+    ))
+    .build()
+    .expect("static PEP 440 regex must be valid");
+    regex.into()
+});
+
+/// Errors that can occur when parsing a [`Scheme`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The scheme is none of the built-in schemes.
+    #[error("unknown version scheme {0:?}, expected `semver`, `pep440` or `calver:<format>`")]
+    Unknown(String),
+    /// The format of a `CalVer` scheme is invalid.
+    #[error("invalid calver scheme {format:?}: {reason}")]
+    InvalidCalVer {
+        /// The offending format.
+        format: String,
+        /// Why the format is invalid.
+        reason: String,
+    },
+}
+
+/// A `CalVer` scheme, such as `calver:YYYY.MM.patch`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalVer {
+    /// The format, such as `YYYY.MM.patch`.
+    pub format: String,
+    /// The `calver_format` of the date component, such as `{YYYY}.{MM}`.
+    pub date_format: String,
+    /// The numeric components following the date, each with the separator before it.
+    pub components: Vec<(String, String)>,
+    /// The `parse` pattern matching the date and the components.
+    pub parse_version_pattern: Regex,
+}
+
+impl CalVer {
+    /// Parse a `CalVer` format such as `YYYY.0M.patch`.
+    ///
+    /// The format starts with one or more [calver.org](https://calver.org) tokens making up the
+    /// date, followed by numeric components, separated by `.` or `-`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCalVer`] if the format does not start with a date token, or a
+    /// component name is not a valid identifier or used twice.
+    pub fn parse(format: &str) -> Result<Self, Error> {
+        let invalid = |reason: String| Error::InvalidCalVer {
+            format: format.to_string(),
+            reason,
+        };
+        let date_tokens = crate::version::calver::tokens(chrono::NaiveDate::default());
+        let is_date_token = |token: &str| date_tokens.contains_key(token);
+
+        let mut date_format = String::new();
+        let mut date_pattern = String::new();
+        let mut components: Vec<(String, String)> = vec![];
+        for (separator, token) in calver_tokens(format) {
+            if token.is_empty() {
+                return Err(invalid("empty token".to_string()));
+            }
+            if is_date_token(token) {
+                if !components.is_empty() {
+                    return Err(invalid(format!(
+                        "date token {token:?} must come before the components"
+                    )));
+                }
+                let token_pattern = if token == "MMM" {
+                    "[A-Za-z]{3}"
+                } else {
+                    r"\d+"
+                };
+                date_format.extend([separator, "{", token, "}"]);
+                date_pattern.push_str(&regex::escape(separator));
+                date_pattern.push_str(token_pattern);
+                continue;
+            }
+            if date_format.is_empty() {
+                return Err(invalid(format!(
+                    "must start with a date token such as `YYYY`, found {token:?}"
+                )));
+            }
+            let is_identifier = token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !is_identifier {
+                return Err(invalid(format!(
+                    "{token:?} is neither a date token nor a component name"
+                )));
+            }
+            if token == CALVER_COMPONENT || components.iter().any(|(_, name)| name == token) {
+                return Err(invalid(format!("component {token:?} is used twice")));
+            }
+            components.push((separator.to_string(), token.to_string()));
+        }
+
+        let mut pattern = format!("(?P<{CALVER_COMPONENT}>{date_pattern})");
+        pattern.extend(
+            components
+                .iter()
+                .map(|(separator, name)| format!(r"{}(?P<{name}>\d+)", regex::escape(separator))),
+        );
+        let parse_version_pattern =
+            Regex::try_from(pattern.as_str()).map_err(|err| invalid(err.to_string()))?;
+        Ok(Self {
+            format: format.to_string(),
+            date_format,
+            components,
+            parse_version_pattern,
+        })
+    }
+}
+
+/// Split a `CalVer` format into its tokens, each with the separator before it.
+fn calver_tokens(format: &str) -> impl Iterator<Item = (&str, &str)> {
+    std::iter::once("")
+        .chain(format.matches(CALVER_SEPARATORS))
+        .zip(format.split(CALVER_SEPARATORS))
+}
+
+/// A built-in version scheme.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scheme {
+    /// [Semantic versioning](https://semver.org), such as `1.2.3-rc.1+build.5`.
+    SemVer,
+    /// [PEP 440](https://peps.python.org/pep-0440/), such as `1!1.2.3rc1.post2.dev3`.
+    Pep440,
+    /// Calendar versioning, such as `calver:YYYY.MM.patch` for `2024.5.3`.
+    CalVer(CalVer),
+}
+
+impl std::fmt::Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SemVer => write!(f, "semver"),
+            Self::Pep440 => write!(f, "pep440"),
+            Self::CalVer(calver) => write!(f, "calver:{}", calver.format),
+        }
+    }
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "semver" => Ok(Self::SemVer),
+            "pep440" => Ok(Self::Pep440),
+            scheme => match scheme.strip_prefix("calver:") {
+                Some(format) => CalVer::parse(format).map(Self::CalVer),
+                None => Err(Error::Unknown(value.to_string())),
+            },
+        }
+    }
+}
+
+/// A placeholder for the component `name`.
+fn component(name: &str) -> Value {
+    Value::argument(name)
+}
+
+/// Literal `text`.
+fn literal(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+/// Build the serialize patterns of `release` with every combination of the optional
+/// `prefixes` and `suffixes`, from the most to the least complete.
+fn serialize_patterns(
+    prefixes: &[Vec<Value>],
+    release: &[Value],
+    suffixes: &[Vec<Value>],
+) -> Vec<PythonFormatString> {
+    let prefix_count = prefixes.len();
+    let groups = prefix_count + suffixes.len();
+    (0..1usize << groups)
+        .rev()
+        .map(|included| {
+            // the first group is the most significant bit, so it is dropped last
+            let is_included = |idx: usize| included & (1 << (groups - 1 - idx)) != 0;
+            let prefixes = prefixes
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| is_included(*idx))
+                .flat_map(|(_, group)| group.iter().cloned());
+            let suffixes = suffixes
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| is_included(prefix_count + idx))
+                .flat_map(|(_, group)| group.iter().cloned());
+            prefixes
+                .chain(release.iter().cloned())
+                .chain(suffixes)
+                .collect()
+        })
+        .collect()
+}
+
+/// A pre-release label, released at `final`.
+///
+/// A label sorts before the release, even one outside of `values`.
+fn prerelease_label(values: &[&str]) -> VersionComponentSpec {
+    VersionComponentSpec {
+        order: Some(ComponentOrder::PreRelease),
+        values: values
+            .iter()
+            .chain(&["final"])
+            .map(ToString::to_string)
+            .collect(),
+        optional_value: Some("final".to_string()),
+        first_value: Some("final".to_string()),
+        ..VersionComponentSpec::default()
+    }
+}
+
+impl Scheme {
+    /// The `parse` pattern of this scheme.
+    #[must_use]
+    pub fn parse_version_pattern(&self) -> Regex {
+        match self {
+            Self::SemVer => SEMVER_REGEX.clone(),
+            Self::Pep440 => PEP440_REGEX.clone(),
+            Self::CalVer(calver) => calver.parse_version_pattern.clone(),
+        }
+    }
+
+    /// The `serialize` patterns of this scheme, from the most to the least complete.
+    #[must_use]
+    pub fn serialize_version_patterns(&self) -> Vec<PythonFormatString> {
+        let release = [
+            component("major"),
+            literal("."),
+            component("minor"),
+            literal("."),
+            component("patch"),
+        ];
+        match self {
+            Self::SemVer => serialize_patterns(
+                &[],
+                &release,
+                &[
+                    vec![
+                        literal("-"),
+                        component("pre_label"),
+                        literal("."),
+                        component("pre_number"),
+                    ],
+                    vec![literal("+"), component("build")],
+                ],
+            ),
+            Self::Pep440 => serialize_patterns(
+                &[vec![component("epoch"), literal("!")]],
+                &release,
+                &[
+                    vec![component("pre_label"), component("pre_number")],
+                    vec![literal(".post"), component("post")],
+                    vec![literal(".dev"), component("dev")],
+                ],
+            ),
+            Self::CalVer(calver) => {
+                let release = std::iter::once(component(CALVER_COMPONENT))
+                    .chain(
+                        calver
+                            .components
+                            .iter()
+                            .flat_map(|(separator, name)| [literal(separator), component(name)]),
+                    )
+                    .collect::<Vec<_>>();
+                serialize_patterns(&[], &release, &[])
+            }
+        }
+    }
+
+    /// The component specs of this scheme.
+    #[must_use]
+    pub fn components(&self) -> VersionComponentConfigs {
+        match self {
+            Self::SemVer => [
+                (
+                    "pre_label".to_string(),
+                    prerelease_label(&["alpha", "beta", "rc"]),
+                ),
+                (
+                    "build".to_string(),
+                    VersionComponentSpec {
+                        order: Some(ComponentOrder::Ignore),
+                        ..VersionComponentSpec::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            Self::Pep440 => [
+                ("pre_label".to_string(), prerelease_label(&["a", "b", "rc"])),
+                (
+                    "epoch".to_string(),
+                    VersionComponentSpec {
+                        independent: Some(true),
+                        ..VersionComponentSpec::default()
+                    },
+                ),
+                (
+                    "dev".to_string(),
+                    VersionComponentSpec {
+                        order: Some(ComponentOrder::PreRelease),
+                        ..VersionComponentSpec::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            Self::CalVer(calver) => [(
+                CALVER_COMPONENT.to_string(),
+                VersionComponentSpec {
+                    calver_format: Some(calver.date_format.clone()),
+                    always_increment: true,
+                    ..VersionComponentSpec::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CALVER_COMPONENT, Error, Scheme};
+    use crate::{
+        config,
+        diagnostics::BufferedPrinter,
+        version::{Version, VersionSpec},
+    };
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+    use std::collections::HashMap;
+
+    fn finalized_config(toml: &str) -> eyre::Result<config::FinalizedConfig> {
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("pyproject.toml".to_string(), toml.to_string());
+        let mut diagnostics = vec![];
+        let config = config::Config::from_pyproject_toml(toml, file_id, true, &mut diagnostics)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        Ok(config.finalize())
+    }
+
+    fn scheme_config(scheme: &str) -> eyre::Result<config::FinalizedConfig> {
+        finalized_config(&format!(
+            "[tool.bumpversion]\ncurrent_version = \"1.0.0\"\nscheme = \"{scheme}\"\n"
+        ))
+    }
+
+    fn parse(config: &config::FinalizedConfig, version: &str) -> eyre::Result<Version> {
+        let components = config::version::version_component_configs(config);
+        let version_spec = VersionSpec::from_components(components);
+        Version::parse(version, &config.global.parse_version_pattern, &version_spec)
+            .ok_or_else(|| eyre::eyre!("expected {version:?} to parse"))
+    }
+
+    fn serialize(config: &config::FinalizedConfig, version: &Version) -> eyre::Result<String> {
+        let ctx = HashMap::<String, String>::new();
+        Ok(version.serialize(&config.global.serialize_version_patterns, &ctx)?)
+    }
+
+    fn bump(
+        config: &config::FinalizedConfig,
+        version: &str,
+        component: &str,
+    ) -> eyre::Result<String> {
+        let bumped = parse(config, version)?.bump(component)?;
+        serialize(config, &bumped)
+    }
+
+    /// Parses the built-in schemes and prints them back.
+    #[test]
+    fn test_parse_scheme() -> eyre::Result<()> {
+        crate::tests::init();
+
+        for scheme in [
+            "semver",
+            "pep440",
+            "calver:YYYY.MM.patch",
+            "calver:YY.0W-build",
+        ] {
+            sim_assert_eq!(scheme.parse::<Scheme>()?.to_string(), scheme);
+        }
+        sim_assert_eq!(
+            "semver2".parse::<Scheme>(),
+            Err(Error::Unknown("semver2".to_string()))
+        );
+        Ok(())
+    }
+
+    /// Rejects `CalVer` formats without a leading date or with unusable components.
+    #[test]
+    fn test_invalid_calver_scheme() {
+        crate::tests::init();
+
+        for format in [
+            "patch.YYYY",
+            "YYYY.patch.MM",
+            "YYYY..patch",
+            "YYYY.patch.patch",
+            "YYYY.date",
+            "YYYY.1st",
+        ] {
+            assert!(
+                matches!(
+                    format!("calver:{format}").parse::<Scheme>(),
+                    Err(Error::InvalidCalVer { .. })
+                ),
+                "expected {format:?} to be invalid"
+            );
+        }
+    }
+
+    /// Serializes every semver version the way it was parsed.
+    #[test]
+    fn test_semver_round_trip() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("semver")?;
+        for version in [
+            "1.2.3",
+            "1.2.3-alpha.0",
+            "1.2.3-rc.1",
+            "1.2.3+build.5",
+            "1.2.3-beta.2+exp.sha.5114f85",
+        ] {
+            sim_assert_eq!(serialize(&config, &parse(&config, version)?)?, version);
+        }
+        Ok(())
+    }
+
+    /// Parses any pre-release of dot-separated identifiers that ends in a number, and keeps a
+    /// label outside of the lifecycle when bumping its number.
+    #[test]
+    fn test_semver_accepts_any_prerelease() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("semver")?;
+        for (version, label, number) in [
+            ("1.0.0-pre.1", "pre", "1"),
+            ("1.0.0-x-y.7.z.92", "x-y.7.z", "92"),
+            ("1.0.0-alpha.beta.0+build.5", "alpha.beta", "0"),
+        ] {
+            let parsed = parse(&config, version)?;
+            sim_assert_eq!(
+                ["pre_label", "pre_number"].map(|name| parsed.get(name).and_then(|c| c.value())),
+                [Some(label), Some(number)]
+            );
+            sim_assert_eq!(serialize(&config, &parsed)?, version);
+        }
+        sim_assert_eq!(bump(&config, "1.0.0-pre.1", "pre_number")?, "1.0.0-pre.2");
+        sim_assert_eq!(bump(&config, "1.0.0-pre.1", "patch")?, "1.0.1");
+        assert!(bump(&config, "1.0.0-pre.1", "pre_label").is_err());
+        Ok(())
+    }
+
+    /// Rejects versions with fewer than three release segments.
+    #[test]
+    fn test_schemes_reject_short_versions() -> eyre::Result<()> {
+        crate::tests::init();
+
+        for (scheme, version) in [("semver", "1.0"), ("pep440", "2.1"), ("pep440", "2")] {
+            let config = scheme_config(scheme)?;
+            assert!(
+                parse(&config, version).is_err(),
+                "expected {version:?} to be rejected by {scheme}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Pre-releases and dev releases sort before their release, and build metadata is ignored.
+    #[test]
+    fn test_scheme_ordering() -> eyre::Result<()> {
+        crate::tests::init();
+
+        for (scheme, lower, higher) in [
+            ("semver", "1.0.0-rc.1", "1.0.0"),
+            ("semver", "1.0.0-pre.1", "1.0.0"),
+            ("semver", "1.0.0-alpha.1", "1.0.0-beta.0"),
+            ("semver", "1.0.0+build.9", "1.0.1+build.1"),
+            ("pep440", "1.2.3.dev4", "1.2.3"),
+            ("pep440", "1.2.3rc1.dev2", "1.2.3rc1"),
+            ("pep440", "1.2.3", "1.2.3.post1"),
+        ] {
+            let config = scheme_config(scheme)?;
+            let (lower, higher) = (parse(&config, lower)?, parse(&config, higher)?);
+            assert!(lower < higher, "expected {lower} < {higher} in {scheme}");
+        }
+
+        let config = scheme_config("semver")?;
+        assert!(parse(&config, "1.0.0+build.1")? == parse(&config, "1.0.0+build.2")?);
+        Ok(())
+    }
+
+    /// Bumps through the semver pre-release labels.
+    #[test]
+    fn test_semver_bump() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("semver")?;
+        sim_assert_eq!(bump(&config, "1.2.3", "patch")?, "1.2.4");
+        sim_assert_eq!(bump(&config, "1.2.3+build.5", "patch")?, "1.2.4");
+        sim_assert_eq!(bump(&config, "1.2.3-rc.1", "pre_number")?, "1.2.3-rc.2");
+        sim_assert_eq!(bump(&config, "1.2.3-alpha.1", "pre_label")?, "1.2.3-beta.0");
+        sim_assert_eq!(bump(&config, "1.2.3-rc.1", "pre_label")?, "1.2.3");
+        Ok(())
+    }
+
+    /// Serializes every PEP 440 version the way it was parsed.
+    #[test]
+    fn test_pep440_round_trip() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("pep440")?;
+        for version in [
+            "1.2.3",
+            "1.2.3a1",
+            "1.2.3rc2",
+            "1.2.3.post1",
+            "1.2.3.dev4",
+            "1!2.0.0rc1.post2.dev3",
+        ] {
+            sim_assert_eq!(serialize(&config, &parse(&config, version)?)?, version);
+        }
+        Ok(())
+    }
+
+    /// Keeps the PEP 440 epoch when bumping the release.
+    #[test]
+    fn test_pep440_bump() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("pep440")?;
+        sim_assert_eq!(bump(&config, "1!1.2.3", "minor")?, "1!1.3.0");
+        sim_assert_eq!(bump(&config, "1.2.3a1", "pre_label")?, "1.2.3b0");
+        sim_assert_eq!(bump(&config, "1.2.3.post1", "post")?, "1.2.3.post2");
+        Ok(())
+    }
+
+    /// Expands a `CalVer` format into its date and numeric components.
+    #[test]
+    fn test_calver_scheme() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = scheme_config("calver:YYYY.MM.patch")?;
+        sim_assert_eq!(
+            config.global.parse_version_pattern.as_str(),
+            r"(?P<date>\d+\.\d+)\.(?P<patch>\d+)"
+        );
+        let date = config
+            .components
+            .get(CALVER_COMPONENT)
+            .ok_or_else(|| eyre::eyre!("expected a date component"))?;
+        sim_assert_eq!(date.calver_format.as_deref(), Some("{YYYY}.{MM}"));
+        assert!(date.always_increment);
+
+        let version = parse(&config, "2024.5.3")?;
+        sim_assert_eq!(serialize(&config, &version)?, "2024.5.3");
+        sim_assert_eq!(
+            version.get(CALVER_COMPONENT).and_then(|c| c.value()),
+            Some("2024.5")
+        );
+        Ok(())
+    }
+
+    /// Keeps explicitly configured keys over the ones of the scheme.
+    #[test]
+    fn test_scheme_keys_can_be_overridden() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let config = finalized_config(indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.0.0"
+            scheme = "semver"
+            serialize = ["{major}.{minor}.{patch}-{pre_label}.{pre_number}", "{major}.{minor}.{patch}"]
+
+            [tool.bumpversion.parts.pre_label]
+            values = ["dev", "final"]
+        "#})?;
+        sim_assert_eq!(
+            config.global.parse_version_pattern.as_str(),
+            super::SEMVER_REGEX.as_str()
+        );
+        sim_assert_eq!(config.global.serialize_version_patterns.len(), 2);
+        let pre_label = config
+            .components
+            .get("pre_label")
+            .ok_or_else(|| eyre::eyre!("expected a pre_label component"))?;
+        sim_assert_eq!(pre_label.values, ["dev", "final"]);
+        sim_assert_eq!(pre_label.optional_value.as_deref(), Some("final"));
+        Ok(())
+    }
+}
@@ -69,7 +69,10 @@ pub(crate) fn replace_version_of_document(
     // new_value = search_for.sub(replace_with, value_before)
     // log_changes(f"{self.path}:{self.file_change.key_path}", value_before, new_value, dry_run)
     //
+    // keep the surrounding whitespace and trailing comments of the value
+    let decor = before.decor().clone();
     *before = Formatted::new(new_value.to_string());
+    *before.decor_mut() = decor;
     // set_nested_value(toml_data, new_value, self.file_change.key_path)
     //
     // self.path.write_text(tomlkit.dumps(toml_data), encoding="utf-8")
@@ -84,9 +87,20 @@ pub(crate) fn replace_version_of_document(
 //     Toml(#[from] toml_edit::TomlError),
 // }
 
+/// Whether the TOML document has a string value at `key_path`.
+fn has_string_value(document: &toml_edit::DocumentMut, key_path: &[&str]) -> bool {
+    key_path
+        .iter()
+        .try_fold(document.as_item(), |item, key| item.get(key))
+        .is_some_and(|item| item.as_str().is_some())
+}
+
 /// Update the `current_version` key in the configuration file
+///
+/// The version is replaced at the first of `key_paths` that holds a string value.
 pub(crate) async fn replace_version<K, V>(
     path: &Path,
+    key_paths: &[impl AsRef<[&str]>],
     config: &super::FinalizedConfig,
     ctx: &HashMap<K, V>,
     // _current_version: &str,
@@ -137,30 +151,24 @@ where
     let replacement = PythonFormatString::parse(replace_pattern)?;
     let replacement = replacement.format(ctx, true)?;
 
-    let _ = replace_version_of_document(
-        &mut document,
-        &["tool", "bumpversion", "current_version"],
-        &search_regex,
-        &replacement,
-    );
+    let replaced = key_paths
+        .iter()
+        .find(|key_path| has_string_value(&document, key_path.as_ref()))
+        .is_some_and(|key_path| {
+            replace_version_of_document(
+                &mut document,
+                key_path.as_ref(),
+                &search_regex,
+                &replacement,
+            )
+        });
 
     let after = document.to_string();
 
     if !dry_run {
-        use tokio::io::AsyncWriteExt;
-        let file = tokio::fs::OpenOptions::new()
-            .write(true)
-            .create(false)
-            .truncate(true)
-            .open(path)
+        files::write_atomically(path, &after)
             .await
             .map_err(as_io_error)?;
-        let mut writer = tokio::io::BufWriter::new(file);
-        writer
-            .write_all(after.as_bytes())
-            .await
-            .map_err(as_io_error)?;
-        writer.flush().await.map_err(as_io_error)?;
     }
 
     let modification = files::Modification {
@@ -171,6 +179,8 @@ where
             search: search_regex.as_str().to_string(),
             replace_pattern: replace_pattern.clone(),
             replace: replacement.clone(),
+            matches: usize::from(replaced),
+            ignored_missing_version: None,
         }],
     };
     Ok(Some(modification))
@@ -302,10 +312,235 @@ mod tests {
             },
             files: vec![],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
         let config = parse_toml(bumpversion_toml, &Printer::default())?.0?;
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_component_width_and_pad() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.0099"
+
+            [tool.bumpversion.parts.build]
+            pad = true
+
+            [tool.bumpversion.parts.code]
+            width = 6
+        "#};
+
+        let config = parse_toml(bumpversion_toml, &Printer::default())?.0?;
+        sim_assert_eq!(
+            config.map(|config| config.components),
+            Some(
+                [
+                    (
+                        "build".to_string(),
+                        VersionComponentSpec {
+                            pad: true,
+                            ..VersionComponentSpec::default()
+                        },
+                    ),
+                    (
+                        "code".to_string(),
+                        VersionComponentSpec {
+                            width: Some(6),
+                            ..VersionComponentSpec::default()
+                        },
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+
+        let invalid = indoc::indoc! {r"
+            [tool.bumpversion.parts.code]
+            width = -1
+        "};
+        assert!(parse_toml(invalid, &Printer::default())?.0.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_include_and_exclude_bumps() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "api.txt"
+            include_bumps = ["major"]
+
+            [[tool.bumpversion.files]]
+            filename = "docs.txt"
+            exclude_bumps = ["patch"]
+
+            [tool.bumpversion.packages.core]
+            parse = '(?P<major>\d+)\.(?P<build>\d+)'
+
+            [[tool.bumpversion.packages.core.files]]
+            filename = "core.txt"
+            include_bumps = ["build"]
+        "#};
+
+        let config = parse_toml(bumpversion_toml, &Printer::default())?
+            .0?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        let bumps: Vec<_> = config
+            .files
+            .iter()
+            .map(|(_, file)| (file.include_bumps.clone(), file.exclude_bumps.clone()))
+            .collect();
+        sim_assert_eq!(
+            bumps,
+            vec![
+                (Some(vec!["major".to_string()]), None),
+                (None, Some(vec!["patch".to_string()])),
+            ]
+        );
+
+        let unknown = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "api.txt"
+            include_bumps = ["major", "mayor"]
+        "#};
+        let printer = Printer::default();
+        let config = parse_toml(unknown, &printer)?
+            .0?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        let unknown = config.unknown_components();
+        let [component] = unknown.as_slice() else {
+            eyre::bail!("expected one unknown component, got {unknown:?}");
+        };
+        sim_assert_eq!(component.component, "mayor");
+        let available: Vec<String> = config.component_names().into_iter().collect();
+        let diagnostic = component
+            .to_diagnostic(&available)
+            .ok_or_else(|| eyre::eyre!("expected a diagnostic"))?;
+        sim_assert_eq!(printer.lines(&diagnostic).ok(), Some(vec![5]));
+        Ok(())
+    }
+
+    /// The tag conflict policy is parsed, and an unknown one points at its value.
+    #[test]
+    fn test_tag_conflict() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            tag_conflict = "replace"
+        "#};
+        let config = parse_toml(bumpversion_toml, &Printer::default())?
+            .0?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?;
+        sim_assert_eq!(
+            config.global.tag_conflict,
+            Some(config::TagConflict::Replace)
+        );
+
+        let unknown = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            tag_conflict = "overwrite"
+        "#};
+        let printer = Printer::default();
+        let (config, _, diagnostics) = parse_toml(unknown, &printer)?;
+        assert!(matches!(
+            config,
+            Err(config::pyproject_toml::ParseError::InvalidTagConflict { .. })
+        ));
+        let diagnostic = diagnostics
+            .first()
+            .ok_or_else(|| eyre::eyre!("expected a diagnostic"))?;
+        sim_assert_eq!(printer.lines(diagnostic).ok(), Some(vec![2]));
+        Ok(())
+    }
+
+    /// A `CalVer` format with an unknown token is rejected when parsing, pointing at its value.
+    #[test]
+    fn test_invalid_calver_format() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "2024.1"
 
+            [tool.bumpversion.parts.release]
+            calver_format = "{YYYY}.{MOnth}"
+        "#};
+        let printer = Printer::default();
+        let (config, _, diagnostics) = parse_toml(bumpversion_toml, &printer)?;
+        assert!(matches!(
+            config,
+            Err(config::pyproject_toml::ParseError::InvalidCalVerFormat { .. })
+        ));
+        let diagnostic = diagnostics
+            .first()
+            .ok_or_else(|| eyre::eyre!("expected a diagnostic"))?;
+        sim_assert_eq!(printer.lines(diagnostic).ok(), Some(vec![4]));
+        Ok(())
+    }
+
+    /// Every setting points at the line that sets it, and files inherit the origins of the
+    /// global settings they fall back to.
+    #[test]
+    fn test_provenance() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let bumpversion_toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            scheme = "semver"
+            message = "Release {new_version}"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+            search = "v{current_version}"
+        "#};
+        let printer = Printer::default();
+        let config = parse_toml(bumpversion_toml, &printer)?
+            .0?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .finalize();
+        let line = |settings: Vec<config::Setting>, key: &str| {
+            settings
+                .into_iter()
+                .find(|setting| setting.key == key)
+                .and_then(|setting| setting.to_diagnostic())
+                .and_then(|diagnostic| printer.lines(&diagnostic).ok())
+        };
+
+        sim_assert_eq!(
+            line(config.global.settings(), "commit_message"),
+            Some(vec![3])
+        );
+        sim_assert_eq!(line(config.global.settings(), "parse"), Some(vec![2]));
+        sim_assert_eq!(
+            config.global.provenance.origin("commit"),
+            config::Origin::Default
+        );
+
+        let (_, file) = config
+            .files
+            .first()
+            .ok_or_else(|| eyre::eyre!("expected a file"))?;
+        sim_assert_eq!(line(file.settings(), "search"), Some(vec![7]));
+        sim_assert_eq!(line(file.settings(), "serialize"), Some(vec![2]));
+        sim_assert_eq!(file.provenance.origin("search").to_string(), "file section");
+        sim_assert_eq!(file.provenance.origin("replace"), config::Origin::Default);
         Ok(())
     }
 
@@ -386,22 +621,22 @@ mod tests {
                 ),
                 serialize_version_patterns: Some(vec![
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String("-".to_string()),
-                        Value::Argument("release".to_string()),
+                        Value::argument("release"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
@@ -445,8 +680,9 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -535,7 +771,7 @@ mod tests {
                     InputFile::Path("should_override_serialize.txt".into()),
                     FileConfig {
                         serialize_version_patterns: Some(vec![
-                            [Value::Argument("major".to_string())].into_iter().collect(),
+                            [Value::argument("major")].into_iter().collect(),
                         ]),
                         ..FileConfig::empty()
                     },
@@ -561,8 +797,9 @@ mod tests {
                 ),
             ],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -617,8 +854,9 @@ mod tests {
             },
             files: vec![].into_iter().collect(),
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -714,22 +952,22 @@ mod tests {
                 commit: Some(false),
                 commit_message: Some(PythonFormatString(vec![
                     Value::String("Bump version: ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" → ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 commit_args: Some(String::new()),
                 tag: Some(false),
                 sign_tags: Some(false),
                 tag_name: Some(PythonFormatString(vec![
                     Value::String("v".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 tag_message: Some(PythonFormatString(vec![
                     Value::String("Bump version: ".to_string()),
-                    Value::Argument("current_version".to_string()),
+                    Value::argument("current_version"),
                     Value::String(" → ".to_string()),
-                    Value::Argument("new_version".to_string()),
+                    Value::argument("new_version"),
                 ])),
                 current_version: Some("1.0.0".to_string()),
                 parse_version_pattern: Some(
@@ -772,33 +1010,31 @@ mod tests {
                     // "{major}.{minor}.{patch}.{dev_label}{distance_to_latest_tag}+{short_branch_name}".to_string(),
                     // "{major}.{minor}.{patch}".to_string(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                         Value::String(".".to_string()),
-                        Value::Argument("dev_label".to_string()),
-                        Value::Argument("distance_to_latest_tag".to_string()),
+                        Value::argument("dev_label"),
+                        Value::argument("distance_to_latest_tag"),
                         Value::String("+".to_string()),
-                        Value::Argument("short_branch_name".to_string()),
+                        Value::argument("short_branch_name"),
                     ]
                     .into_iter()
                     .collect(),
                     [
-                        Value::Argument("major".to_string()),
+                        Value::argument("major"),
                         Value::String(".".to_string()),
-                        Value::Argument("minor".to_string()),
+                        Value::argument("minor"),
                         Value::String(".".to_string()),
-                        Value::Argument("patch".to_string()),
+                        Value::argument("patch"),
                     ]
                     .into_iter()
                     .collect(),
                 ]),
                 search: Some(RegexTemplate::Escaped(
-                    [Value::Argument("current_version".to_string())]
-                        .into_iter()
-                        .collect(),
+                    [Value::argument("current_version")].into_iter().collect(),
                 )),
                 replace: Some("{new_version}".to_string()),
                 ..GlobalConfig::empty()
@@ -862,9 +1098,12 @@ mod tests {
             ]
             .into_iter()
             .collect(),
+            packages: [].into_iter().collect(),
         };
 
-        let mut config = config.ok_or_else(|| eyre::eyre!("expected parsed bumpversion config"))?;
+        let mut config = config
+            .ok_or_else(|| eyre::eyre!("expected parsed bumpversion config"))?
+            .strip_origins();
 
         let actual_parse_pattern = config
             .global
@@ -929,8 +1168,9 @@ mod tests {
                 },
             )],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -963,7 +1203,7 @@ mod tests {
                     search: Some(RegexTemplate::Regex(
                         [
                             Value::String("^version: ".to_string()),
-                            Value::Argument("current_version".to_string()),
+                            Value::argument("current_version"),
                         ]
                         .into_iter()
                         .collect(),
@@ -973,8 +1213,9 @@ mod tests {
                 },
             )],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 
@@ -1020,8 +1261,9 @@ mod tests {
                 (InputFile::Path("VERSION".into()), FileConfig::empty()),
             ],
             components: [].into_iter().collect(),
+            packages: [].into_iter().collect(),
         };
-        sim_assert_eq!(config, Some(expected));
+        sim_assert_eq!(config.map(Config::strip_origins), Some(expected));
         Ok(())
     }
 }
@@ -3,6 +3,47 @@ use indexmap::IndexMap;
 /// Mapping from component name to its [`VersionComponentSpec`].
 pub type VersionComponentConfigs = IndexMap<String, VersionComponentSpec>;
 
+/// How a version component takes part in comparing versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ComponentOrder {
+    /// Compare the values, with a missing value standing for its optional value.
+    #[default]
+    Value,
+    /// Like [`ComponentOrder::Value`], but a version with the component sorts before the one
+    /// without it, as a PEP 440 dev release does.
+    PreRelease,
+    /// Leave the component out of comparisons, as semver build metadata.
+    Ignore,
+}
+
+/// The component order is none of `value`, `pre_release` or `ignore`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown component order {0:?}, expected `value`, `pre_release` or `ignore`")]
+pub struct UnknownComponentOrder(pub String);
+
+impl std::str::FromStr for ComponentOrder {
+    type Err = UnknownComponentOrder;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "value" => Ok(Self::Value),
+            "pre_release" => Ok(Self::PreRelease),
+            "ignore" => Ok(Self::Ignore),
+            _ => Err(UnknownComponentOrder(value.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ComponentOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Value => write!(f, "value"),
+            Self::PreRelease => write!(f, "pre_release"),
+            Self::Ignore => write!(f, "ignore"),
+        }
+    }
+}
+
 /// Configuration of a version component.
 ///
 /// This is used to read in the configuration from the bumpversion config file.
@@ -34,6 +75,35 @@ pub struct VersionComponentSpec {
 
     /// The name of the component this component depends on
     pub depends_on: Option<String>,
+
+    /// The minimum number of digits of a numeric component, zero-padded
+    pub width: Option<usize>,
+
+    /// Should a numeric component keep the number of digits of its current value?
+    ///
+    /// Off by default, so `007` is bumped to `8`.
+    pub pad: bool,
+
+    /// How the component takes part in comparing versions, [`ComponentOrder::Value`] if unset.
+    pub order: Option<ComponentOrder>,
+}
+
+impl<'a> super::MergeWith<&'a VersionComponentSpec> for VersionComponentSpec {
+    fn merge_with(&mut self, other: &'a VersionComponentSpec) {
+        self.independent.merge_with(other.independent.as_ref());
+        self.optional_value
+            .merge_with(other.optional_value.as_ref());
+        if self.values.is_empty() {
+            self.values.clone_from(&other.values);
+        }
+        self.first_value.merge_with(other.first_value.as_ref());
+        self.always_increment |= other.always_increment;
+        self.calver_format.merge_with(other.calver_format.as_ref());
+        self.depends_on.merge_with(other.depends_on.as_ref());
+        self.width.merge_with(other.width.as_ref());
+        self.pad |= other.pad;
+        self.order.merge_with(other.order.as_ref());
+    }
 }
 
 /// Make sure all version components are included
@@ -30,6 +30,10 @@ fn base_context(
     [
         ("now".to_string(), chrono::Local::now().to_rfc3339()),
         ("utcnow".to_string(), chrono::Utc::now().to_rfc3339()),
+        (
+            "date".to_string(),
+            chrono::Local::now().format("%Y-%m-%d").to_string(),
+        ),
     ]
     .into_iter()
     .chain(prefixed_env())
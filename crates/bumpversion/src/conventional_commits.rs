@@ -0,0 +1,173 @@
+//! Bump-level detection from [Conventional Commits](https://www.conventionalcommits.org).
+//!
+//! Classifies commit messages by their type and breaking-change markers, and maps
+//! them onto the configured version components.
+use crate::config::version::VersionComponentConfigs;
+
+/// The key in the `conventional_commits` mapping used for breaking changes.
+pub const BREAKING: &str = "breaking";
+
+/// The parts of a Conventional Commit message that decide the bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConventionalCommit<'a> {
+    /// The commit type, e.g. `feat` or `fix`.
+    pub commit_type: &'a str,
+    /// The optional scope in parentheses after the type.
+    pub scope: Option<&'a str>,
+    /// The description after the colon.
+    pub description: &'a str,
+    /// Whether the commit is marked as a breaking change.
+    ///
+    /// Either by a `!` before the colon, or by a `BREAKING CHANGE` footer.
+    pub breaking: bool,
+}
+
+impl<'a> ConventionalCommit<'a> {
+    /// Parse a commit message.
+    ///
+    /// Returns `None` if the subject line is not of the form `type(scope)!: description`.
+    #[must_use]
+    pub fn parse(message: &'a str) -> Option<Self> {
+        let subject = message.lines().next()?.trim();
+        let (prefix, description) = subject.split_once(':')?;
+        let description = description.trim();
+        if description.is_empty() {
+            return None;
+        }
+        let (prefix, breaking_marker) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (commit_type, scope) = match prefix.split_once('(') {
+            Some((commit_type, scope)) => (commit_type, Some(scope.strip_suffix(')')?)),
+            None => (prefix, None),
+        };
+        let is_word = |value: &str| {
+            !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        if !is_word(commit_type) {
+            return None;
+        }
+        let breaking_footer = message.lines().skip(1).any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+        Some(Self {
+            commit_type,
+            scope,
+            description,
+            breaking: breaking_marker || breaking_footer,
+        })
+    }
+
+    /// The component this commit asks for, according to `commit_types`.
+    #[must_use]
+    pub fn component<'c>(&self, commit_types: &'c [(String, String)]) -> Option<&'c str> {
+        let lookup = |key: &str| {
+            commit_types
+                .iter()
+                .find(|(commit_type, _)| commit_type.eq_ignore_ascii_case(key))
+                .map(|(_, component)| component.as_str())
+        };
+        self.breaking
+            .then(|| lookup(BREAKING))
+            .flatten()
+            .or_else(|| lookup(self.commit_type))
+    }
+}
+
+/// The component to bump for a set of commit `messages`.
+///
+/// Each commit that parses as a Conventional Commit asks for the component mapped to its type
+/// in `commit_types`, or to [`BREAKING`] if it is a breaking change. The most significant of
+/// those wins, where significance follows the order of `components`: `major` before `minor`
+/// before `patch`.
+///
+/// Returns `None` if no commit asks for a bump.
+pub fn component_to_bump<'c>(
+    messages: impl IntoIterator<Item = impl AsRef<str>>,
+    commit_types: &'c [(String, String)],
+    components: &VersionComponentConfigs,
+) -> Option<&'c str> {
+    let significance = |component: &str| components.get_index_of(component).unwrap_or(usize::MAX);
+    messages
+        .into_iter()
+        .filter_map(|message| ConventionalCommit::parse(message.as_ref())?.component(commit_types))
+        .min_by_key(|component| significance(component))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConventionalCommit, component_to_bump};
+    use crate::config::{self, version::VersionComponentConfigs};
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+
+    fn components() -> VersionComponentConfigs {
+        let config = config::Config::default().finalize();
+        config::version::version_component_configs(&config)
+    }
+
+    /// The type, scope and both breaking-change markers all come from the message.
+    #[test]
+    fn test_parse_conventional_commit() {
+        crate::tests::init();
+        sim_assert_eq!(
+            ConventionalCommit::parse("feat(cli): add auto"),
+            Some(ConventionalCommit {
+                commit_type: "feat",
+                scope: Some("cli"),
+                description: "add auto",
+                breaking: false,
+            })
+        );
+        sim_assert_eq!(
+            ConventionalCommit::parse("fix!: drop the old flag").map(|commit| commit.breaking),
+            Some(true)
+        );
+        sim_assert_eq!(
+            ConventionalCommit::parse("refactor: rework\n\nBREAKING CHANGE: config moved")
+                .map(|commit| commit.breaking),
+            Some(true)
+        );
+        sim_assert_eq!(ConventionalCommit::parse("Merge branch 'main'"), None);
+        sim_assert_eq!(ConventionalCommit::parse("fix: "), None);
+    }
+
+    /// The most significant component any commit asks for wins, and unmapped types are ignored.
+    #[test]
+    fn test_component_to_bump_picks_most_significant() -> eyre::Result<()> {
+        crate::tests::init();
+        let commit_types = config::GlobalConfig::default()
+            .conventional_commits
+            .ok_or_else(|| eyre::eyre!("missing default conventional commit types"))?;
+        let components = components();
+
+        let bump = component_to_bump(
+            ["docs: typo", "fix: off by one"],
+            &commit_types,
+            &components,
+        );
+        sim_assert_eq!(bump, Some("patch"));
+
+        let bump = component_to_bump(
+            ["fix: off by one", "feat: add auto", "chore: tidy"],
+            &commit_types,
+            &components,
+        );
+        sim_assert_eq!(bump, Some("minor"));
+
+        let bump = component_to_bump(
+            ["feat: add auto", "fix(core)!: change the api"],
+            &commit_types,
+            &components,
+        );
+        sim_assert_eq!(bump, Some("major"));
+
+        let bump = component_to_bump(["docs: typo", "wip"], &commit_types, &components);
+        sim_assert_eq!(bump, None);
+        Ok(())
+    }
+}
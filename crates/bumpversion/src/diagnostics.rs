@@ -204,6 +204,28 @@ where
     }
 }
 
+/// The location of a configuration entry in its source file.
+///
+/// A location only exists to point diagnostics at the entry. Compare configurations with
+/// `strip_origins` in tests to ignore where they were defined.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location(Option<(FileId, usize, usize)>);
+
+impl Location {
+    /// Create a new [`Location`] of `span` in the file with `file_id`.
+    #[must_use]
+    pub fn new(file_id: FileId, span: impl Into<Span>) -> Self {
+        let span = span.into();
+        Self(Some((file_id, span.start, span.end)))
+    }
+
+    /// The file id and span of the location, if known.
+    #[must_use]
+    pub fn get(&self) -> Option<(FileId, Span)> {
+        self.0.map(|(file_id, start, end)| (file_id, start..end))
+    }
+}
+
 /// A diagnostics printer that buffers messages for later emission.
 pub type BufferedPrinter = Printer<term::termcolor::Buffer>;
 /// A diagnostics printer that writes formatted messages to stderr.
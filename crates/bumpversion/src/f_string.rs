@@ -1,10 +1,116 @@
 //! Parsing support for Python-style format strings used in version templates.
 //!
 //! Provides utilities to split format strings into literal text and argument placeholders,
-//! and to unescape double curly braces.
+//! and to unescape double curly braces. Placeholders support Python's conversions and format
+//! specs, as in `{name!r}`, `{patch:02}` or `{utcnow:%Y%m%d}`.
+//!
+//! Attribute and index access, as in `{bag[wand]}`, is not supported: values are flat, so the
+//! whole field name `bag[wand]` is looked up as is.
 pub use parser::ParseError;
 use std::collections::HashMap;
 
+/// A conversion applied to the value of a placeholder before formatting, as in `{name!r}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Conversion {
+    /// `!s`, the value unchanged.
+    Str,
+    /// `!r`, the value as a quoted Python string literal.
+    Repr,
+    /// `!a`, like `!r` but with non-ASCII characters escaped.
+    Ascii,
+}
+
+impl Conversion {
+    /// The character selecting this conversion after the `!`.
+    #[must_use]
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Str => 's',
+            Self::Repr => 'r',
+            Self::Ascii => 'a',
+        }
+    }
+
+    fn apply(self, value: &str) -> String {
+        match self {
+            Self::Str => value.to_string(),
+            Self::Repr => spec::repr(value, false),
+            Self::Ascii => spec::repr(value, true),
+        }
+    }
+}
+
+/// The placeholders holding an RFC 3339 timestamp, formatted with a `strftime`-style format spec.
+const TIMESTAMPS: [&str; 2] = ["now", "utcnow"];
+
+/// A placeholder of the form `{name!conversion:format_spec}`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Argument {
+    /// The field name the value is looked up by.
+    pub name: String,
+    /// The conversion applied to the value, if any.
+    pub conversion: Option<Conversion>,
+    /// The format spec applied to the value, if any.
+    pub format_spec: Option<String>,
+}
+
+impl std::fmt::Display for Argument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{{}", self.name)?;
+        if let Some(conversion) = self.conversion {
+            write!(f, "!{}", conversion.as_char())?;
+        }
+        if let Some(format_spec) = &self.format_spec {
+            write!(f, ":{format_spec}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Argument {
+    /// A placeholder for `name` without conversion or format spec.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            conversion: None,
+            format_spec: None,
+        }
+    }
+
+    /// Render `value` with the conversion and format spec of this placeholder.
+    ///
+    /// Without a conversion, an integer value is aligned and padded like a Python `int`, so
+    /// `{patch:02}` renders `3` as `03`, and the RFC 3339 timestamps `now` and `utcnow` take a
+    /// `strftime`-style format spec. Any other value is formatted like a Python `str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::InvalidFormatSpec`] if the format spec is not valid for `value`.
+    pub fn render(&self, value: &str) -> Result<String, FormatError> {
+        let format_spec = self.format_spec.as_deref();
+        let rendered = match (self.conversion, format_spec) {
+            (None, None) => Some(value.to_string()),
+            (Some(conversion), None) => Some(conversion.apply(value)),
+            (Some(conversion), Some(format_spec)) => spec::FormatSpec::parse(format_spec)
+                .and_then(|spec| spec.format_str(&conversion.apply(value))),
+            (None, Some(format_spec)) if TIMESTAMPS.contains(&self.name.as_str()) => {
+                chrono::DateTime::parse_from_rfc3339(value)
+                    .ok()
+                    .and_then(|timestamp| spec::strftime(&timestamp, format_spec))
+            }
+            (None, Some(format_spec)) => {
+                spec::FormatSpec::parse(format_spec).and_then(|spec| spec.format(value))
+            }
+        };
+        rendered.ok_or_else(|| FormatError::InvalidFormatSpec {
+            argument: self.name.clone(),
+            format_spec: format_spec.unwrap_or_default().to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
 /// A segment of a format string: either literal text or a placeholder.
 ///
 /// `Value::String` holds literal content, while `Value::Argument` represents `{name}`.
@@ -12,32 +118,38 @@ use std::collections::HashMap;
 pub enum Value {
     /// Literal string content.
     String(String),
-    /// Placeholder argument name.
-    Argument(String),
+    /// Placeholder argument.
+    Argument(Argument),
 }
 
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::String(s) => write!(f, "{s}"),
-            Self::Argument(arg) => write!(f, "{{{arg}}}"),
+            Self::Argument(arg) => write!(f, "{arg}"),
         }
     }
 }
 
 impl Value {
+    /// A placeholder for `name` without conversion or format spec.
+    #[must_use]
+    pub fn argument(name: impl Into<String>) -> Self {
+        Self::Argument(Argument::new(name))
+    }
+
     /// If this is an argument placeholder, return its name, otherwise `None`.
     ///
     /// # Examples
     /// ```
     /// use bumpversion::f_string::Value;
-    /// assert_eq!(Value::Argument("x".to_string()).as_argument(), Some("x"));
+    /// assert_eq!(Value::argument("x").as_argument(), Some("x"));
     /// assert_eq!(Value::String("x".to_string()).as_argument(), None);
     /// ```
     #[must_use]
     pub fn as_argument(&self) -> Option<&str> {
         match self {
-            Self::Argument(arg) => Some(arg),
+            Self::Argument(arg) => Some(&arg.name),
             Self::String(_) => None,
         }
     }
@@ -47,7 +159,7 @@ impl Value {
     /// # Examples
     /// ```
     /// use bumpversion::f_string::Value;
-    /// assert!(Value::Argument("y".to_string()).is_argument());
+    /// assert!(Value::argument("y").is_argument());
     /// assert!(!Value::String("y".to_string()).is_argument());
     /// ```
     #[must_use]
@@ -60,7 +172,11 @@ impl<'a> From<parser::Value<'a>> for Value {
     fn from(value: parser::Value<'a>) -> Self {
         match value {
             parser::Value::String(s) => Self::String(s.clone()),
-            parser::Value::Argument(s) => Self::Argument(s.to_string()),
+            parser::Value::Argument(arg) => Self::Argument(Argument {
+                name: arg.name.to_string(),
+                conversion: arg.conversion,
+                format_spec: arg.format_spec.map(str::to_string),
+            }),
         }
     }
 }
@@ -69,19 +185,43 @@ pub mod parser {
     //! Internal module implementing the parser for format strings.
     //!
     //! Users should call `escape_double_curly_braces` or `parse_format_arguments`.
-    use winnow::combinator::{alt, delimited, repeat};
+    use super::Conversion;
+    use winnow::combinator::{alt, delimited, opt, preceded, repeat};
     use winnow::error::InputError;
     use winnow::prelude::*;
 
     use winnow::token::take_while;
 
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// Parsed placeholder of the form `{name!conversion:format_spec}`.
+    pub struct Argument<'a> {
+        /// The field name, including any attribute or index access.
+        pub name: &'a str,
+        /// The conversion, if any.
+        pub conversion: Option<Conversion>,
+        /// The format spec, if any.
+        pub format_spec: Option<&'a str>,
+    }
+
     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
     /// Parsed format string segment.
     pub enum Value<'a> {
         /// Literal string content.
         String(String),
-        /// Placeholder argument name.
-        Argument(&'a str),
+        /// Placeholder argument.
+        Argument(Argument<'a>),
+    }
+
+    impl<'a> Value<'a> {
+        /// A placeholder for `name` without conversion or format spec.
+        #[must_use]
+        pub fn argument(name: &'a str) -> Self {
+            Self::Argument(Argument {
+                name,
+                conversion: None,
+                format_spec: None,
+            })
+        }
     }
 
     fn any_except_curly_bracket0<'a>(s: &mut &'a str) -> ModalResult<&'a str, InputError<&'a str>> {
@@ -111,13 +251,53 @@ pub mod parser {
         .parse_next(s)
     }
 
+    fn field_name<'a>(s: &mut &'a str) -> ModalResult<&'a str, InputError<&'a str>> {
+        repeat::<_, _, (), _, _>(
+            0..,
+            alt((
+                take_while(1.., |c| !matches!(c, '[' | '!' | ':' | '{' | '}')),
+                delimited("[", take_while(0.., |c| !matches!(c, ']' | '{' | '}')), "]"),
+            )),
+        )
+        .take()
+        .context("field_name")
+        .parse_next(s)
+    }
+
+    fn conversion<'a>(s: &mut &'a str) -> ModalResult<Conversion, InputError<&'a str>> {
+        preceded(
+            "!",
+            alt((
+                's'.value(Conversion::Str),
+                'r'.value(Conversion::Repr),
+                'a'.value(Conversion::Ascii),
+            )),
+        )
+        .context("conversion")
+        .parse_next(s)
+    }
+
     fn non_escaped_bracket_argument<'a>(
         s: &mut &'a str,
     ) -> ModalResult<Value<'a>, InputError<&'a str>> {
-        delimited("{", any_except_curly_bracket0, "}")
-            .map(Value::Argument)
-            .context("non_escaped_bracket_argument")
-            .parse_next(s)
+        delimited(
+            "{",
+            (
+                field_name,
+                opt(conversion),
+                opt(preceded(":", any_except_curly_bracket0)),
+            ),
+            "}",
+        )
+        .map(|(name, conversion, format_spec)| {
+            Value::Argument(Argument {
+                name,
+                conversion,
+                format_spec,
+            })
+        })
+        .context("non_escaped_bracket_argument")
+        .parse_next(s)
     }
 
     fn text_or_argument<'a>(s: &mut &'a str) -> ModalResult<Value<'a>, InputError<&'a str>> {
@@ -185,26 +365,58 @@ pub mod parser {
                 parse_format_arguments("this is a {test} value")?,
                 vec![
                     Value::String("this is a ".to_string()),
-                    Value::Argument("test"),
+                    Value::argument("test"),
                     Value::String(" value".to_string()),
                 ]
             );
 
             sim_assert_eq!(
                 parse_format_arguments("{jane!s}")?,
-                vec![Value::Argument("jane!s")]
+                vec![Value::Argument(Argument {
+                    name: "jane",
+                    conversion: Some(Conversion::Str),
+                    format_spec: None,
+                })]
             );
 
             sim_assert_eq!(
                 parse_format_arguments("Magic wand: {bag['wand']:^10}")?,
                 vec![
                     Value::String("Magic wand: ".to_string()),
-                    Value::Argument("bag['wand']:^10"),
+                    Value::Argument(Argument {
+                        name: "bag['wand']",
+                        conversion: None,
+                        format_spec: Some("^10"),
+                    }),
                 ]
             );
+
+            sim_assert_eq!(
+                parse_format_arguments("{utcnow:%Y-%m-%dT%H:%M:%SZ}")?,
+                vec![Value::Argument(Argument {
+                    name: "utcnow",
+                    conversion: None,
+                    format_spec: Some("%Y-%m-%dT%H:%M:%SZ"),
+                })]
+            );
+
+            sim_assert_eq!(
+                parse_format_arguments("{name!r:>10}")?,
+                vec![Value::Argument(Argument {
+                    name: "name",
+                    conversion: Some(Conversion::Repr),
+                    format_spec: Some(">10"),
+                })]
+            );
             Ok(())
         }
 
+        #[test]
+        fn rejects_invalid_arguments() {
+            assert!(parse_format_arguments("{jane!x}").is_err());
+            assert!(parse_format_arguments("{bag['wand'}").is_err());
+        }
+
         #[test]
         fn parses_version_pattern() {
             sim_assert_eq!(
@@ -212,16 +424,16 @@ pub mod parser {
                     "{major}.{minor}.{patch}.{dev}{$PR_NUMBER}.dev{distance_to_latest_tag}"
                 ),
                 Ok(vec![
-                    Value::Argument("major"),
+                    Value::argument("major"),
                     Value::String(".".to_string()),
-                    Value::Argument("minor"),
+                    Value::argument("minor"),
                     Value::String(".".to_string()),
-                    Value::Argument("patch"),
+                    Value::argument("patch"),
                     Value::String(".".to_string()),
-                    Value::Argument("dev"),
-                    Value::Argument("$PR_NUMBER"),
+                    Value::argument("dev"),
+                    Value::argument("$PR_NUMBER"),
                     Value::String(".dev".to_string()),
-                    Value::Argument("distance_to_latest_tag"),
+                    Value::argument("distance_to_latest_tag"),
                 ])
             );
         }
@@ -240,16 +452,16 @@ pub mod parser {
 
             sim_assert_eq!(
                 non_escaped_bracket_argument.parse("{test}"),
-                Ok(Value::Argument("test"))
+                Ok(Value::argument("test"))
             );
 
             sim_assert_eq!(
                 repeat(1.., text_or_argument).parse("this is a {test} for parsing {arguments}"),
                 Ok(vec![
                     Value::String("this is a ".to_string()),
-                    Value::Argument("test"),
+                    Value::argument("test"),
                     Value::String(" for parsing ".to_string()),
-                    Value::Argument("arguments"),
+                    Value::argument("arguments"),
                 ])
             );
 
@@ -260,7 +472,7 @@ pub mod parser {
 
             sim_assert_eq!(
                 non_escaped_bracket_argument.parse("{}"),
-                Ok(Value::Argument(""))
+                Ok(Value::argument(""))
             );
 
             sim_assert_eq!(
@@ -272,7 +484,7 @@ pub mod parser {
                 parse_format_arguments("this }} {{ is a {test}"),
                 Ok(vec![
                     Value::String("this } { is a ".to_string()),
-                    Value::Argument("test"),
+                    Value::argument("test"),
                 ])
             );
 
@@ -280,15 +492,238 @@ pub mod parser {
                 parse_format_arguments("this }} {{ is a {test} for parsing {arguments}"),
                 Ok(vec![
                     Value::String("this } { is a ".to_string()),
-                    Value::Argument("test"),
+                    Value::argument("test"),
                     Value::String(" for parsing ".to_string()),
-                    Value::Argument("arguments"),
+                    Value::argument("arguments"),
                 ])
             );
         }
     }
 }
 
+mod spec {
+    //! Python's format spec mini-language, applied to string values.
+    use chrono::format::{Item, StrftimeItems};
+
+    /// A parsed `[[fill]align][sign]["z"]["#"]["0"][width][grouping]["." precision][type]`.
+    #[derive(Debug, Default)]
+    pub(super) struct FormatSpec {
+        fill: Option<char>,
+        align: Option<char>,
+        sign: Option<char>,
+        coerce_negative_zero: bool,
+        alternate: bool,
+        zero: bool,
+        width: usize,
+        grouping: Option<char>,
+        precision: Option<usize>,
+        kind: Option<char>,
+    }
+
+    impl FormatSpec {
+        /// Parse `spec`, or `None` if it is not a valid format spec.
+        pub(super) fn parse(spec: &str) -> Option<Self> {
+            let is_align = |c: char| matches!(c, '<' | '>' | '=' | '^');
+            let mut parsed = Self::default();
+            let mut chars = spec.chars().peekable();
+            let mut lookahead = spec.chars();
+            match (lookahead.next(), lookahead.next()) {
+                (Some(fill), Some(align)) if is_align(align) => {
+                    parsed.fill = Some(fill);
+                    parsed.align = Some(align);
+                    chars.nth(1);
+                }
+                (Some(align), _) if is_align(align) => {
+                    parsed.align = Some(align);
+                    chars.next();
+                }
+                _ => {}
+            }
+            parsed.sign = chars.next_if(|c| matches!(c, '+' | '-' | ' '));
+            parsed.coerce_negative_zero = chars.next_if_eq(&'z').is_some();
+            parsed.alternate = chars.next_if_eq(&'#').is_some();
+            parsed.zero = chars.next_if_eq(&'0').is_some();
+            let width: String =
+                std::iter::from_fn(|| chars.next_if(char::is_ascii_digit)).collect();
+            if !width.is_empty() {
+                parsed.width = width.parse().ok()?;
+            }
+            parsed.grouping = chars.next_if(|c| matches!(c, ',' | '_'));
+            if chars.next_if_eq(&'.').is_some() {
+                let precision: String =
+                    std::iter::from_fn(|| chars.next_if(char::is_ascii_digit)).collect();
+                parsed.precision = Some(precision.parse().ok()?);
+            }
+            parsed.kind = chars.next_if(|c| "bcdeEfFgGnosxX%".contains(*c));
+            match chars.next() {
+                Some(_) => None,
+                None => Some(parsed),
+            }
+        }
+
+        /// Format `value` like a Python `int` if it is an integer, otherwise like a `str`.
+        pub(super) fn format(&self, value: &str) -> Option<String> {
+            let digits = value.strip_prefix('-').unwrap_or(value);
+            let is_integer = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+            if is_integer && self.kind != Some('s') {
+                self.format_int(value)
+            } else {
+                self.format_str(value)
+            }
+        }
+
+        /// Format `value` like a Python `str`, or `None` if the spec only applies to numbers.
+        pub(super) fn format_str(&self, value: &str) -> Option<String> {
+            if self.sign.is_some()
+                || self.coerce_negative_zero
+                || self.alternate
+                || self.grouping.is_some()
+                || self.align == Some('=')
+                || !matches!(self.kind, None | Some('s'))
+            {
+                return None;
+            }
+            let value: String = match self.precision {
+                Some(precision) => value.chars().take(precision).collect(),
+                None => value.to_string(),
+            };
+            let fill = self.fill.unwrap_or(if self.zero { '0' } else { ' ' });
+            Some(pad("", &value, self.width, fill, self.align.unwrap_or('<')))
+        }
+
+        /// Format the integer `value` like a Python `int`, keeping any leading zeros of a
+        /// decimal value.
+        fn format_int(&self, value: &str) -> Option<String> {
+            if self.precision.is_some() || self.coerce_negative_zero {
+                return None;
+            }
+            let (negative, digits) = match value.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, value),
+            };
+            let radix = |format: fn(u128) -> String| digits.parse::<u128>().ok().map(format);
+            let (mut digits, prefix, group_size) = match self.kind {
+                None | Some('d') => (digits.to_string(), "", 3),
+                Some('n') if self.grouping.is_none() => (digits.to_string(), "", 3),
+                Some('b') => (radix(|n| format!("{n:b}"))?, "0b", 4),
+                Some('o') => (radix(|n| format!("{n:o}"))?, "0o", 4),
+                Some('x') => (radix(|n| format!("{n:x}"))?, "0x", 4),
+                Some('X') => (radix(|n| format!("{n:X}"))?, "0X", 4),
+                _ => return None,
+            };
+            if self.grouping == Some(',') && group_size != 3 {
+                return None;
+            }
+            let sign = match (negative, self.sign) {
+                (true, _) => "-",
+                (false, Some('+')) => "+",
+                (false, Some(' ')) => " ",
+                (false, _) => "",
+            };
+            let prefix = format!("{sign}{}", if self.alternate { prefix } else { "" });
+            let group = |digits: &str| match self.grouping {
+                Some(separator) => group_digits(digits, separator, group_size),
+                None => digits.to_string(),
+            };
+            if self.zero && self.fill.is_none() && self.align.is_none() {
+                // zero padding extends the digits, so it is grouped like them
+                let mut grouped = group(&digits);
+                while prefix.len() + grouped.chars().count() < self.width {
+                    digits.insert(0, '0');
+                    grouped = group(&digits);
+                }
+                return Some(format!("{prefix}{grouped}"));
+            }
+            let fill = self.fill.unwrap_or(if self.zero { '0' } else { ' ' });
+            Some(pad(
+                &prefix,
+                &group(&digits),
+                self.width,
+                fill,
+                self.align.unwrap_or('>'),
+            ))
+        }
+    }
+
+    /// Separate `digits` into groups of `size` from the right.
+    fn group_digits(digits: &str, separator: char, size: usize) -> String {
+        let count = digits.chars().count();
+        let mut grouped = String::new();
+        for (idx, c) in digits.chars().enumerate() {
+            if idx > 0 && (count - idx).is_multiple_of(size) {
+                grouped.push(separator);
+            }
+            grouped.push(c);
+        }
+        grouped
+    }
+
+    /// Pad `prefix` followed by `body` with `fill` to `width` characters.
+    fn pad(prefix: &str, body: &str, width: usize, fill: char, align: char) -> String {
+        let padding = width.saturating_sub(prefix.chars().count() + body.chars().count());
+        let fill = |count: usize| fill.to_string().repeat(count);
+        match align {
+            '<' => format!("{prefix}{body}{}", fill(padding)),
+            '^' => format!(
+                "{}{prefix}{body}{}",
+                fill(padding / 2),
+                fill(padding - padding / 2)
+            ),
+            '=' => format!("{prefix}{}{body}", fill(padding)),
+            _ => format!("{}{prefix}{body}", fill(padding)),
+        }
+    }
+
+    /// Format `timestamp` with the `strftime`-style `format`, or `None` if it is invalid.
+    pub(super) fn strftime(
+        timestamp: &chrono::DateTime<chrono::FixedOffset>,
+        format: &str,
+    ) -> Option<String> {
+        let items: Vec<Item<'_>> = StrftimeItems::new(format).collect();
+        if items.contains(&Item::Error) {
+            return None;
+        }
+        Some(timestamp.format_with_items(items.iter()).to_string())
+    }
+
+    /// Quote `value` like Python's `repr` of a `str`, escaping non-ASCII characters if
+    /// `ascii` is set.
+    pub(super) fn repr(value: &str, ascii: bool) -> String {
+        let quote = if value.contains('\'') && !value.contains('"') {
+            '"'
+        } else {
+            '\''
+        };
+        let mut quoted = String::from(quote);
+        for c in value.chars() {
+            match c {
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                c if c == quote => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                c if c.is_control() || (ascii && !c.is_ascii()) => {
+                    let code = u32::from(c);
+                    let escaped = if code < 0x100 {
+                        format!("\\x{code:02x}")
+                    } else if code < 0x10000 {
+                        format!("\\u{code:04x}")
+                    } else {
+                        format!("\\U{code:08x}")
+                    };
+                    quoted.push_str(&escaped);
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push(quote);
+        quoted
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// A parsed Python-style format string.
 ///
@@ -341,9 +776,22 @@ impl std::str::FromStr for PythonFormatString {
 }
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq, PartialOrd, Hash)]
-#[error("missing argument {0:?}")]
-/// Error returned when a required placeholder is missing during formatting.
-pub struct MissingArgumentError(String);
+/// Error returned when a format string cannot be formatted with the given values.
+pub enum FormatError {
+    /// A required placeholder has no value.
+    #[error("missing argument {0:?}")]
+    MissingArgument(String),
+    /// The format spec of a placeholder is invalid, or cannot format its value.
+    #[error("cannot format {value:?} of argument {argument:?} with format spec {format_spec:?}")]
+    InvalidFormatSpec {
+        /// Name of the placeholder.
+        argument: String,
+        /// The offending format spec.
+        format_spec: String,
+        /// The value of the placeholder.
+        value: String,
+    },
+}
 
 impl PythonFormatString {
     /// Parse a Python-style format string.
@@ -362,13 +810,10 @@ impl PythonFormatString {
     ///
     /// # Errors
     ///
-    /// Returns [`MissingArgumentError`] when `strict` is `true` and a placeholder has no
-    /// corresponding value.
-    pub fn format<K, V>(
-        &self,
-        values: &HashMap<K, V>,
-        strict: bool,
-    ) -> Result<String, MissingArgumentError>
+    /// Returns [`FormatError::MissingArgument`] when `strict` is `true` and a placeholder has
+    /// no corresponding value, and [`FormatError::InvalidFormatSpec`] when the format spec of a
+    /// placeholder cannot format its value.
+    pub fn format<K, V>(&self, values: &HashMap<K, V>, strict: bool) -> Result<String, FormatError>
     where
         K: std::borrow::Borrow<str>,
         K: std::hash::Hash + Eq,
@@ -376,28 +821,11 @@ impl PythonFormatString {
     {
         self.0.iter().try_fold(String::new(), |mut acc, value| {
             let value = match value {
-                Value::Argument(arg) => {
-                    let as_timestamp = || {
-                        // try to parse as timestamp of format "utcnow:%Y-%m-%dT%H:%M:%SZ"
-                        arg.split_once(':').and_then(|(arg, format)| {
-                            values.get(arg).and_then(|value| {
-                                let timestamp =
-                                    chrono::DateTime::parse_from_rfc3339(value.as_ref()).ok()?;
-                                Some(timestamp.format(format).to_string())
-                            })
-                        })
-                    };
-                    let value = values
-                        .get(arg)
-                        .map(|value| value.as_ref().to_string())
-                        .or_else(as_timestamp);
-
-                    match value {
-                        Some(value) => Ok(value),
-                        None if strict => Err(MissingArgumentError(arg.clone())),
-                        None => Ok(String::new()),
-                    }
-                }
+                Value::Argument(arg) => match values.get(arg.name.as_str()) {
+                    Some(value) => arg.render(value.as_ref()),
+                    None if strict => Err(FormatError::MissingArgument(arg.name.clone())),
+                    None => Ok(String::new()),
+                },
                 Value::String(s) => Ok(s.clone()),
             }?;
             acc.push_str(&value);
@@ -405,6 +833,23 @@ impl PythonFormatString {
         })
     }
 
+    /// Replace every placeholder named `name` with `value`, rendered with the placeholder's
+    /// conversion and format spec, keeping all others.
+    ///
+    /// A placeholder whose format spec cannot format `value` is kept.
+    #[must_use]
+    pub fn with_argument(&self, name: &str, value: &str) -> Self {
+        self.0
+            .iter()
+            .map(|segment| match segment {
+                Value::Argument(arg) if arg.name == name => arg
+                    .render(value)
+                    .map_or_else(|_| segment.clone(), Value::String),
+                other => other.clone(),
+            })
+            .collect()
+    }
+
     /// Iterate over all placeholder argument names in this format string.
     pub fn named_arguments(&self) -> impl Iterator<Item = &str> {
         self.0.iter().filter_map(|value| value.as_argument())
@@ -418,7 +863,7 @@ impl PythonFormatString {
 
 #[cfg(test)]
 mod tests {
-    use super::{PythonFormatString, Value};
+    use super::{FormatError, PythonFormatString, Value};
     use color_eyre::eyre;
     use similar_asserts::assert_eq as sim_assert_eq;
     use std::collections::HashMap;
@@ -431,7 +876,7 @@ mod tests {
             fstring.as_ref().as_slice(),
             [
                 Value::String("this is a formatted ".to_string()),
-                Value::Argument("value".to_string()),
+                Value::argument("value"),
                 Value::String("!".to_string()),
             ]
         );
@@ -459,7 +904,7 @@ mod tests {
             fstring.iter().collect::<Vec<_>>(),
             vec![
                 &Value::String("this is a formatted ".to_string()),
-                &Value::Argument("value".to_string()),
+                &Value::argument("value"),
                 &Value::String("!".to_string()),
             ]
         );
@@ -474,9 +919,9 @@ mod tests {
             fstring.as_ref().as_slice(),
             [
                 Value::String("this is a formatted ".to_string()),
-                Value::Argument("$value1".to_string()),
+                Value::argument("$value1"),
                 Value::String(", and ".to_string()),
-                Value::Argument("another1".to_string()),
+                Value::argument("another1"),
                 Value::String("!".to_string()),
             ]
         );
@@ -508,9 +953,9 @@ mod tests {
             fstring.as_ref().as_slice(),
             [
                 Value::String("this is a formatted ".to_string()),
-                Value::Argument("$value1".to_string()),
+                Value::argument("$value1"),
                 Value::String(", and ".to_string()),
-                Value::Argument("another1".to_string()),
+                Value::argument("another1"),
                 Value::String("!".to_string()),
             ]
         );
@@ -542,9 +987,9 @@ mod tests {
             fstring.as_ref().as_slice(),
             [
                 Value::String("this is a formatted ".to_string()),
-                Value::Argument("$value1".to_string()),
+                Value::argument("$value1"),
                 Value::String(", and ".to_string()),
-                Value::Argument("another1".to_string()),
+                Value::argument("another1"),
                 Value::String("!".to_string()),
             ]
         );
@@ -561,7 +1006,7 @@ mod tests {
                 .collect::<HashMap<&str, &str>>(),
                 strict
             ),
-            Err(super::MissingArgumentError("$value1".to_string())),
+            Err(FormatError::MissingArgument("$value1".to_string())),
         );
         Ok(())
     }
@@ -574,4 +1019,135 @@ mod tests {
         sim_assert_eq!(&fstring.to_string(), raw_fstring);
         Ok(())
     }
+
+    #[test]
+    fn f_string_display_with_conversion_and_format_spec() -> eyre::Result<()> {
+        crate::tests::init();
+        let raw_fstring = "{name!r:>10} at {utcnow:%Y-%m-%dT%H:%M:%SZ} with {bag['wand']:^10}";
+        let fstring = PythonFormatString::parse(raw_fstring)?;
+        sim_assert_eq!(&fstring.to_string(), raw_fstring);
+        sim_assert_eq!(
+            fstring.named_arguments().collect::<Vec<_>>(),
+            ["name", "utcnow", "bag['wand']"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_spec_matches_python() -> eyre::Result<()> {
+        crate::tests::init();
+        let cases = [
+            ("{patch:02}", "3", "03"),
+            ("{patch:03}", "12", "012"),
+            ("{patch:>4}", "3", "   3"),
+            ("{patch:<4}", "3", "3   "),
+            ("{patch:*^7}", "3", "***3***"),
+            ("{patch:+}", "3", "+3"),
+            ("{patch:05}", "-3", "-0003"),
+            ("{patch:,}", "1234567", "1,234,567"),
+            ("{patch:08,}", "1234", "0,001,234"),
+            ("{patch:#x}", "255", "0xff"),
+            ("{patch:#06b}", "5", "0b0101"),
+            ("{patch:_x}", "11259375", "ab_cdef"),
+            ("{patch:}", "3", "3"),
+            ("{name:>6}", "rc", "    rc"),
+            ("{name:^6}", "rc", "  rc  "),
+            ("{name:-<6}", "rc", "rc----"),
+            ("{name:.3}", "alpha", "alp"),
+            ("{name:02}", "a", "a0"),
+            ("{name!r}", "rc", "'rc'"),
+            ("{name!r}", "it's", "\"it's\""),
+            ("{name!a}", "ü", "'\\xfc'"),
+            ("{name!r:>6}", "rc", "  'rc'"),
+            ("{name!s:02}", "3", "30"),
+        ];
+        for (template, value, expected) in cases {
+            let values: HashMap<&str, &str> =
+                [("patch", value), ("name", value)].into_iter().collect();
+            sim_assert_eq!(
+                PythonFormatString::parse(template)?
+                    .format(&values, true)
+                    .as_deref(),
+                Ok(expected),
+                "{template} with {value:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn format_spec_formats_timestamps() -> eyre::Result<()> {
+        crate::tests::init();
+        let values: HashMap<&str, &str> = [("utcnow", "2024-03-05T14:07:09+00:00")]
+            .into_iter()
+            .collect();
+        sim_assert_eq!(
+            PythonFormatString::parse("{utcnow:%Y%m%d}.{utcnow:%H:%M:%S}")?
+                .format(&values, true)
+                .as_deref(),
+            Ok("20240305.14:07:09")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_spec_formats_only_now_as_timestamp() -> eyre::Result<()> {
+        crate::tests::init();
+        let values: HashMap<&str, &str> = [("build", "2024-03-05T14:07:09+00:00")]
+            .into_iter()
+            .collect();
+        sim_assert_eq!(
+            PythonFormatString::parse("{build:>26}")?
+                .format(&values, true)
+                .as_deref(),
+            Ok(" 2024-03-05T14:07:09+00:00")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_spec_rejects_invalid_specs() -> eyre::Result<()> {
+        crate::tests::init();
+        for (template, value) in [
+            ("{name:+}", "rc"),
+            ("{name:,}", "rc"),
+            ("{name:d}", "rc"),
+            ("{patch:.2}", "3"),
+            ("{patch:q}", "3"),
+            ("{patch:10.}", "3"),
+            ("{utcnow:%Q}", "2024-03-05T14:07:09+00:00"),
+        ] {
+            let values: HashMap<&str, &str> =
+                [("name", value), ("patch", value), ("utcnow", value)]
+                    .into_iter()
+                    .collect();
+            let argument = template
+                .trim_matches(['{', '}'])
+                .split_once(':')
+                .map(|(name, format_spec)| (name.to_string(), format_spec.to_string()));
+            let Some((argument, format_spec)) = argument else {
+                eyre::bail!("invalid test case {template}");
+            };
+            sim_assert_eq!(
+                PythonFormatString::parse(template)?.format(&values, true),
+                Err(FormatError::InvalidFormatSpec {
+                    argument,
+                    format_spec,
+                    value: value.to_string(),
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn with_argument_applies_format_spec() -> eyre::Result<()> {
+        crate::tests::init();
+        let fstring = PythonFormatString::parse("{package:>5}-v{new_version}")?;
+        sim_assert_eq!(
+            fstring.with_argument("package", "cli").to_string(),
+            "  cli-v{new_version}"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,448 @@
+//! Cargo workspace replacement.
+//!
+//! Updates the version of every crate in a Cargo workspace: the `package.version` of each member
+//! (or the `workspace.package.version` members inherit with `version.workspace = true`), the
+//! `version` requirement of `path` dependencies on sibling crates, and the `[[package]]` entries
+//! of the workspace crates in `Cargo.lock`. All edits go through `toml_edit`, so formatting and
+//! comments are preserved.
+use super::IoError;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// File name of a Cargo manifest.
+pub const MANIFEST: &str = "Cargo.toml";
+
+/// File name of a Cargo lockfile.
+pub const LOCKFILE: &str = "Cargo.lock";
+
+/// Tables holding the dependencies of a manifest.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Errors that can occur when resolving or updating a Cargo workspace.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// A manifest or lockfile could not be read.
+    #[error(transparent)]
+    Io(#[from] IoError),
+    /// A manifest or lockfile could not be parsed.
+    #[error("invalid TOML in {path:?}")]
+    Toml {
+        /// Path of the file.
+        path: PathBuf,
+        /// The parse error.
+        #[source]
+        source: toml_edit::TomlError,
+    },
+    /// A workspace member pattern is invalid.
+    #[error(transparent)]
+    Glob(#[from] super::GlobError),
+    /// A member manifest has no `package.name`.
+    #[error("{path:?} has no `package.name`")]
+    MissingPackageName {
+        /// Path of the member manifest.
+        path: PathBuf,
+    },
+}
+
+/// The files and crates of a Cargo workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// The root manifest, followed by the manifests of the members.
+    pub manifests: Vec<PathBuf>,
+    /// The lockfile next to the root manifest, if there is one.
+    pub lockfile: Option<PathBuf>,
+    /// Names of the workspace crates, sorted.
+    pub crates: Vec<String>,
+}
+
+fn parse(path: &Path, contents: &str) -> Result<toml_edit::DocumentMut, Error> {
+    contents
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|source| Error::Toml {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn read(path: &Path) -> Result<toml_edit::DocumentMut, Error> {
+    let contents = std::fs::read_to_string(path).map_err(|source| IoError::new(source, path))?;
+    parse(path, &contents)
+}
+
+/// The strings in the array at `key` of `table`.
+fn strings<'a>(table: Option<&'a toml_edit::Item>, key: &str) -> Vec<&'a str> {
+    table
+        .and_then(|table| table.get(key))
+        .and_then(toml_edit::Item::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml_edit::Value::as_str)
+        .collect()
+}
+
+impl Workspace {
+    /// Resolve the workspace of the root manifest at `manifest_path`.
+    ///
+    /// The members are the directories matched by `workspace.members`, minus the ones in
+    /// `workspace.exclude`. A root manifest with a `[package]` is a member itself, and a manifest
+    /// without a `[workspace]` is a workspace of just its own crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if a manifest cannot be read or parsed, a member pattern is invalid, or a
+    /// member has no package name.
+    pub fn resolve(manifest_path: &Path) -> Result<Self, Error> {
+        let root = manifest_path.parent().unwrap_or(Path::new(""));
+        let document = read(manifest_path)?;
+        let workspace = document.get("workspace");
+
+        let excluded: BTreeSet<PathBuf> = strings(workspace, "exclude")
+            .into_iter()
+            .map(|path| root.join(path))
+            .collect();
+        let mut member_dirs = BTreeSet::new();
+        for pattern in strings(workspace, "members") {
+            let pattern = root.join(pattern);
+            let matches = glob::glob(&pattern.to_string_lossy()).map_err(super::GlobError::from)?;
+            for dir in matches {
+                let dir = dir.map_err(super::GlobError::from)?;
+                if !excluded.contains(&dir) && dir.join(MANIFEST).is_file() {
+                    member_dirs.insert(dir);
+                }
+            }
+        }
+
+        let mut manifests = vec![manifest_path.to_path_buf()];
+        let mut crates = BTreeSet::new();
+        if let Some(name) = document.get("package").map(|package| package.get("name")) {
+            let name = name.and_then(toml_edit::Item::as_str).ok_or_else(|| {
+                Error::MissingPackageName {
+                    path: manifest_path.to_path_buf(),
+                }
+            })?;
+            crates.insert(name.to_string());
+        }
+        for dir in member_dirs {
+            let path = dir.join(MANIFEST);
+            if path == manifest_path {
+                continue;
+            }
+            let member = read(&path)?;
+            let name = member
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(toml_edit::Item::as_str)
+                .ok_or_else(|| Error::MissingPackageName { path: path.clone() })?;
+            crates.insert(name.to_string());
+            manifests.push(path);
+        }
+
+        let lockfile = Some(root.join(LOCKFILE)).filter(|path| path.is_file());
+        Ok(Self {
+            manifests,
+            lockfile,
+            crates: crates.into_iter().collect(),
+        })
+    }
+}
+
+/// Replace `search` with `replacement` in the string `value`, keeping its decor.
+///
+/// Returns the number of replaced matches.
+fn replace_string(
+    value: Option<&mut toml_edit::Item>,
+    search: &regex::Regex,
+    replacement: &str,
+) -> usize {
+    let Some(toml_edit::Value::String(before)) = value.and_then(toml_edit::Item::as_value_mut)
+    else {
+        return 0;
+    };
+    let matches = search.find_iter(before.value()).count();
+    if matches > 0 {
+        let decor = before.decor().clone();
+        *before = toml_edit::Formatted::new(search.replace_all(before.value(), replacement).into());
+        *before.decor_mut() = decor;
+    }
+    matches
+}
+
+/// Replace the version requirements of the `path` dependencies on `crates` in `dependencies`.
+fn replace_requirements(
+    dependencies: Option<&mut toml_edit::Item>,
+    crates: &[String],
+    search: &regex::Regex,
+    replacement: &str,
+) -> usize {
+    let Some(dependencies) = dependencies.and_then(toml_edit::Item::as_table_like_mut) else {
+        return 0;
+    };
+    let mut matches = 0;
+    for (key, dependency) in dependencies.iter_mut() {
+        let Some(dependency) = dependency.as_table_like_mut() else {
+            continue;
+        };
+        let name = dependency
+            .get("package")
+            .and_then(toml_edit::Item::as_str)
+            .unwrap_or(key.get());
+        if dependency.contains_key("path") && crates.iter().any(|c| c == name) {
+            matches += replace_string(dependency.get_mut("version"), search, replacement);
+        }
+    }
+    matches
+}
+
+/// Replace the versions in the manifest `document`.
+fn replace_manifest(
+    document: &mut toml_edit::DocumentMut,
+    crates: &[String],
+    search: &regex::Regex,
+    replacement: &str,
+) -> usize {
+    let mut matches = 0;
+    if let Some(package) = document.get_mut("package") {
+        matches += replace_string(package.get_mut("version"), search, replacement);
+    }
+    if let Some(workspace) = document.get_mut("workspace") {
+        if let Some(package) = workspace.get_mut("package") {
+            matches += replace_string(package.get_mut("version"), search, replacement);
+        }
+        let dependencies = workspace.get_mut("dependencies");
+        matches += replace_requirements(dependencies, crates, search, replacement);
+    }
+    for table in DEPENDENCY_TABLES {
+        matches += replace_requirements(document.get_mut(table), crates, search, replacement);
+    }
+    let targets = document
+        .get_mut("target")
+        .and_then(toml_edit::Item::as_table_like_mut);
+    for (_, target) in targets.into_iter().flat_map(toml_edit::TableLike::iter_mut) {
+        for table in DEPENDENCY_TABLES {
+            matches += replace_requirements(target.get_mut(table), crates, search, replacement);
+        }
+    }
+    matches
+}
+
+/// Replace the versions of the `[[package]]` entries of `crates` in the lockfile `document`.
+///
+/// Packages from a registry or git have a `source` and are never workspace crates.
+fn replace_lockfile(
+    document: &mut toml_edit::DocumentMut,
+    crates: &[String],
+    search: &regex::Regex,
+    replacement: &str,
+) -> usize {
+    let packages = document
+        .get_mut("package")
+        .and_then(toml_edit::Item::as_array_of_tables_mut);
+    packages
+        .into_iter()
+        .flat_map(toml_edit::ArrayOfTables::iter_mut)
+        .filter(|package| {
+            !package.contains_key("source")
+                && package
+                    .get("name")
+                    .and_then(toml_edit::Item::as_str)
+                    .is_some_and(|name| crates.iter().any(|c| c == name))
+        })
+        .map(|package| replace_string(package.get_mut("version"), search, replacement))
+        .sum()
+}
+
+/// Replace `search` with `replacement` in the versions of the workspace `crates` in the manifest
+/// or lockfile at `path`.
+///
+/// Returns the updated contents together with the number of replaced matches.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the contents cannot be parsed.
+pub fn replace_versions(
+    path: &Path,
+    contents: &str,
+    crates: &[String],
+    search: &regex::Regex,
+    replacement: &str,
+) -> Result<(String, usize), Error> {
+    let mut document = parse(path, contents)?;
+    let matches = if path.file_name().is_some_and(|name| name == LOCKFILE) {
+        replace_lockfile(&mut document, crates, search, replacement)
+    } else {
+        replace_manifest(&mut document, crates, search, replacement)
+    };
+    if matches == 0 {
+        return Ok((contents.to_string(), 0));
+    }
+    Ok((document.to_string(), matches))
+}
+
+/// Returns `true` if the manifest at `path` sets a version of its own.
+///
+/// A lockfile, a member inheriting `version.workspace = true` and a virtual manifest without a
+/// `workspace.package.version` do not, so not finding the version in them is not an error.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the contents cannot be parsed.
+pub fn declares_version(path: &Path, contents: &str) -> Result<bool, Error> {
+    if path.file_name().is_some_and(|name| name == LOCKFILE) {
+        return Ok(false);
+    }
+    let document = parse(path, contents)?;
+    let version = |key_path: &[&str]| {
+        key_path
+            .iter()
+            .try_fold(document.as_item(), |item, key| item.get(key))
+            .and_then(toml_edit::Item::as_str)
+            .is_some()
+    };
+    Ok(version(&["package", "version"]) || version(&["workspace", "package", "version"]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Workspace, replace_versions};
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+    use std::path::Path;
+
+    fn replace(path: &str, contents: &str, crates: &[&str]) -> eyre::Result<(String, usize)> {
+        let search = regex::Regex::new(r"1\.2\.3")?;
+        let crates: Vec<String> = crates.iter().map(ToString::to_string).collect();
+        Ok(replace_versions(
+            Path::new(path),
+            contents,
+            &crates,
+            &search,
+            "1.3.0",
+        )?)
+    }
+
+    #[test]
+    fn test_replace_manifest_versions() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            [package]
+            name = "app"
+            version = "1.2.3" # the version
+
+            [dependencies]
+            core = { path = "../core", version = "=1.2.3" }
+            renamed = { package = "macros", path = "../macros", version = "1.2.3" }
+            serde = { version = "1.2.3" }
+
+            [dev-dependencies.core]
+            path = "../core"
+            version = "1.2.3"
+
+            [target.'cfg(unix)'.build-dependencies]
+            core = { path = "../core", version = "1.2.3" }
+        "#};
+        let (after, matches) = replace("app/Cargo.toml", contents, &["core", "macros"])?;
+        sim_assert_eq!(matches, 5);
+        sim_assert_eq!(
+            after,
+            contents.replace("1.2.3", "1.3.0").replace(
+                r#"serde = { version = "1.3.0" }"#,
+                r#"serde = { version = "1.2.3" }"#
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_workspace_versions() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.package]
+            version = "1.2.3"
+
+            [workspace.dependencies]
+            core = { path = "crates/core", version = "1.2.3" }
+            tokio = "1.2.3"
+        "#};
+        let (after, matches) = replace("Cargo.toml", contents, &["core"])?;
+        sim_assert_eq!(matches, 2);
+        sim_assert_eq!(
+            after,
+            contents
+                .replace(r#"version = "1.2.3""#, r#"version = "1.3.0""#)
+                .replace(r#"tokio = "1.3.0""#, r#"tokio = "1.2.3""#)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_lockfile_versions() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "1.2.3"
+            dependencies = [
+             "core",
+            ]
+
+            [[package]]
+            name = "core"
+            version = "1.2.3"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+        "#};
+        let (after, matches) = replace("Cargo.lock", contents, &["app", "core"])?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replacen(r#"version = "1.2.3""#, r#"version = "1.3.0""#, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_workspace() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            indoc::indoc! {r#"
+                [workspace]
+                members = ["crates/*"]
+                exclude = ["crates/scratch"]
+            "#},
+        )?;
+        for name in ["core", "app", "scratch"] {
+            std::fs::create_dir_all(root.join("crates").join(name))?;
+            std::fs::write(
+                root.join("crates").join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{name}\"\nversion.workspace = true\n"),
+            )?;
+        }
+        std::fs::write(root.join("Cargo.lock"), "version = 4\n")?;
+
+        let workspace = Workspace::resolve(&root.join("Cargo.toml"))?;
+        sim_assert_eq!(
+            workspace,
+            Workspace {
+                manifests: vec![
+                    root.join("Cargo.toml"),
+                    root.join("crates/app/Cargo.toml"),
+                    root.join("crates/core/Cargo.toml"),
+                ],
+                lockfile: Some(root.join("Cargo.lock")),
+                crates: vec!["app".to_string(), "core".to_string()],
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,770 @@
+//! Structured data-file replacement.
+//!
+//! Updates the single value at a `key_path` in a TOML, JSON, YAML or INI file instead of
+//! searching the whole file. Everything outside of that value is preserved byte for byte.
+use std::path::{Path, PathBuf};
+
+/// Data file formats that support replacing the value at a key path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Format {
+    /// A TOML file, edited with `toml_edit`.
+    Toml,
+    /// A JSON file.
+    Json,
+    /// A YAML file.
+    Yaml,
+    /// An INI file, addressed as `section.key`.
+    Ini,
+}
+
+impl Format {
+    /// Detect the format of a data file from the extension of `path`.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "ini" | "cfg" => Some(Self::Ini),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur when replacing a value in a data file.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The file is not in a format that supports `key_path`.
+    #[error("{path:?} is not a TOML, JSON, YAML or INI file and does not support `key_path`")]
+    UnsupportedFormat {
+        /// Path of the file.
+        path: PathBuf,
+    },
+    /// The TOML file could not be parsed.
+    #[error(transparent)]
+    Toml(#[from] toml_edit::TomlError),
+    /// The JSON file could not be parsed.
+    #[error("invalid JSON at byte {offset}: {message}")]
+    Json {
+        /// Description of the problem.
+        message: String,
+        /// Byte offset of the problem.
+        offset: usize,
+    },
+    /// The YAML file could not be parsed.
+    #[error("invalid YAML at byte {offset}: {message}")]
+    Yaml {
+        /// Description of the problem.
+        message: String,
+        /// Byte offset of the problem.
+        offset: usize,
+    },
+    /// The value at the key path cannot be replaced without changing anything else.
+    #[error("the value at `{key_path}` {reason} and cannot be replaced")]
+    UnsupportedValue {
+        /// The dotted key path.
+        key_path: String,
+        /// Why the value is not supported.
+        reason: String,
+    },
+}
+
+/// Replace `search` with `replacement` inside the value at `key_path`.
+///
+/// Returns the updated contents together with the number of replaced matches. A key path that
+/// does not exist, or does not point at a scalar value, results in no replacements.
+///
+/// # Errors
+///
+/// Returns [`Error`] if the contents cannot be parsed in the given `format`, or if the value at
+/// `key_path` cannot be edited in place, such as a JSON number or a YAML block scalar.
+pub fn replace_value(
+    format: Format,
+    contents: &str,
+    key_path: &[&str],
+    search: &regex::Regex,
+    replacement: &str,
+) -> Result<(String, usize), Error> {
+    let span = match format {
+        Format::Toml => return replace_toml_value(contents, key_path, search, replacement),
+        Format::Json => json::find(contents, key_path)?,
+        Format::Yaml => yaml::find(contents, key_path)?,
+        Format::Ini => ini::find(contents, key_path),
+    };
+    let Some((before, value, after)) = span.and_then(|span| {
+        Some((
+            contents.get(..span.start)?,
+            contents.get(span.clone())?,
+            contents.get(span.end..)?,
+        ))
+    }) else {
+        return Ok((contents.to_string(), 0));
+    };
+
+    let matches = search.find_iter(value).count();
+    if matches == 0 {
+        return Ok((contents.to_string(), 0));
+    }
+    let replaced = search.replace_all(value, replacement);
+    Ok(([before, replaced.as_ref(), after].concat(), matches))
+}
+
+fn replace_toml_value(
+    contents: &str,
+    key_path: &[&str],
+    search: &regex::Regex,
+    replacement: &str,
+) -> Result<(String, usize), Error> {
+    let mut document = contents.parse::<toml_edit::DocumentMut>()?;
+    let matches = key_path
+        .iter()
+        .try_fold(document.as_item(), |item, key| item.get(key))
+        .and_then(toml_edit::Item::as_str)
+        .map_or(0, |value| search.find_iter(value).count());
+    if matches == 0 {
+        return Ok((contents.to_string(), 0));
+    }
+    crate::config::toml::replace_version_of_document(&mut document, key_path, search, replacement);
+    Ok((document.to_string(), matches))
+}
+
+mod json {
+    use super::Error;
+    use std::{borrow::Cow, ops::Range};
+
+    struct Parser<'a> {
+        source: &'a str,
+        key_path: &'a [&'a str],
+        pos: usize,
+    }
+
+    impl Parser<'_> {
+        fn error(&self, message: &str) -> Error {
+            Error::Json {
+                message: message.to_string(),
+                offset: self.pos,
+            }
+        }
+
+        fn peek(&self) -> Option<u8> {
+            self.source.as_bytes().get(self.pos).copied()
+        }
+
+        fn skip_whitespace(&mut self) {
+            while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+        }
+
+        fn expect(&mut self, expected: u8) -> Result<(), Error> {
+            self.skip_whitespace();
+            if self.peek() == Some(expected) {
+                self.pos += 1;
+                Ok(())
+            } else {
+                Err(self.error(&format!("expected `{}`", char::from(expected))))
+            }
+        }
+
+        /// Parse a string and return the span of its contents, without the quotes.
+        fn string(&mut self) -> Result<Range<usize>, Error> {
+            self.expect(b'"')?;
+            let start = self.pos;
+            loop {
+                match self.peek() {
+                    None => return Err(self.error("unterminated string")),
+                    Some(b'"') => {
+                        let span = start..self.pos;
+                        self.pos += 1;
+                        return Ok(span);
+                    }
+                    Some(b'\\') => self.pos += 2,
+                    Some(_) => self.pos += 1,
+                }
+            }
+        }
+
+        /// The contents of the string at `span`, with its escape sequences resolved.
+        fn unescape(&self, span: Range<usize>) -> Result<Cow<'_, str>, Error> {
+            fn code_unit(chars: &mut std::str::CharIndices<'_>) -> Option<u16> {
+                let hex: String = chars.by_ref().take(4).map(|(_, char)| char).collect();
+                (hex.len() == 4 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+                    .then(|| u16::from_str_radix(&hex, 16).ok())
+                    .flatten()
+            }
+
+            let raw = self.source.get(span.clone()).unwrap_or_default();
+            if !raw.contains('\\') {
+                return Ok(Cow::Borrowed(raw));
+            }
+            let mut unescaped = String::with_capacity(raw.len());
+            let mut chars = raw.char_indices();
+            while let Some((index, char)) = chars.next() {
+                if char != '\\' {
+                    unescaped.push(char);
+                    continue;
+                }
+                let invalid = || Error::Json {
+                    message: "invalid escape sequence".to_string(),
+                    offset: span.start + index,
+                };
+                let escaped = match chars.next().map(|(_, char)| char) {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => {
+                        let high = code_unit(&mut chars).ok_or_else(invalid)?;
+                        // a character outside the basic plane is escaped as a surrogate pair
+                        let low = if (0xD800..0xDC00).contains(&high)
+                            && chars.next().map(|(_, char)| char) == Some('\\')
+                            && chars.next().map(|(_, char)| char) == Some('u')
+                        {
+                            code_unit(&mut chars)
+                        } else {
+                            None
+                        };
+                        char::decode_utf16(std::iter::once(high).chain(low))
+                            .next()
+                            .and_then(Result::ok)
+                            .ok_or_else(invalid)?
+                    }
+                    _ => return Err(invalid()),
+                };
+                unescaped.push(escaped);
+            }
+            Ok(Cow::Owned(unescaped))
+        }
+
+        /// Parse a number or a literal and return its span.
+        fn scalar(&mut self) -> Result<Range<usize>, Error> {
+            let start = self.pos;
+            while self.peek().is_some_and(|byte| {
+                byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'+' | b'.')
+            }) {
+                self.pos += 1;
+            }
+            if self.pos == start {
+                return Err(self.error("expected a value"));
+            }
+            Ok(start..self.pos)
+        }
+
+        /// Parse a value and return the span of the scalar at `target`.
+        ///
+        /// `target` is the key path that remains below this value, or `None` if the value is
+        /// only skipped. Keys are compared once their escape sequences are resolved, and of
+        /// duplicate keys the last one wins, as in most JSON parsers.
+        fn value(&mut self, target: Option<&[&str]>) -> Result<Option<Range<usize>>, Error> {
+            let child = |key: &str| {
+                target
+                    .and_then(<[&str]>::split_first)
+                    .and_then(|(first, rest)| (*first == key).then_some(rest))
+            };
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'{') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        self.pos += 1;
+                        return Ok(None);
+                    }
+                    let mut found = None;
+                    loop {
+                        let key = self.string()?;
+                        let key = self.unescape(key)?.into_owned();
+                        self.expect(b':')?;
+                        let span = self.value(child(&key))?;
+                        found = span.or(found);
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b',') => self.pos += 1,
+                            Some(b'}') => {
+                                self.pos += 1;
+                                return Ok(found);
+                            }
+                            _ => return Err(self.error("expected `,` or `}`")),
+                        }
+                    }
+                }
+                Some(b'[') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        self.pos += 1;
+                        return Ok(None);
+                    }
+                    let mut found = None;
+                    for index in 0.. {
+                        let span = self.value(child(&index.to_string()))?;
+                        found = found.or(span);
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b',') => self.pos += 1,
+                            Some(b']') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return Err(self.error("expected `,` or `]`")),
+                        }
+                    }
+                    Ok(found)
+                }
+                Some(b'"') => {
+                    let span = self.string()?;
+                    Ok(target.is_some_and(<[&str]>::is_empty).then_some(span))
+                }
+                Some(_) => {
+                    self.scalar()?;
+                    if target.is_some_and(<[&str]>::is_empty) {
+                        return Err(Error::UnsupportedValue {
+                            key_path: self.key_path.join("."),
+                            reason: "is not a string".to_string(),
+                        });
+                    }
+                    Ok(None)
+                }
+                None => Err(self.error("unexpected end of input")),
+            }
+        }
+    }
+
+    /// Find the span of the scalar at `key_path` in a JSON document.
+    ///
+    /// Array elements are addressed by their index.
+    pub(super) fn find(contents: &str, key_path: &[&str]) -> Result<Option<Range<usize>>, Error> {
+        let mut parser = Parser {
+            source: contents,
+            key_path,
+            pos: 0,
+        };
+        parser.value(Some(key_path))
+    }
+}
+
+mod yaml {
+    use super::Error;
+    use saphyr_parser::{Event, Parser, ScalarStyle, ScanError, Span};
+    use std::ops::Range;
+
+    struct Walker<'a> {
+        source: &'a str,
+        key_path: &'a [&'a str],
+        events: Parser<'a, saphyr_parser::StrInput<'a>>,
+    }
+
+    impl<'a> Walker<'a> {
+        /// Convert a character index of the parser into a byte offset into the source.
+        fn offset(&self, index: usize) -> usize {
+            self.source
+                .char_indices()
+                .nth(index)
+                .map_or(self.source.len(), |(offset, _)| offset)
+        }
+
+        fn unsupported(&self, reason: &str) -> Error {
+            Error::UnsupportedValue {
+                key_path: self.key_path.join("."),
+                reason: reason.to_string(),
+            }
+        }
+
+        fn next(&mut self) -> Result<(Event<'a>, Span), Error> {
+            match self.events.next() {
+                Some(Ok(event)) => Ok(event),
+                Some(Err(err)) => Err(self.parse_error(&err)),
+                None => Err(Error::Yaml {
+                    message: "unexpected end of input".to_string(),
+                    offset: self.source.len(),
+                }),
+            }
+        }
+
+        fn parse_error(&self, err: &ScanError) -> Error {
+            Error::Yaml {
+                message: err.info().to_string(),
+                offset: self.offset(err.marker().index()),
+            }
+        }
+
+        /// Return the span of a scalar in the source, without the quotes.
+        ///
+        /// Only scalars written out verbatim on a single line are supported, so that replacing
+        /// the span cannot change anything but the value.
+        fn scalar(
+            &self,
+            value: &str,
+            style: ScalarStyle,
+            span: Span,
+        ) -> Result<Range<usize>, Error> {
+            let start = self.offset(span.start.index());
+            let (start, quote) = match style {
+                ScalarStyle::Plain => (start, None),
+                ScalarStyle::SingleQuoted => (start + 1, Some('\'')),
+                ScalarStyle::DoubleQuoted => (start + 1, Some('"')),
+                ScalarStyle::Literal | ScalarStyle::Folded => {
+                    return Err(self.unsupported("is a block scalar"));
+                }
+            };
+            let end = start + value.len();
+            let verbatim = self.source.get(start..end) == Some(value)
+                && quote.is_none_or(|quote| {
+                    self.source
+                        .get(end..)
+                        .is_some_and(|rest| rest.starts_with(quote))
+                });
+            if !verbatim {
+                return Err(self.unsupported("spans several lines or contains escapes"));
+            }
+            Ok(start..end)
+        }
+
+        /// Walk the node that starts with `event` and return the span of the scalar at `target`.
+        ///
+        /// `target` is the key path that remains below this node, or `None` if the node is only
+        /// skipped. Walking stops at the first match.
+        fn node(
+            &mut self,
+            event: Event<'a>,
+            span: Span,
+            target: Option<&[&str]>,
+        ) -> Result<Option<Range<usize>>, Error> {
+            let child = |key: &str| {
+                target
+                    .and_then(<[&str]>::split_first)
+                    .and_then(|(first, rest)| (*first == key).then_some(rest))
+            };
+            match event {
+                Event::Scalar(value, style, _, _) => {
+                    if target.is_some_and(<[&str]>::is_empty) {
+                        self.scalar(&value, style, span).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                }
+                Event::Alias(_) if target.is_some() => Err(self.unsupported("is an alias")),
+                Event::MappingStart(_, _) => loop {
+                    let (key, key_span) = self.next()?;
+                    let target = match &key {
+                        Event::MappingEnd => return Ok(None),
+                        Event::Scalar(key, _, _, _) => child(key),
+                        _ => None,
+                    };
+                    self.node(key, key_span, None)?;
+                    let (value, value_span) = self.next()?;
+                    if let Some(found) = self.node(value, value_span, target)? {
+                        return Ok(Some(found));
+                    }
+                },
+                Event::SequenceStart(_, _) => {
+                    for index in 0.. {
+                        let (item, item_span) = self.next()?;
+                        if matches!(item, Event::SequenceEnd) {
+                            break;
+                        }
+                        if let Some(found) =
+                            self.node(item, item_span, child(&index.to_string()))?
+                        {
+                            return Ok(Some(found));
+                        }
+                    }
+                    Ok(None)
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    /// Find the span of the scalar at `key_path` in a YAML stream.
+    ///
+    /// Sequence items are addressed by their index. In a stream of several documents, the
+    /// first document that contains the key path is used.
+    pub(super) fn find(contents: &str, key_path: &[&str]) -> Result<Option<Range<usize>>, Error> {
+        let mut walker = Walker {
+            source: contents,
+            key_path,
+            events: Parser::new_from_str(contents),
+        };
+        loop {
+            match walker.next()? {
+                (Event::StreamEnd, _) => return Ok(None),
+                (Event::DocumentStart(_), _) => {
+                    let (root, span) = walker.next()?;
+                    if let Some(found) = walker.node(root, span, Some(key_path))? {
+                        return Ok(Some(found));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+mod ini {
+    use crate::config::ini::{assignment_key, section_name};
+    use std::ops::Range;
+
+    /// Find the span of the value at the `section.key` path in an INI document.
+    pub(super) fn find(contents: &str, key_path: &[&str]) -> Option<Range<usize>> {
+        let [section, key] = key_path else {
+            return None;
+        };
+        let mut in_section = false;
+        let mut offset = 0;
+        for line in contents.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+
+            if let Some(name) = section_name(line) {
+                in_section = name == *section;
+            } else if in_section && assignment_key(line) == Some(*key) {
+                let delimiter = line.find(['=', ':'])?;
+                let rest = line.get(delimiter + 1..)?;
+                let value = rest.trim();
+                if value.is_empty() {
+                    return None;
+                }
+                let start = line_start + delimiter + 1 + (rest.len() - rest.trim_start().len());
+                return Some(start..start + value.len());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, replace_value};
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+
+    fn replace(format: Format, contents: &str, key_path: &str) -> eyre::Result<(String, usize)> {
+        let search = regex::Regex::new(r"1\.2\.3")?;
+        let key_path: Vec<&str> = key_path.split('.').collect();
+        Ok(replace_value(
+            format, contents, &key_path, &search, "1.3.0",
+        )?)
+    }
+
+    #[test]
+    fn test_replace_toml_value() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            [package]
+            name = "demo"
+            version = "1.2.3" # the version
+
+            [dependencies]
+            other = { version = "1.2.3" }
+        "#};
+        let (after, matches) = replace(Format::Toml, contents, "package.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replacen(r#"version = "1.2.3""#, r#"version = "1.3.0""#, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_json_value() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            {
+              "name": "demo",
+              "dependencies": { "other": "1.2.3" },
+              "version":   "1.2.3",
+              "files": ["a", {"version": "1.2.3"}]
+            }
+        "#};
+        let (after, matches) = replace(Format::Json, contents, "version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace(r#""version":   "1.2.3""#, r#""version":   "1.3.0""#)
+        );
+
+        let (after, matches) = replace(Format::Json, contents, "files.1.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace(r#"{"version": "1.2.3"}"#, r#"{"version": "1.3.0"}"#)
+        );
+
+        let (after, matches) = replace(Format::Json, contents, "name")?;
+        sim_assert_eq!(matches, 0);
+        sim_assert_eq!(after, contents);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_json_value_with_escaped_key() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = r#"{ "name": "a\"b", "\u0076ersion": "1.2.3", "\ud83d\ude80": "1.2.3" }"#;
+        let (after, matches) = replace(Format::Json, contents, "version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            r#"{ "name": "a\"b", "\u0076ersion": "1.3.0", "\ud83d\ude80": "1.2.3" }"#
+        );
+
+        let (after, matches) = replace(Format::Json, contents, "\u{1f680}")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            r#"{ "name": "a\"b", "\u0076ersion": "1.2.3", "\ud83d\ude80": "1.3.0" }"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_json_value_of_last_duplicate_key() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = r#"{ "version": "1.2.3", "version": "1.2.3" }"#;
+        let (after, matches) = replace(Format::Json, contents, "version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(after, r#"{ "version": "1.2.3", "version": "1.3.0" }"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_json_number_fails() {
+        crate::tests::init();
+
+        let contents = r#"{ "version": 1.2 }"#;
+        let result = replace(Format::Json, contents, "version");
+        assert!(
+            matches!(
+                result
+                    .as_ref()
+                    .map_err(|err| err.downcast_ref::<super::Error>()),
+                Err(Some(super::Error::UnsupportedValue { .. }))
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_replace_yaml_value() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            # chart
+            name: demo
+            dependencies:
+              other:
+                version: 1.2.3
+              version: "1.2.3"
+            version: 1.2.3 # the version
+        "#};
+        let (after, matches) = replace(Format::Yaml, contents, "version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace("version: 1.2.3 #", "version: 1.3.0 #")
+        );
+
+        let (after, matches) = replace(Format::Yaml, contents, "dependencies.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace(r#"version: "1.2.3""#, r#"version: "1.3.0""#)
+        );
+
+        let (_, matches) = replace(Format::Yaml, contents, "other.version")?;
+        sim_assert_eq!(matches, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_yaml_value_in_nested_structures() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r#"
+            name: démo
+            images:
+              - name: app
+                version: 1.2.3
+              - { name: "sidecar", version: '1.2.3' }
+            "app version": 1.2.3
+            ---
+            release: {version: 1.2.3}
+        "#};
+        let (after, matches) = replace(Format::Yaml, contents, "images.0.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace("    version: 1.2.3", "    version: 1.3.0")
+        );
+
+        let (after, matches) = replace(Format::Yaml, contents, "images.1.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(after, contents.replace("'1.2.3'", "'1.3.0'"));
+
+        let (after, matches) = replace(Format::Yaml, contents, "app version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace(r#""app version": 1.2.3"#, r#""app version": 1.3.0"#)
+        );
+
+        let (after, matches) = replace(Format::Yaml, contents, "release.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replace("{version: 1.2.3}", "{version: 1.3.0}")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_yaml_block_scalar_fails() {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r"
+            version: |
+              1.2.3
+        "};
+        let result = replace(Format::Yaml, contents, "version");
+        assert!(
+            matches!(
+                result
+                    .as_ref()
+                    .map_err(|err| err.downcast_ref::<super::Error>()),
+                Err(Some(super::Error::UnsupportedValue { .. }))
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_replace_ini_value() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let contents = indoc::indoc! {r"
+            [metadata]
+            name = demo
+            version = 1.2.3
+
+            [options]
+            version = 1.2.3
+        "};
+        let (after, matches) = replace(Format::Ini, contents, "metadata.version")?;
+        sim_assert_eq!(matches, 1);
+        sim_assert_eq!(
+            after,
+            contents.replacen("version = 1.2.3", "version = 1.3.0", 1)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,721 @@
+//! File operations for searching and replacing version strings in project files.
+//!
+//! Handles reading, modifying, and writing files based on configuration.
+use crate::{
+    config::{self, FileChange, InputFile, VersionComponentConfigs},
+    diagnostics::{FileId, Location},
+    f_string::{self, PythonFormatString},
+    version::{self, Version},
+};
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub mod cargo;
+pub mod data;
+
+// /// Does the search pattern match any part of the contents?
+// fn contains_pattern(contents: &str, search_pattern: &regex::Regex) -> bool {
+//     let matches = search_pattern.captures_iter(contents);
+//     let Some(m) = matches.into_iter().next() else {
+//         return false;
+//     };
+//     let Some(m) = m.iter().next().flatten() else {
+//         return false;
+//     };
+//     let line_num = contents[..m.start()].chars().filter(|c| *c == '\n').count() + 1;
+//     tracing::info!(
+//         "found {:?} at line {}: {:?}",
+//         search_pattern.as_str(),
+//         line_num,
+//         m.as_str(),
+//     );
+//     true
+// }
+
+/// Errors that can occur when replacing version strings in files.
+#[derive(thiserror::Error, Debug)]
+pub enum ReplaceVersionError {
+    #[error(transparent)]
+    /// I/O error while reading or writing a file.
+    Io(#[from] IoError),
+    #[error(transparent)]
+    /// Failed to serialize a version according to the configured patterns.
+    Serialize(#[from] version::SerializeError),
+    #[error(transparent)]
+    /// Template argument is missing or cannot be formatted.
+    Format(#[from] f_string::FormatError),
+    #[error(transparent)]
+    /// Invalid Python-style format string.
+    InvalidFormatString(#[from] f_string::ParseError),
+
+    #[error(transparent)]
+    /// Failed to format a regex template.
+    RegexTemplate(#[from] config::regex::RegexTemplateError),
+    #[error(transparent)]
+    /// TOML editing error.
+    Toml(#[from] toml_edit::TomlError),
+    #[error(transparent)]
+    /// Failed to replace the value at a `key_path` in a data file.
+    Data(#[from] data::Error),
+    #[error(transparent)]
+    /// Failed to update the versions of a Cargo workspace.
+    Cargo(#[from] cargo::Error),
+    #[error("did not find {search:?} in {path:?}")]
+    /// The search pattern did not match and missing versions are not ignored.
+    VersionNotFound {
+        /// Path of the file that was searched.
+        path: PathBuf,
+        /// The search regex that did not match.
+        search: String,
+        /// Where the file entry was defined in the config file.
+        location: Location,
+    },
+}
+
+impl ReplaceVersionError {
+    /// Diagnostics pointing at the config entry responsible for this error, if known.
+    #[must_use]
+    pub fn diagnostics(&self) -> Vec<Diagnostic<FileId>> {
+        match self {
+            Self::VersionNotFound {
+                path,
+                search,
+                location,
+            } => location
+                .get()
+                .map(|(file_id, span)| {
+                    Diagnostic::error()
+                        .with_message(format!("did not find `{search}` in {}", path.display()))
+                        .with_labels(vec![
+                            Label::primary(file_id, span)
+                                .with_message("the search pattern of this entry did not match"),
+                        ])
+                        .with_notes(vec![
+                            "set `ignore_missing_version = true` to allow files without the version"
+                                .to_string(),
+                        ])
+                })
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// Apply a list of `changes` to the input `before` content of the file at `path`,
+/// producing the modified text and a record of replacements.
+///
+/// # Errors
+/// Returns `ReplaceVersionError` if serialization, I/O, or formatting fails, or if a search
+/// pattern does not match and the change does not ignore missing versions.
+pub fn replace_version<'a, K, V, S>(
+    path: &Path,
+    before: String,
+    changes: &'a [FileChange],
+    current_version: &'a Version,
+    new_version: &'a Version,
+    ctx: &'a HashMap<K, V, S>,
+) -> Result<Modification, ReplaceVersionError>
+where
+    K: std::borrow::Borrow<str> + std::hash::Hash + Eq + std::fmt::Debug,
+    V: AsRef<str> + std::fmt::Debug,
+    S: std::hash::BuildHasher,
+{
+    let mut after = before.clone();
+    let mut replacements = vec![];
+    for change in changes {
+        tracing::debug!(
+            search = ?change.search,
+            replace = ?change.replace,
+            "update",
+        );
+
+        // we need to update the version because each file may serialize versions differently
+        let current_version_serialized =
+            current_version.serialize(&change.serialize_version_patterns, ctx)?;
+        let new_version_serialized =
+            new_version.serialize(&change.serialize_version_patterns, ctx)?;
+
+        let ctx: HashMap<&str, &str> = ctx
+            .iter()
+            .map(|(k, v)| (k.borrow(), v.as_ref()))
+            .chain([
+                ("current_version", current_version_serialized.as_str()),
+                ("new_version", new_version_serialized.as_str()),
+            ])
+            .collect();
+
+        let search_pattern = &change.search;
+        let search_regex = search_pattern.format(&ctx, true)?;
+
+        let replace_pattern = &change.replace;
+        let replacement = PythonFormatString::parse(replace_pattern)?;
+        let replacement = replacement.format(&ctx, true)?;
+
+        let matches = if let Some(crates) = &change.cargo_workspace {
+            let (replaced, matches) =
+                cargo::replace_versions(path, &after, crates, &search_regex, &replacement)?;
+            after = replaced;
+            matches
+        } else if let Some(key_path) = &change.key_path {
+            let format =
+                data::Format::from_path(path).ok_or_else(|| data::Error::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                })?;
+            let key_path: Vec<&str> = key_path.split('.').collect();
+            let (replaced, matches) =
+                data::replace_value(format, &after, &key_path, &search_regex, &replacement)?;
+            after = replaced;
+            matches
+        } else {
+            let matches = search_regex.find_iter(&after).count();
+            after = search_regex.replace_all(&after, &replacement).to_string();
+            matches
+        };
+        // a lockfile or a manifest inheriting its version may not contain the version at all
+        let requires_version = match change.cargo_workspace {
+            Some(_) => cargo::declares_version(path, &after)?,
+            None => true,
+        };
+        let version_missing = matches == 0 && requires_version;
+        if version_missing && !change.ignore_missing_version {
+            return Err(ReplaceVersionError::VersionNotFound {
+                path: path.to_path_buf(),
+                search: search_regex.as_str().to_string(),
+                location: change.location.clone(),
+            });
+        }
+
+        replacements.push(Replacement {
+            search_pattern: search_pattern.to_string(),
+            search: search_regex.as_str().to_string(),
+            replace_pattern: replace_pattern.clone(),
+            replace: replacement,
+            matches,
+            ignored_missing_version: version_missing.then(|| change.location.clone()),
+        });
+    }
+
+    let modification = Modification {
+        before,
+        after,
+        replacements,
+    };
+    Ok(modification)
+}
+
+/// A single substitution made during version replacement.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Replacement {
+    /// The regex string used to search for the existing version.
+    pub search: String,
+    /// Original search template from configuration before formatting.
+    pub search_pattern: String,
+    /// Replacement string generated with the new version.
+    pub replace: String,
+    /// Template used for replacement before formatting.
+    pub replace_pattern: String,
+    /// Number of occurrences of the search pattern that were replaced.
+    pub matches: usize,
+    /// Where the file entry was defined, if its version was missing and
+    /// `ignore_missing_version` let the file pass.
+    #[serde(skip)]
+    pub ignored_missing_version: Option<Location>,
+}
+
+impl Replacement {
+    /// A warning for the file at `path` if its version was missing and ignored.
+    #[must_use]
+    pub fn diagnostic(&self, path: &Path) -> Option<Diagnostic<FileId>> {
+        let location = self.ignored_missing_version.as_ref()?;
+        Some(
+            Diagnostic::warning()
+                .with_message(format!(
+                    "did not find `{}` in {}",
+                    self.search,
+                    path.display()
+                ))
+                .with_labels(
+                    location
+                        .get()
+                        .map(|(file_id, span)| {
+                            Label::primary(file_id, span)
+                                .with_message("the search pattern of this entry did not match")
+                        })
+                        .into_iter()
+                        .collect(),
+                )
+                .with_notes(vec![
+                    "the file is left unchanged because `ignore_missing_version` is set"
+                        .to_string(),
+                ]),
+        )
+    }
+}
+
+/// Represents the overall result of modifying a file.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Modification {
+    /// Original file content before any replacements.
+    pub before: String,
+    /// File content after applying all replacements.
+    pub after: String,
+    /// Detailed list of individual replacements performed.
+    pub replacements: Vec<Replacement>,
+}
+
+impl Modification {
+    /// Generate a unified diff between original and modified content.
+    ///
+    /// If `path` is provided, include labels in the diff header.
+    #[must_use]
+    pub fn diff(&self, path: Option<&Path>) -> Option<String> {
+        if self.before == self.after {
+            None
+        } else {
+            let (label_before, label_after) = if let Some(path) = path {
+                (
+                    format!("{} (before)", path.display()),
+                    format!("{} (after)", path.display()),
+                )
+            } else {
+                ("before".to_string(), "after".to_string())
+            };
+            let diff = similar_asserts::SimpleDiff::from_str(
+                &self.before,
+                &self.after,
+                &label_before,
+                &label_after,
+            );
+            Some(diff.to_string())
+        }
+    }
+
+    /// Generate a plain unified diff between original and modified content.
+    ///
+    /// Unlike [`Modification::diff`], the result has no terminal styling, so it can be
+    /// applied with `patch` or embedded in machine-readable output.
+    #[must_use]
+    pub fn unified_diff(&self, path: &Path) -> Option<String> {
+        if self.before == self.after {
+            return None;
+        }
+        let path = path.display().to_string();
+        let diff = similar::TextDiff::from_lines(&self.before, &self.after)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string();
+        Some(diff)
+    }
+}
+
+/// Read a file at `path`, apply version replacement, and write back if changed.
+///
+/// Honors `dry_run` to skip writing. Returns `None` if file unchanged or missing (when allowed).
+///
+/// # Errors
+///
+/// Returns [`ReplaceVersionError`] if the file cannot be read or written, or a configured version
+/// replacement cannot be rendered.
+pub async fn replace_version_in_file<K, V, S>(
+    path: &Path,
+    changes: &[FileChange],
+    current_version: &Version,
+    new_version: &Version,
+    ctx: &HashMap<K, V, S>,
+    dry_run: bool,
+) -> Result<Option<Modification>, ReplaceVersionError>
+where
+    K: std::borrow::Borrow<str> + std::hash::Hash + Eq + std::fmt::Debug,
+    V: AsRef<str> + std::fmt::Debug,
+    S: std::hash::BuildHasher,
+{
+    let as_io_error = |source: std::io::Error| -> IoError { IoError::new(source, path) };
+    if !path.is_file() {
+        if changes.iter().all(|change| change.ignore_missing_file) {
+            tracing::info!(?path, "file not found");
+            return Ok(None);
+        }
+        let not_found = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+        return Err(ReplaceVersionError::from(as_io_error(not_found)));
+    }
+
+    let before = tokio::fs::read_to_string(path).await.map_err(as_io_error)?;
+    let modification = replace_version(path, before, changes, current_version, new_version, ctx)?;
+
+    if modification.before == modification.after {
+        // tracing::warn!(?path, "no change after version replacement");
+        // TODO(roman): can we also not do this?
+        // && current_version.original {
+        // og_context = deepcopy(context)
+        // og_context["current_version"] = current_version.original
+        // search_for_og, _ = self.file_change.get_search_pattern(og_context)
+        // file_content_after = search_for_og.sub(replace_with, file_content_before)
+        // return Ok(());
+    }
+
+    if !dry_run {
+        write_atomically(path, &modification.after)
+            .await
+            .map_err(as_io_error)?;
+    }
+    Ok(Some(modification))
+}
+
+/// Write `contents` to `path` through a temporary file in the same directory.
+///
+/// The temporary file is renamed over `path`, so a reader never sees a partially written file.
+/// The permissions of an existing file are kept.
+///
+/// # Errors
+///
+/// Returns an [`std::io::Error`] if the temporary file cannot be written or renamed.
+pub async fn write_atomically(path: &Path, contents: &str) -> Result<(), std::io::Error> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{file_name}.bumpversion.tmp"));
+    tokio::fs::write(&tmp_path, contents).await?;
+    let permissions = match tokio::fs::metadata(path).await {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    if let Some(permissions) = permissions {
+        tokio::fs::set_permissions(&tmp_path, permissions).await?;
+    }
+    if let Err(err) = tokio::fs::rename(&tmp_path, path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// The files written by a bump, with their original contents.
+///
+/// Rolling back restores every file to what it was before the bump, and removes the files the
+/// bump created. The files staged for the commit are remembered as well, so the caller can
+/// unstage them.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    /// Written files and their original contents, or `None` if they did not exist.
+    originals: Vec<(PathBuf, Option<String>)>,
+    /// Files staged for the commit.
+    staged: Vec<PathBuf>,
+}
+
+impl Transaction {
+    /// Write the modified contents to `path`, remembering the original.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`IoError`] if the file cannot be written.
+    pub async fn write(&mut self, path: &Path, modification: &Modification) -> Result<(), IoError> {
+        let existed = path.exists();
+        if existed && modification.before == modification.after {
+            return Ok(());
+        }
+        write_atomically(path, &modification.after)
+            .await
+            .map_err(|source| IoError::new(source, path))?;
+        self.originals.push((
+            path.to_path_buf(),
+            existed.then(|| modification.before.clone()),
+        ));
+        Ok(())
+    }
+
+    /// Remember that `files` were staged for the commit.
+    pub fn stage(&mut self, files: &[PathBuf]) {
+        self.staged.extend_from_slice(files);
+    }
+
+    /// The files staged for the commit, which must be unstaged when rolling back.
+    #[must_use]
+    pub fn staged(&self) -> &[PathBuf] {
+        &self.staged
+    }
+
+    /// Restore all written files, most recently written first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`IoError`] for the first file that cannot be restored. The remaining files
+    /// are still restored.
+    pub async fn rollback(self) -> Result<(), IoError> {
+        let mut result = Ok(());
+        for (path, original) in self.originals.into_iter().rev() {
+            tracing::info!(?path, "restoring original contents");
+            let restored = match original {
+                Some(original) => write_atomically(&path, &original).await,
+                None => tokio::fs::remove_file(&path).await,
+            };
+            if let Err(source) = restored
+                && result.is_ok()
+            {
+                result = Err(IoError::new(source, path));
+            }
+        }
+        result
+    }
+}
+
+/// Errors encountered when resolving glob patterns to file paths.
+#[derive(thiserror::Error, Debug)]
+pub enum GlobError {
+    #[error(transparent)]
+    /// Invalid glob pattern.
+    Pattern(#[from] glob::PatternError),
+    #[error(transparent)]
+    /// Error while iterating glob matches.
+    Glob(#[from] glob::GlobError),
+}
+
+/// I/O error with optional path context.
+#[derive(thiserror::Error, Debug)]
+pub struct IoError {
+    /// Underlying I/O error.
+    #[source]
+    pub source: std::io::Error,
+    /// Path of the file that caused the error, if available.
+    pub path: Option<PathBuf>,
+}
+
+impl IoError {
+    /// Create a new [`IoError`] with a source error and a path.
+    pub fn new(source: impl Into<std::io::Error>, path_or_stream: impl Into<PathBuf>) -> Self {
+        Self {
+            source: source.into(),
+            path: Some(path_or_stream.into()),
+        }
+    }
+}
+
+impl std::fmt::Display for IoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "io error for {}", path.display()),
+            None => write!(f, "io error"),
+        }
+    }
+}
+
+/// Combined error type for file resolution operations.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    /// Error while resolving file globs.
+    Glob(#[from] GlobError),
+    #[error(transparent)]
+    /// I/O error while resolving files.
+    Io(#[from] IoError),
+    #[error(transparent)]
+    /// Error while resolving a Cargo workspace.
+    Cargo(#[from] cargo::Error),
+}
+
+/// Return a list of file configurations that match the glob pattern
+fn resolve_glob_files(
+    pattern: &str,
+    exclude_patterns: &[String],
+) -> Result<Vec<PathBuf>, GlobError> {
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+    let included: HashSet<PathBuf> =
+        glob::glob_with(pattern, options)?.collect::<Result<_, _>>()?;
+
+    let excluded: HashSet<PathBuf> = exclude_patterns
+        .iter()
+        .map(|pattern| glob::glob_with(pattern, options))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flat_map(std::iter::IntoIterator::into_iter)
+        .collect::<Result<_, _>>()?;
+
+    // Sorted: `glob` yields matches in an unspecified order and the set difference
+    // discards it entirely, so without this the files of one glob entry would be
+    // rewritten — and logged — in a different order on every run.
+    let mut matched: Vec<PathBuf> = included.difference(&excluded).cloned().collect();
+    matched.sort();
+    Ok(matched)
+}
+
+/// Mapping from file paths to the list of version `FileChange`s to apply.
+pub type FileMap = IndexMap<PathBuf, Vec<FileChange>>;
+
+/// Build the file map from `config`, expanding glob patterns and relative paths.
+///
+/// Applies `parts` (version component configs) and resolves paths under `base_dir`.
+///
+/// # Errors
+///
+/// Returns [`Error`] if a glob is invalid, a glob match cannot be read, or a relative path of an
+/// existing file cannot be canonicalized.
+pub fn resolve_files_from_config(
+    config: &mut config::FinalizedConfig,
+    parts: &VersionComponentConfigs,
+    base_dir: Option<&Path>,
+) -> Result<FileMap, Error> {
+    let files = config.files.drain(..);
+    let new_files: Vec<_> = files
+        .into_iter()
+        .map(|(file, file_config)| {
+            let new_files = match file {
+                InputFile::GlobPattern {
+                    pattern,
+                    exclude_patterns,
+                } => resolve_glob_files(&pattern, exclude_patterns.as_deref().unwrap_or_default()),
+                InputFile::Path(path) => Ok(vec![path.clone()]),
+            }?;
+
+            let file_change = FileChange::new(file_config, parts);
+            let new_files = new_files
+                .into_iter()
+                .map(|file| {
+                    if file.is_absolute() {
+                        Ok(file)
+                    } else if let Some(base_dir) = base_dir {
+                        let file = base_dir.join(&file);
+                        // a missing file is reported when checking or bumping, where
+                        // `ignore_missing_file` applies
+                        match file.canonicalize() {
+                            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(file),
+                            canonicalized => {
+                                canonicalized.map_err(|source| IoError::new(source, file))
+                            }
+                        }
+                    } else {
+                        Ok(file)
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if file_change.cargo_workspace.is_some() {
+                return resolve_cargo_workspaces(new_files, &file_change);
+            }
+            Ok(new_files
+                .into_iter()
+                .map(|file| (file, file_change.clone()))
+                .collect())
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let new_files = new_files.into_iter().flatten().fold(
+        IndexMap::<PathBuf, Vec<FileChange>>::new(),
+        |mut acc, (file, config)| {
+            acc.entry(file).or_default().push(config);
+            acc
+        },
+    );
+    Ok(new_files)
+}
+
+/// Expand the Cargo workspace `manifests` into the manifests of their crates and their lockfiles.
+fn resolve_cargo_workspaces(
+    manifests: Vec<PathBuf>,
+    file_change: &FileChange,
+) -> Result<Vec<(PathBuf, FileChange)>, Error> {
+    let mut files = vec![];
+    for manifest in manifests {
+        let workspace = cargo::Workspace::resolve(&manifest)?;
+        let file_change = FileChange {
+            cargo_workspace: Some(workspace.crates),
+            ..file_change.clone()
+        };
+        files.extend(
+            workspace
+                .manifests
+                .into_iter()
+                .chain(workspace.lockfile)
+                .map(|file| (file, file_change.clone())),
+        );
+    }
+    Ok(files)
+}
+
+/// Filter the `file_map` based on `config` include/exclude settings.
+pub fn files_to_modify(
+    config: &config::FinalizedConfig,
+    file_map: FileMap,
+) -> impl Iterator<Item = (PathBuf, Vec<FileChange>)> + use<'_> {
+    let excluded_paths_from_config: HashSet<&PathBuf> = config
+        .global
+        .excluded_paths
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .collect();
+
+    let included_paths_from_config: HashSet<&PathBuf> = config
+        .global
+        .included_paths
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .collect();
+
+    let included_files: HashSet<&PathBuf> = file_map
+        .keys()
+        .collect::<HashSet<&PathBuf>>()
+        .difference(&excluded_paths_from_config)
+        .copied()
+        .collect();
+
+    let included_files: HashSet<PathBuf> = included_paths_from_config
+        .union(&included_files)
+        .copied()
+        .cloned()
+        .collect();
+
+    file_map
+        .into_iter()
+        .filter(move |(file, _)| included_files.contains(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Modification, Transaction};
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+
+    fn modification(before: &str, after: &str) -> Modification {
+        Modification {
+            before: before.to_string(),
+            after: after.to_string(),
+            replacements: vec![],
+        }
+    }
+
+    /// Rolling back restores modified files and removes files that did not exist before.
+    #[tokio::test]
+    async fn test_transaction_rollback() -> eyre::Result<()> {
+        crate::tests::init();
+        let dir = tempfile::tempdir()?;
+        let version = dir.path().join("VERSION");
+        let changelog = dir.path().join("CHANGELOG.md");
+        tokio::fs::write(&version, "1.2.3\n").await?;
+
+        let mut transaction = Transaction::default();
+        transaction
+            .write(&version, &modification("1.2.3\n", "1.3.0\n"))
+            .await?;
+        transaction
+            .write(&changelog, &modification("", "## 1.3.0\n"))
+            .await?;
+        sim_assert_eq!(tokio::fs::read_to_string(&version).await?, "1.3.0\n");
+        sim_assert_eq!(tokio::fs::read_to_string(&changelog).await?, "## 1.3.0\n");
+
+        transaction.rollback().await?;
+        sim_assert_eq!(tokio::fs::read_to_string(&version).await?, "1.2.3\n");
+        assert!(!changelog.exists());
+
+        let mut leftovers = std::fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<Result<Vec<_>, _>>()?;
+        leftovers.sort();
+        sim_assert_eq!(leftovers, vec!["VERSION"]);
+        Ok(())
+    }
+}
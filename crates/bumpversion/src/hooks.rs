@@ -139,10 +139,13 @@ where
     ///
     /// # Errors
     /// When one of the user-provided setup hooks exits with a non-zero exit code.
-    pub async fn run_setup_hooks(&self, current_version: Option<&Version>) -> Result<(), Error> {
+    pub async fn run_setup_hooks(
+        &self,
+        setup_hooks: &[String],
+        current_version: Option<&Version>,
+    ) -> Result<(), Error> {
         let env = setup_hook_env(&self.tag_and_revision, current_version);
 
-        let setup_hooks = &self.config.global.setup_hooks;
         self.logger.log_hooks("setup", setup_hooks);
 
         run_hooks(
@@ -160,6 +163,7 @@ where
     /// When one of the user-provided pre-commit hooks exits with a non-zero exit code.
     pub async fn run_pre_commit_hooks(
         &self,
+        pre_commit_hooks: &[String],
         current_version: Option<&Version>,
         new_version: Option<&Version>,
         new_version_serialized: &str,
@@ -173,7 +177,6 @@ where
             new_version_tag,
         );
 
-        let pre_commit_hooks = &self.config.global.pre_commit_hooks;
         self.logger.log_hooks("pre-commit", pre_commit_hooks);
 
         run_hooks(
@@ -191,6 +194,7 @@ where
     /// When one of the user-provided post-commit hooks exits with a non-zero exit code.
     pub async fn run_post_commit_hooks(
         &self,
+        post_commit_hooks: &[String],
         current_version: Option<&Version>,
         new_version: Option<&Version>,
         new_version_serialized: &str,
@@ -204,7 +208,6 @@ where
             new_version_tag,
         );
 
-        let post_commit_hooks = &self.config.global.post_commit_hooks;
         self.logger.log_hooks("post-commit", post_commit_hooks);
 
         run_hooks(
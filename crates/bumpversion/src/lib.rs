@@ -22,10 +22,12 @@
 //! let printer = Printer::stderr(None);
 //!
 //! // find and parse configuration
-//! // `None` searches the default candidate names; `Some(path)` reads just that file
+//! // `None` searches the default candidate names; `Some(path)` reads just that file,
+//! // and a package name selects one package of a monorepo
 //! let (config_file, mut config) = bumpversion::find_config(
 //!   &repo_path,
 //!   None,
+//!   None,
 //!   &Default::default(),
 //!   &printer,
 //! ).await?.unwrap();
@@ -61,17 +63,26 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! To review a bump before making it, [`BumpVersion::plan`] returns a [`plan::BumpPlan`] with
+//! the new version, the modification of every file, the commit and the tag, without touching the
+//! repository. [`BumpVersion::apply`] later carries out exactly that plan.
 #![forbid(unsafe_code)]
 // #![warn(missing_docs)]
 
+pub mod changelog;
+pub mod check;
 pub mod command;
 pub mod config;
 pub mod context;
+pub mod conventional_commits;
 pub mod diagnostics;
 pub mod f_string;
 pub mod files;
 pub mod hooks;
 pub mod logging;
+pub mod plan;
+pub mod report;
 pub mod vcs;
 /// Version parsing, bumping, and serialization.
 pub mod version;
@@ -87,31 +98,96 @@ use indexmap::{IndexMap, IndexSet};
 use logging::{LogExt, Verbosity};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 
 /// Specifies which version bump to perform.
 ///
 /// Variants:
 /// - `Component(name)`: increment the named component (e.g., "major", "minor", "patch").
 /// - `NewVersion(version)`: set the version to the given value.
+/// - `Pre(component)`, `Promote` and `Release`: move through the pre-release lifecycle.
 pub enum Bump<'a> {
     /// Increment the named version component (e.g., "major", "minor", "patch").
     Component(&'a str),
     /// Set the version to the specified new version string.
     NewVersion(&'a str),
+    /// Enter a pre-release by bumping the named component, or bump the pre-release number.
+    Pre(Option<&'a str>),
+    /// Promote a pre-release to its next label (e.g., from "rc" to "final").
+    Promote,
+    /// Release a pre-release by setting its label to the optional value.
+    Release,
+}
+
+/// Parse the contents of a config file according to its format.
+fn parse_config_file(
+    config_file: &config::ConfigFile,
+    config: &str,
+    file_id: diagnostics::FileId,
+    strict: bool,
+    diagnostics: &mut Vec<codespan_reporting::diagnostic::Diagnostic<diagnostics::FileId>>,
+) -> Result<Option<config::Config>, config::Error> {
+    use diagnostics::ToDiagnostics;
+    match config_file {
+        config::ConfigFile::BumpversionToml(path) | config::ConfigFile::PyProject(path) => {
+            let res = config::Config::from_pyproject_toml(config, file_id, strict, diagnostics);
+            if let Err(ref err) = res {
+                diagnostics.extend(err.to_diagnostics(file_id));
+            }
+            res.map_err(|source| config::Error::Toml {
+                source,
+                path: path.clone(),
+            })
+        }
+        config::ConfigFile::BumpversionCfg(path) => {
+            let options = config::ini::Options::default();
+            let res = config::Config::from_ini(config, options, file_id, strict, diagnostics);
+            if let Err(ref err) = res {
+                diagnostics.extend(err.to_diagnostics(file_id));
+            }
+            res.map_err(|source| config::Error::Ini {
+                source,
+                path: path.clone(),
+            })
+        }
+        config::ConfigFile::SetupCfg(path) => {
+            let options = config::ini::Options::default();
+            let res =
+                config::Config::from_setup_cfg_ini(config, options, file_id, strict, diagnostics);
+            if let Err(ref err) = res {
+                diagnostics.extend(err.to_diagnostics(file_id));
+            }
+            res.map_err(|source| config::Error::Ini {
+                source,
+                path: path.clone(),
+            })
+        }
+        config::ConfigFile::CargoToml(path) => {
+            let res = config::Config::from_cargo_toml(config, file_id, strict, diagnostics);
+            if let Err(ref err) = res {
+                diagnostics.extend(err.to_diagnostics(file_id));
+            }
+            res.map_err(|source| config::Error::CargoToml {
+                source,
+                path: path.clone(),
+            })
+        }
+    }
 }
 
 /// Find config file in one of the default config file locations.
 ///
 /// When `config_file` is given, only that file is considered and the usual
-/// candidate list in `dir` is skipped entirely.
+/// candidate list in `dir` is skipped entirely. When `package` is given, the
+/// configuration of that package is selected.
 ///
 /// # Errors
 ///
-/// Returns [`config::Error`] if a discovered configuration file cannot be read or parsed.
+/// Returns [`config::Error`] if a discovered configuration file cannot be read or parsed,
+/// or if `package` is not configured.
 pub async fn find_config<W>(
     dir: &Path,
     config_file: Option<&Path>,
+    package: Option<&str>,
     config_overrides: &config::GlobalConfig,
     printer: &diagnostics::Printer<W>,
 ) -> Result<Option<(config::ConfigFile, config::FinalizedConfig)>, config::Error>
@@ -119,7 +195,6 @@ where
     W: codespan_reporting::term::WriteStyle + Send + Sync + 'static,
     // W: codespan_reporting::term::termcolor::WriteColor + Send + Sync + 'static,
 {
-    use diagnostics::ToDiagnostics;
     let config_files: Vec<config::ConfigFile> = match config_file {
         Some(path) => vec![config::ConfigFile::from_path(path)],
         None => config::config_file_locations(dir).collect(),
@@ -145,59 +220,8 @@ where
                 let mut diagnostics = vec![];
                 let strict = true;
 
-                let config_res = match &config_file {
-                    config::ConfigFile::BumpversionToml(path)
-                    | config::ConfigFile::PyProject(path) => {
-                        let res = config::Config::from_pyproject_toml(
-                            &config,
-                            file_id,
-                            strict,
-                            &mut diagnostics,
-                        );
-                        if let Err(ref err) = res {
-                            diagnostics.extend(err.to_diagnostics(file_id));
-                        }
-                        res.map_err(|source| config::Error::Toml {
-                            source,
-                            path: path.clone(),
-                        })
-                    }
-                    config::ConfigFile::BumpversionCfg(path) => {
-                        let options = config::ini::Options::default();
-                        let res = config::Config::from_ini(
-                            &config,
-                            options,
-                            file_id,
-                            strict,
-                            &mut diagnostics,
-                        );
-                        if let Err(ref err) = res {
-                            diagnostics.extend(err.to_diagnostics(file_id));
-                        }
-                        res.map_err(|source| config::Error::Ini {
-                            source,
-                            path: path.clone(),
-                        })
-                    }
-                    config::ConfigFile::SetupCfg(path) => {
-                        let options = config::ini::Options::default();
-                        let res = config::Config::from_setup_cfg_ini(
-                            &config,
-                            options,
-                            file_id,
-                            strict,
-                            &mut diagnostics,
-                        );
-                        if let Err(ref err) = res {
-                            diagnostics.extend(err.to_diagnostics(file_id));
-                        }
-                        res.map_err(|source| config::Error::Ini {
-                            source,
-                            path: path.clone(),
-                        })
-                    }
-                    config::ConfigFile::CargoToml(_) => Ok(None),
-                };
+                let config_res =
+                    parse_config_file(&config_file, &config, file_id, strict, &mut diagnostics);
 
                 config_res.map(|c| c.map(|c| (config_file.clone(), c, diagnostics)))
             });
@@ -220,11 +244,36 @@ where
                 printer.emit(diagnostic).map_err(diagnostics::Error::from)?;
             }
 
+            if let Some(name) = package {
+                config = config.package(name)?;
+            }
+
             let mut global_config = config_overrides.clone();
             global_config.merge_with(&config.global);
             config.global = global_config;
 
-            Ok::<_, config::Error>((config_file, config.finalize()))
+            let unknown = config.unknown_components();
+            if !unknown.is_empty() {
+                let available: Vec<String> = config.component_names().into_iter().collect();
+                for diagnostic in unknown
+                    .iter()
+                    .filter_map(|component| component.to_diagnostic(&available))
+                {
+                    printer
+                        .emit(&diagnostic)
+                        .map_err(diagnostics::Error::from)?;
+                }
+                return Err(config::Error::UnknownComponents {
+                    components: unknown,
+                    available,
+                });
+            }
+
+            let config = match package {
+                Some(name) => config.finalize().for_package(name),
+                None => config.finalize(),
+            };
+            Ok::<_, config::Error>((config_file, config))
         })
         .transpose()
 }
@@ -244,21 +293,50 @@ where
     /// No previous tagged version is available for finalization.
     #[error("cannot finalize without a previous tagged version")]
     MissingPreviousVersion,
+    /// No tag matches `tag_name`, so there is no version to describe from.
+    #[error("cannot describe without a tagged version")]
+    MissingTag,
     /// The configured version already matches the latest tag.
     #[error("version {0} is already tagged")]
     AlreadyFinalized(String),
     /// Parsed version string was empty or invalid.
     #[error("version is empty")]
     EmptyVersion,
+    /// The new version is not newer than the current version.
+    #[error("new version {new_version} is not newer than current version {current_version}")]
+    Downgrade {
+        /// The current version.
+        current_version: String,
+        /// The rejected new version.
+        new_version: String,
+    },
+    /// No commit since the latest tag calls for a version bump.
+    #[error("no commits since {} call for a version bump", .0.as_deref().unwrap_or("the first commit"))]
+    NoReleasableCommits(Option<String>),
     /// A configured setup hook failed.
     #[error("setup hook failed")]
     SetupHook(#[source] crate::hooks::Error),
     /// A configured pre-commit hook failed.
     #[error("pre-commit hook failed")]
-    PreCommitHook(#[source] crate::hooks::Error),
+    PreCommitHook {
+        /// The hook error.
+        #[source]
+        source: crate::hooks::Error,
+        /// Whether the files written by the bump were restored.
+        rolled_back: bool,
+    },
     /// A configured post-commit hook failed.
     #[error("post-commit hook failed")]
     PostCommitHook(#[source] crate::hooks::Error),
+    /// The commit and tag could not be pushed, e.g. because the remote rejected them.
+    #[error("failed to push to {remote:?}")]
+    Push {
+        /// The remote that was pushed to.
+        remote: String,
+        /// The VCS error.
+        #[source]
+        source: VCS::Error,
+    },
     /// An error occurred while bumping the version component.
     #[error("failed to bump version")]
     Bump(#[from] crate::version::BumpError),
@@ -268,9 +346,21 @@ where
     /// Error replacing version in project files.
     #[error("failed to replace version")]
     ReplaceVersion(#[from] crate::files::ReplaceVersionError),
-    /// A required template argument was missing.
+    /// The tag of the new version already exists, and `tag_conflict` is `error`.
+    #[error("tag {0:?} already exists")]
+    TagConflict(String),
+    /// The preflight of a bump found problems before any file was touched.
+    #[error("{} found before touching any file", check::count_problems(.0))]
+    Preflight(Vec<check::Problem>),
+    /// A file changed between planning and applying a bump.
+    #[error("{0:?} changed since the bump was planned")]
+    OutdatedPlan(PathBuf),
+    /// The changelog could not be read or written.
+    #[error("failed to update changelog")]
+    Changelog(#[source] IoError),
+    /// A template argument was missing or could not be formatted.
     #[error(transparent)]
-    MissingArgument(#[from] f_string::MissingArgumentError),
+    Format(#[from] f_string::FormatError),
     /// Underlying version control system error.
     #[error(transparent)]
     VCS(VCS::Error),
@@ -372,6 +462,55 @@ where
             .collect()
     }
 
+    fn changelog_path(&self) -> Option<PathBuf> {
+        self.config.global.changelog.as_ref().map(|path| {
+            if path.is_absolute() {
+                path.clone()
+            } else {
+                self.repo.path().join(path)
+            }
+        })
+    }
+
+    /// Render the changelog with a section for the new version added.
+    ///
+    /// The section lists the commits since the latest tag.
+    async fn update_changelog(
+        &self,
+        path: &Path,
+        ctx: &HashMap<String, String>,
+    ) -> Result<files::Modification, BumpError<VCS>> {
+        let since = self
+            .tag_and_revision
+            .tag
+            .as_ref()
+            .map(|tag| tag.current_tag.as_str());
+        let messages = self
+            .repo
+            .commit_messages(since)
+            .await
+            .map_err(BumpError::VCS)?;
+        let section = changelog::render_section(
+            &messages,
+            &self.config.global.changelog_sections,
+            &self.config.global.changelog_header,
+            &self.config.global.changelog_entry,
+            ctx,
+        )?;
+
+        let before = match tokio::fs::read_to_string(path).await {
+            Ok(before) => before,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(BumpError::Changelog(IoError::new(err, path))),
+        };
+        let after = changelog::insert_section(&before, &section);
+        Ok(files::Modification {
+            before,
+            after,
+            replacements: vec![],
+        })
+    }
+
     fn new_version_tag(
         &self,
         context: &HashMap<String, String>,
@@ -384,117 +523,141 @@ where
             .map_err(Into::into)
     }
 
-    async fn apply_version_bump(
-        &self,
-        bump: Bump<'_>,
-        current_version: version::Version,
-        current_version_serialized: &str,
-        new_version: version::Version,
-        new_version_serialized: String,
-    ) -> Result<(), BumpError<VCS>> {
-        if current_version_serialized == new_version_serialized {
-            tracing::info!(
-                version = new_version_serialized,
-                "next version matches current version"
-            );
-            return Ok(());
-        }
-
-        if self.config.global.dry_run {
-            tracing::info!("dry run active, won't touch any files.");
-        }
-
-        let mut configured_files = self.configured_files();
-
-        // filter the files that are not valid for this bump
-        if let Bump::Component(version_component_to_bump) = bump {
-            for changes in configured_files.values_mut() {
-                changes.retain(|change| change.will_bump_component(version_component_to_bump));
-                changes.retain(|change| !change.will_not_bump_component(version_component_to_bump));
+    /// Fail if a file the `plan` writes has changed since it was planned.
+    ///
+    /// A plan may be applied long after it was made, and writing its contents over a file that
+    /// changed in the meantime would silently revert that change.
+    async fn check_plan_is_current(&self, plan: &plan::BumpPlan) -> Result<(), BumpError<VCS>> {
+        for (path, modification) in plan.modifications() {
+            let contents = match tokio::fs::read_to_string(path).await {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(err) => {
+                    return Err(files::ReplaceVersionError::from(IoError::new(err, path)).into());
+                }
+            };
+            if contents != modification.before {
+                return Err(BumpError::OutdatedPlan(path.clone()));
             }
         }
+        Ok(())
+    }
 
-        let ctx_with_new_version: HashMap<String, String> = context::get_context(
-            Some(&self.tag_and_revision),
-            Some(&current_version),
-            Some(&new_version),
-            Some(current_version_serialized),
-            Some(&new_version_serialized),
-        )
-        .collect();
-
-        let configured_files = Arc::new(configured_files);
-
-        let modifications = apply_modifications(
-            &configured_files,
-            &current_version,
-            &new_version,
-            &ctx_with_new_version,
-            self.config.global.dry_run,
-        )
-        .await?;
-
-        for (path, modification) in modifications {
+    /// Write the modified files, the config file and the changelog of `plan`, and log their
+    /// changes.
+    ///
+    /// Every written file is recorded in `transaction`, so it can be restored, and reported
+    /// in `file_reports`.
+    async fn write_files(
+        &self,
+        transaction: &mut files::Transaction,
+        file_reports: &mut Vec<report::FileReport>,
+        plan: &plan::BumpPlan,
+    ) -> Result<(), BumpError<VCS>> {
+        let dry_run = self.config.global.dry_run;
+        for (path, modification) in plan.files.iter().chain(plan.config_file.iter()) {
+            if let Some(modification) = modification
+                && !dry_run
+            {
+                transaction
+                    .write(path, modification)
+                    .await
+                    .map_err(files::ReplaceVersionError::from)?;
+            }
+            file_reports.push(report::FileReport::new(path, modification.as_ref()));
             self.logger.log(Verbosity::Low, "");
-            self.logger.log_modification(path, modification);
+            self.logger.log_modification(path, modification.clone());
         }
 
-        if let Some(ref config_file) = self.config_file {
-            let modification = self
-                .update_config_file(config_file, &ctx_with_new_version)
-                .await?;
+        if let Some((path, modification)) = &plan.changelog {
+            if !dry_run {
+                transaction
+                    .write(path, modification)
+                    .await
+                    .map_err(BumpError::Changelog)?;
+            }
+            file_reports.push(report::FileReport::new(path, Some(modification)));
             self.logger.log(Verbosity::Low, "");
             self.logger
-                .log_modification(config_file.path(), modification);
+                .log_modification(path, Some(modification.clone()));
         }
+        Ok(())
+    }
 
-        // The tag this bump is about to create, so hooks see the new name rather
-        // than the one already on the repository. `None` when tagging is off.
-        let new_version_tag = self.new_version_tag(&ctx_with_new_version)?;
-
-        self.run_pre_commit_hooks(
-            Some(&current_version),
-            Some(&new_version),
-            &new_version_serialized,
-            new_version_tag.as_deref(),
-        )
-        .await
-        .map_err(BumpError::PreCommitHook)?;
-
-        let additional_files = self.additional_files();
+    /// Unstage and restore the files written by a failed bump, unless `keep_on_failure` is set.
+    ///
+    /// Returns the error that failed the bump.
+    async fn roll_back(
+        &self,
+        transaction: files::Transaction,
+        err: BumpError<VCS>,
+    ) -> BumpError<VCS> {
+        if self.config.global.keep_on_failure || self.config.global.dry_run {
+            return err;
+        }
+        self.logger
+            .log(Verbosity::Low, &format!("{}", "[rollback]".magenta()));
+        if let Err(reset_err) = self.repo.reset(transaction.staged()).await {
+            tracing::error!("failed to unstage the bumped files: {reset_err}");
+            return err;
+        }
+        if let Err(rollback_err) = transaction.rollback().await {
+            tracing::error!("failed to restore the original files: {rollback_err}");
+            return err;
+        }
+        match err {
+            BumpError::PreCommitHook { source, .. } => BumpError::PreCommitHook {
+                source,
+                rolled_back: true,
+            },
+            err => err,
+        }
+    }
 
-        // TODO: warn for files that dirty but not in either configured or additional files
-        self.commit_changes(
-            &configured_files,
-            &additional_files,
-            current_version_serialized.to_string(),
-            new_version_serialized.clone(),
-            &ctx_with_new_version,
-        )
-        .await?;
+    /// Log a version under `label`, with its components at higher verbosity.
+    fn log_version(&self, label: &str, serialized: &str, version: &version::Version) {
+        self.logger
+            .log(Verbosity::Low, &format!("{}", format!("[{label}]").blue()));
+        self.logger
+            .log(Verbosity::Low, &format!("\t{}", serialized.yellow().bold()));
+        self.logger.log(
+            Verbosity::Medium,
+            &format!("\t{}", crate::logging::format_version(version, Color::Cyan)),
+        );
+    }
 
-        self.run_post_commit_hooks(
-            Some(&current_version),
-            Some(&new_version),
-            &new_version_serialized,
-            new_version_tag.as_deref(),
-        )
-        .await
-        .map_err(BumpError::PostCommitHook)?;
+    /// The spec of the configured components, displaying versions with the configured
+    /// `serialize` patterns.
+    fn version_spec(&self) -> version::VersionSpec {
+        version::VersionSpec::from_components(self.components.clone())
+            .with_serialize_version_patterns(self.config.global.serialize_version_patterns.clone())
+    }
 
+    /// Reject a `new_version` that is not newer than `current_version`, unless
+    /// `allow_downgrade` is set.
+    ///
+    /// Versions that cannot be compared, such as one with a value outside of the `values` of
+    /// its component, are let through.
+    fn check_upgrade(
+        &self,
+        current_version: &version::Version,
+        new_version: &version::Version,
+    ) -> Result<(), BumpError<VCS>> {
+        let is_downgrade = matches!(
+            new_version.partial_cmp(current_version),
+            Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
+        );
+        if is_downgrade && !self.config.global.allow_downgrade {
+            return Err(BumpError::Downgrade {
+                current_version: current_version.to_string(),
+                new_version: new_version.to_string(),
+            });
+        }
         Ok(())
     }
 
-    /// Bump the desired version component to the next value or set the version to `new_version`.
-    ///
-    /// # Errors
-    /// - When the no current version is present.
-    /// - When the current or next version are empty.
-    /// - When one of the user-provided setup, pre, or post-commit hooks fails.
-    /// - When the current version component cannot be bumped.
-    /// - When the next version cannot be serialized.
-    /// - When a version in a file cannot be replaced.
-    pub async fn bump(&self, bump: Bump<'_>) -> Result<(), BumpError<VCS>> {
+    /// Parse the configured current version.
+    fn current_version(&self) -> Result<(&str, version::Version), BumpError<VCS>> {
         let current_version_serialized = self
             .config
             .global
@@ -507,52 +670,175 @@ where
             "parsing current version"
         );
 
+        let current_version = self.parse_version(current_version_serialized)?;
+        Ok((current_version_serialized, current_version))
+    }
+
+    /// Parse a serialized version with the configured `parse` pattern.
+    fn parse_version(&self, serialized: &str) -> Result<version::Version, BumpError<VCS>> {
         let parse_version_pattern = &self.config.global.parse_version_pattern;
-        let version_spec = version::VersionSpec::from_components(self.components.clone());
-        let current_version = version::Version::parse(
-            current_version_serialized,
-            parse_version_pattern,
-            &version_spec,
-        );
-        // let current_version = self.current_version()?;
-        let current_version = current_version.ok_or_else(|| BumpError::EmptyVersion)?;
+        let version_spec = self.version_spec();
+        version::Version::parse(serialized, parse_version_pattern, &version_spec)
+            .ok_or_else(|| BumpError::EmptyVersion)
+    }
 
-        self.logger
-            .log(Verbosity::Low, &format!("{}", "[current version]".blue()));
-        self.logger.log(
-            Verbosity::Low,
-            &format!("\t{}", current_version_serialized.yellow().bold()),
-        );
-        self.logger.log(
-            Verbosity::Medium,
-            &format!(
-                "\t{}",
-                crate::logging::format_version(&current_version, Color::Cyan)
-            ),
-        );
+    /// The current and the new version of `plan`, parsed again with the configured `parse`
+    /// pattern.
+    ///
+    /// # Errors
+    /// - When either version does not match the `parse` pattern.
+    pub fn plan_versions(
+        &self,
+        plan: &plan::BumpPlan,
+    ) -> Result<(version::Version, version::Version), BumpError<VCS>> {
+        Ok((
+            self.parse_version(&plan.current_version_serialized)?,
+            self.parse_version(&plan.new_version_serialized)?,
+        ))
+    }
 
-        self.run_setup_hooks(Some(&current_version))
-            .await
-            .map_err(BumpError::SetupHook)?;
+    /// The files committed with a bump of `configured_files`: those, the `additional_files`,
+    /// the config file and the changelog.
+    fn files_to_commit<'p>(
+        &self,
+        configured_files: impl IntoIterator<Item = &'p PathBuf>,
+        additional_files: &[impl AsRef<Path>],
+    ) -> Vec<PathBuf> {
+        // Insertion-ordered: the staged files are logged in this order, and a set
+        // whose iteration order varies between runs would make the verbose report
+        // differ from one identical invocation to the next.
+        let mut files_to_commit: IndexSet<PathBuf> = configured_files
+            .into_iter()
+            .cloned()
+            .chain(
+                additional_files
+                    .iter()
+                    .map(|path| path.as_ref().to_path_buf()),
+            )
+            .collect();
 
-        let new_version = match bump {
-            Bump::Component(comp_name) => {
-                tracing::info!(
-                    component = comp_name.to_string(),
-                    "attempting to increment version component"
-                );
-                current_version.bump(comp_name).map_err(Into::into)
-            }
-            Bump::NewVersion(new_version) => {
-                tracing::info!(new_version, "parse new version");
-                version::Version::parse(new_version, parse_version_pattern, &version_spec)
-                    .ok_or_else(|| BumpError::EmptyVersion)
-            }
-        }?;
+        if let Some(ref config_file) = self.config_file {
+            files_to_commit.insert(config_file.path().to_path_buf());
+        }
+        if let Some(changelog_path) = self.changelog_path() {
+            files_to_commit.insert(changelog_path);
+        }
+        files_to_commit.into_iter().collect()
+    }
 
-        tracing::info!(new_version = new_version.to_string(), "next version");
+    /// The commit of `files`, if committing is enabled.
+    fn commit_plan(
+        &self,
+        files: Vec<PathBuf>,
+        ctx: &HashMap<String, String>,
+    ) -> Result<Option<plan::CommitPlan>, BumpError<VCS>> {
+        if !self.config.global.commit {
+            return Ok(None);
+        }
+        let args = self
+            .config
+            .global
+            .commit_args
+            .as_deref()
+            .and_then(shlex::split)
+            .unwrap_or_default();
+        let message = self.config.global.commit_message.format(ctx, true)?;
+        Ok(Some(plan::CommitPlan {
+            files,
+            message,
+            args,
+        }))
+    }
 
-        let ctx_without_new_version: HashMap<String, String> = context::get_context(
+    /// The tag for the new version, if tagging is enabled.
+    async fn tag_plan(
+        &self,
+        ctx: &HashMap<String, String>,
+    ) -> Result<Option<plan::TagPlan>, BumpError<VCS>> {
+        if !self.config.global.tag {
+            return Ok(None);
+        }
+        let name = self.config.global.tag_name.format(ctx, true)?;
+        let message = self.config.global.tag_message.format(ctx, true)?;
+        let existing_tags = self.repo.tags().await.map_err(BumpError::VCS)?;
+        let exists = existing_tags.contains(&name);
+        let tag_conflict = self.config.global.tag_conflict;
+        if exists && tag_conflict == config::TagConflict::Error {
+            return Err(BumpError::TagConflict(name));
+        }
+        let replace = exists && tag_conflict == config::TagConflict::Replace;
+        // An existing release tag is left alone, and so are the tags that follow it.
+        let moved: IndexSet<String> = if exists && !replace {
+            IndexSet::new()
+        } else {
+            self.config
+                .global
+                .moveable_tags
+                .iter()
+                .map(|moveable_tag| moveable_tag.format(ctx, true))
+                .filter(|moved| moved.as_ref().ok().is_none_or(|moved| *moved != name))
+                .collect::<Result<_, _>>()?
+        };
+        Ok(Some(plan::TagPlan {
+            exists,
+            replace,
+            name,
+            message,
+            sign: self.config.global.sign_tags,
+            moved: moved.into_iter().collect(),
+        }))
+    }
+
+    /// The push of the new commit and tag, if pushing is enabled.
+    ///
+    /// The current branch is only pushed along with a commit, and the tag and the moved tags only
+    /// if the tag is created. A replaced tag is force-pushed along with the moved tags.
+    fn push_plan(
+        &self,
+        commit: Option<&plan::CommitPlan>,
+        tag: Option<&plan::TagPlan>,
+    ) -> Option<plan::PushPlan> {
+        if !self.config.global.push {
+            return None;
+        }
+        let branch = commit
+            .and(self.tag_and_revision.revision.as_ref())
+            .map(|revision| revision.branch_name.as_str())
+            .filter(|branch| *branch != "HEAD")
+            .map(ToString::to_string);
+        let created_tag = tag.filter(|tag| !tag.exists || tag.replace);
+        let moved_tags = created_tag
+            .map(|tag| {
+                let replaced = tag.replace.then(|| tag.name.clone());
+                replaced.into_iter().chain(tag.moved.clone()).collect()
+            })
+            .unwrap_or_default();
+        let tag = created_tag
+            .filter(|tag| !tag.replace)
+            .map(|tag| tag.name.clone());
+        if branch.is_none() && tag.is_none() {
+            return None;
+        }
+        Some(plan::PushPlan {
+            remote: self.config.global.push_remote.clone(),
+            branch,
+            tag,
+            moved_tags,
+        })
+    }
+
+    /// Check that every configured file contains the current version, and that the current
+    /// version is the version of the latest tag.
+    ///
+    /// Nothing is written; every disagreement is collected in the returned report.
+    ///
+    /// # Errors
+    /// - When the no current version is present.
+    /// - When the current version is empty.
+    /// - When a configured file cannot be read, or its search pattern cannot be rendered.
+    pub async fn check(&self) -> Result<check::CheckReport, BumpError<VCS>> {
+        let (current_version_serialized, current_version) = self.current_version()?;
+        let ctx: HashMap<String, String> = context::get_context(
             Some(&self.tag_and_revision),
             Some(&current_version),
             None,
@@ -561,42 +847,542 @@ where
         )
         .collect();
 
-        let serialize_version_patterns = &self.config.global.serialize_version_patterns;
-        let new_version_serialized =
-            new_version.serialize(serialize_version_patterns, &ctx_without_new_version)?;
-        tracing::info!(version = new_version_serialized, "next version");
-
-        self.logger
-            .log(Verbosity::Low, &format!("{}", "[new version]".blue()));
-        self.logger.log(
-            Verbosity::Low,
-            &format!("\t{}", new_version_serialized.yellow().bold()),
-        );
-        self.logger.log(
-            Verbosity::Medium,
-            &format!(
-                "\t{}",
-                crate::logging::format_version(&new_version, Color::Cyan)
-            ),
-        );
+        let mut report = check::CheckReport {
+            current_version: current_version_serialized.to_string(),
+            files: vec![],
+            problems: vec![],
+        };
+        for (path, changes) in self.configured_files() {
+            if path.is_file() {
+                let contents = tokio::fs::read_to_string(&path).await.map_err(|source| {
+                    files::ReplaceVersionError::from(IoError::new(source, &path))
+                })?;
+                report.problems.extend(check::check_file(
+                    &path,
+                    &contents,
+                    &changes,
+                    &current_version,
+                    &ctx,
+                )?);
+            } else if !changes.iter().all(|change| change.ignore_missing_file) {
+                report.problems.push(check::Problem::MissingFile {
+                    path: path.clone(),
+                    location: changes
+                        .first()
+                        .map(|change| change.location.clone())
+                        .unwrap_or_default(),
+                });
+            }
+            report.files.push(path);
+        }
 
-        self.apply_version_bump(
-            bump,
-            current_version,
-            current_version_serialized,
-            new_version,
-            new_version_serialized,
-        )
-        .await
+        if let Some(tag) = &self.tag_and_revision.tag
+            && tag.current_version != current_version_serialized
+        {
+            report.problems.push(check::Problem::TagMismatch {
+                current_version: current_version_serialized.to_string(),
+                tag: tag.current_tag.clone(),
+                tagged_version: tag.current_version.clone(),
+            });
+        }
+        Ok(report)
     }
 
-    /// Finalizes a version bump that is already applied to the working tree.
+    /// Describe the working tree as a development version, without touching the repository.
     ///
-    /// The latest VCS tag supplies the previous version, and the configured current version is the
-    /// release target.
-    /// This method does not replace version strings or rerun setup hooks.
+    /// On a clean checkout of the latest tag, this is the current version. Otherwise, the
+    /// `dev_version` template is rendered with the distance to the latest tag, the commit and
+    /// whether the tree is dirty. With a `component`, `new_version` and the `new_*` components
+    /// hold the version a bump of it would produce, as for `{new_version}-dev.{distance_to_latest_tag}`.
+    ///
+    /// # Errors
+    /// - When the no current version is present.
+    /// - When the current version is empty.
+    /// - When there is no tagged version.
+    /// - When the `component` cannot be bumped, or the template cannot be rendered.
+    pub fn describe(
+        &self,
+        component: Option<&str>,
+    ) -> Result<report::DescribeReport, BumpError<VCS>> {
+        let (current_version_serialized, current_version) = self.current_version()?;
+        let tag = self
+            .tag_and_revision
+            .tag
+            .as_ref()
+            .ok_or(BumpError::MissingTag)?;
+
+        let ctx_without_new_version: HashMap<String, String> = context::get_context(
+            Some(&self.tag_and_revision),
+            Some(&current_version),
+            None,
+            Some(current_version_serialized),
+            None,
+        )
+        .collect();
+        let new_version = component
+            .map(|component| current_version.bump(component))
+            .transpose()?;
+        let new_version_serialized = new_version
+            .as_ref()
+            .map(|new_version| {
+                new_version.serialize(
+                    &self.config.global.serialize_version_patterns,
+                    &ctx_without_new_version,
+                )
+            })
+            .transpose()?;
+
+        let version = if tag.distance_to_latest_tag == 0 && !tag.dirty {
+            current_version_serialized.to_string()
+        } else {
+            let ctx: HashMap<String, String> = context::get_context(
+                Some(&self.tag_and_revision),
+                Some(&current_version),
+                new_version.as_ref(),
+                Some(current_version_serialized),
+                new_version_serialized.as_deref(),
+            )
+            .collect();
+            self.config.global.dev_version.format(&ctx, true)?
+        };
+        Ok(report::DescribeReport {
+            version,
+            current_version: current_version_serialized.to_string(),
+            tag: tag.current_tag.clone(),
+            distance_to_latest_tag: tag.distance_to_latest_tag,
+            commit_sha: tag.commit_sha.clone(),
+            dirty: tag.dirty,
+        })
+    }
+
+    /// Plan a bump without touching the repository.
+    ///
+    /// The plan holds the versions, the modification of every file, the commit, the tag, the push
+    /// and the hooks, and can be carried out later with [`BumpVersion::apply`].
+    ///
+    /// # Errors
+    /// - When the no current version is present.
+    /// - When the current or next version are empty.
+    /// - When the current version component cannot be bumped.
+    /// - When the next version is not newer than the current one, unless `allow_downgrade` is set.
+    /// - When the next version cannot be serialized.
+    /// - When the preflight finds a missing file, a file without the current version or a template
+    ///   that cannot be rendered.
+    /// - When a version in a file cannot be replaced.
+    /// - When the tag of the new version already exists and `tag_conflict` is `error`.
+    pub async fn plan(&self, bump: Bump<'_>) -> Result<plan::BumpPlan, BumpError<VCS>> {
+        let (current_version_serialized, current_version) = self.current_version()?;
+        let new_version = self.next_version(&current_version, &bump)?;
+        tracing::info!(new_version = new_version.to_string(), "next version");
+        self.check_upgrade(&current_version, &new_version)?;
+
+        let ctx_without_new_version: HashMap<String, String> = context::get_context(
+            Some(&self.tag_and_revision),
+            Some(&current_version),
+            None,
+            Some(current_version_serialized),
+            None,
+        )
+        .collect();
+
+        let serialize_version_patterns = &self.config.global.serialize_version_patterns;
+        let new_version_serialized =
+            new_version.serialize(serialize_version_patterns, &ctx_without_new_version)?;
+        tracing::info!(version = new_version_serialized, "next version");
+
+        let mut plan = plan::BumpPlan {
+            current_version_serialized: current_version_serialized.to_string(),
+            new_version_serialized,
+            files: vec![],
+            config_file: None,
+            changelog: None,
+            commit: None,
+            tag: None,
+            push: None,
+            hooks: plan::HookPlan {
+                setup: self.config.global.setup_hooks.clone(),
+                pre_commit: self.config.global.pre_commit_hooks.clone(),
+                post_commit: self.config.global.post_commit_hooks.clone(),
+            },
+        };
+        if plan.is_noop() {
+            tracing::info!(
+                version = plan.new_version_serialized,
+                "next version matches current version"
+            );
+            return Ok(plan);
+        }
+
+        let mut configured_files = self.configured_files();
+
+        // filter the files that are not valid for this bump
+        if let Bump::Component(version_component_to_bump)
+        | Bump::Pre(Some(version_component_to_bump)) = bump
+        {
+            for changes in configured_files.values_mut() {
+                changes.retain(|change| change.will_bump_component(version_component_to_bump));
+                changes.retain(|change| !change.will_not_bump_component(version_component_to_bump));
+            }
+        }
+
+        let ctx_with_new_version: HashMap<String, String> = context::get_context(
+            Some(&self.tag_and_revision),
+            Some(&current_version),
+            Some(&new_version),
+            Some(&plan.current_version_serialized),
+            Some(&plan.new_version_serialized),
+        )
+        .collect();
+
+        let problems = self
+            .preflight(
+                &configured_files,
+                (&current_version, &new_version),
+                &ctx_with_new_version,
+            )
+            .await?;
+        if problems.iter().any(check::Problem::is_error) {
+            return Err(BumpError::Preflight(problems));
+        }
+
+        plan.files = apply_modifications(
+            &configured_files,
+            &current_version,
+            &new_version,
+            &ctx_with_new_version,
+            true,
+        )
+        .await?
+        .into_iter()
+        .map(|(path, modification)| (path.clone(), modification))
+        .collect();
+
+        if let Some(ref config_file) = self.config_file {
+            let modification = self
+                .config_file_modification(config_file, &ctx_with_new_version, true)
+                .await?;
+            plan.config_file = Some((config_file.path().to_path_buf(), modification));
+        }
+
+        if let Some(path) = self.changelog_path() {
+            let modification = self.update_changelog(&path, &ctx_with_new_version).await?;
+            plan.changelog = Some((path, modification));
+        }
+
+        // TODO: warn for files that dirty but not in either configured or additional files
+        let files_to_commit =
+            self.files_to_commit(configured_files.keys(), &self.additional_files());
+        plan.commit = self.commit_plan(files_to_commit, &ctx_with_new_version)?;
+        plan.tag = self.tag_plan(&ctx_with_new_version).await?;
+        plan.push = self.push_plan(plan.commit.as_ref(), plan.tag.as_ref());
+        Ok(plan)
+    }
+
+    /// Find every problem that would fail the bump from the current to the new version, before
+    /// any file is touched.
+    ///
+    /// Renders the commit message, the tag name and message, the moveable tags and the `search`
+    /// and `replace` of every configured file, and checks that every file exists and contains
+    /// the current version, and whether the tag already exists.
+    async fn preflight(
+        &self,
+        configured_files: &IndexMap<PathBuf, Vec<config::change::FileChange>>,
+        (current_version, new_version): (&version::Version, &version::Version),
+        ctx: &HashMap<String, String>,
+    ) -> Result<Vec<check::Problem>, BumpError<VCS>> {
+        let mut problems = vec![];
+        for (path, changes) in configured_files {
+            if path.is_file() {
+                let contents = tokio::fs::read_to_string(path).await.map_err(|source| {
+                    files::ReplaceVersionError::from(IoError::new(source, path))
+                })?;
+                problems.extend(check::preflight_file(
+                    path,
+                    &contents,
+                    changes,
+                    current_version,
+                    new_version,
+                    ctx,
+                )?);
+            } else if !changes.iter().all(|change| change.ignore_missing_file) {
+                problems.push(check::Problem::MissingFile {
+                    path: path.clone(),
+                    location: changes
+                        .first()
+                        .map(|change| change.location.clone())
+                        .unwrap_or_default(),
+                });
+            }
+        }
+
+        let global = &self.config.global;
+        let mut templates = vec![];
+        if global.commit {
+            templates.push(("commit_message", &global.commit_message));
+        }
+        if global.tag {
+            templates.push(("tag_name", &global.tag_name));
+            templates.push(("tag_message", &global.tag_message));
+            templates.extend(
+                global
+                    .moveable_tags
+                    .iter()
+                    .map(|moveable_tag| ("moveable_tags", moveable_tag)),
+            );
+        }
+        for (setting, template) in templates {
+            if let Err(err) = template.format(ctx, true) {
+                problems.push(check::Problem::InvalidTemplate {
+                    setting: setting.to_string(),
+                    path: None,
+                    message: err.to_string(),
+                    location: diagnostics::Location::default(),
+                });
+            }
+        }
+
+        if global.tag
+            && let Ok(tag) = global.tag_name.format(ctx, true)
+            && self
+                .repo
+                .tags()
+                .await
+                .map_err(BumpError::VCS)?
+                .contains(&tag)
+        {
+            problems.push(check::Problem::TagExists {
+                tag,
+                conflict: global.tag_conflict,
+            });
+        }
+        Ok(problems)
+    }
+
+    /// Carry out a `plan`, after its setup hooks ran.
+    async fn execute(&self, plan: plan::BumpPlan) -> Result<report::BumpReport, BumpError<VCS>> {
+        let (current_version, new_version) = self.plan_versions(&plan)?;
+        self.log_version("new version", &plan.new_version_serialized, &new_version);
+
+        let mut report = report::BumpReport {
+            dry_run: self.config.global.dry_run,
+            current_version: report::VersionReport::new(
+                &plan.current_version_serialized,
+                &current_version,
+            ),
+            new_version: report::VersionReport::new(&plan.new_version_serialized, &new_version),
+            files: vec![],
+            commit: None,
+            tag: None,
+            push: None,
+        };
+        if plan.is_noop() {
+            return Ok(report);
+        }
+
+        if self.config.global.dry_run {
+            tracing::info!("dry run active, won't touch any files.");
+        }
+
+        self.check_plan_is_current(&plan).await?;
+
+        // The tag this bump is about to create, so hooks see the new name rather
+        // than the one already on the repository. `None` when tagging is off.
+        let new_version_tag = plan.tag.as_ref().map(|tag| tag.name.as_str());
+
+        let mut transaction = files::Transaction::default();
+        let result = async {
+            self.write_files(&mut transaction, &mut report.files, &plan)
+                .await?;
+
+            self.run_pre_commit_hooks(
+                &plan.hooks.pre_commit,
+                Some(&current_version),
+                Some(&new_version),
+                &plan.new_version_serialized,
+                new_version_tag,
+            )
+            .await
+            .map_err(|source| BumpError::PreCommitHook {
+                source,
+                rolled_back: false,
+            })?;
+
+            self.commit(
+                &mut transaction,
+                plan.commit.as_ref(),
+                &plan.current_version_serialized,
+                &plan.new_version_serialized,
+            )
+            .await
+        }
+        .await;
+        if let Err(err) = result {
+            return Err(self.roll_back(transaction, err).await);
+        }
+
+        self.create_tag(plan.tag.as_ref()).await?;
+
+        self.run_post_commit_hooks(
+            &plan.hooks.post_commit,
+            Some(&current_version),
+            Some(&new_version),
+            &plan.new_version_serialized,
+            new_version_tag,
+        )
+        .await
+        .map_err(BumpError::PostCommitHook)?;
+
+        self.push(plan.push.as_ref()).await?;
+
+        report.commit = plan.commit.map(|commit| report::CommitReport {
+            files: commit.files,
+            message: commit.message,
+        });
+        report.tag = plan.tag.map(|tag| report::TagReport {
+            name: tag.name,
+            message: tag.message,
+            sign: tag.sign,
+            created: !tag.exists || tag.replace,
+            replaced: tag.replace,
+            moved: tag.moved,
+        });
+        report.push = plan.push.map(|push| report::PushReport {
+            remote: push.remote,
+            branch: push.branch,
+            tag: push.tag,
+            moved_tags: push.moved_tags,
+        });
+        Ok(report)
+    }
+
+    /// Apply a `plan` made by [`BumpVersion::plan`].
+    ///
+    /// Runs the setup hooks, writes the planned contents of every file, runs the pre-commit
+    /// hooks, commits, tags, runs the post-commit hooks and pushes, exactly as planned.
+    ///
+    /// The plan may have been serialized and read back since, so its versions are parsed again.
+    ///
+    /// # Errors
+    /// - When the versions of the plan do not match the `parse` pattern.
+    /// - When a file the plan writes changed since it was planned.
+    /// - When one of the setup, pre, or post-commit hooks fails.
+    /// - When a file cannot be written, or the commit, tag or push fails.
+    pub async fn apply(&self, plan: plan::BumpPlan) -> Result<report::BumpReport, BumpError<VCS>> {
+        let current_version = self.parse_version(&plan.current_version_serialized)?;
+        self.log_version(
+            "current version",
+            &plan.current_version_serialized,
+            &current_version,
+        );
+        self.run_setup_hooks(&plan.hooks.setup, Some(&current_version))
+            .await
+            .map_err(BumpError::SetupHook)?;
+        self.execute(plan).await
+    }
+
+    /// Detect the bump from the Conventional Commits since the latest tag.
+    ///
+    /// Commit types are mapped onto components by the `conventional_commits` config, and the most
+    /// significant component any commit asks for is bumped.
+    ///
+    /// # Errors
+    /// - When the commit messages cannot be read.
+    /// - When no commit asks for a bump.
+    pub async fn auto_bump(&self) -> Result<Bump<'_>, BumpError<VCS>> {
+        let since = self
+            .tag_and_revision
+            .tag
+            .as_ref()
+            .map(|tag| tag.current_tag.as_str());
+        let messages = self
+            .repo
+            .commit_messages(since)
+            .await
+            .map_err(BumpError::VCS)?;
+        tracing::debug!(commits = messages.len(), since, "classifying commits");
+
+        let component = conventional_commits::component_to_bump(
+            &messages,
+            &self.config.global.conventional_commits,
+            &self.components,
+        )
+        .ok_or_else(|| BumpError::NoReleasableCommits(since.map(ToString::to_string)))?;
+        Ok(Bump::Component(component))
+    }
+
+    /// The version that `bump` turns `current_version` into.
+    fn next_version(
+        &self,
+        current_version: &version::Version,
+        bump: &Bump<'_>,
+    ) -> Result<version::Version, BumpError<VCS>> {
+        let parse_version_pattern = &self.config.global.parse_version_pattern;
+        let version_spec = self.version_spec();
+        match *bump {
+            Bump::Component(comp_name) => {
+                tracing::info!(
+                    component = comp_name.to_string(),
+                    "attempting to increment version component"
+                );
+                current_version.bump(comp_name).map_err(Into::into)
+            }
+            Bump::NewVersion(new_version) => {
+                tracing::info!(new_version, "parse new version");
+                version::Version::parse(new_version, parse_version_pattern, &version_spec)
+                    .ok_or_else(|| BumpError::EmptyVersion)
+            }
+            Bump::Pre(comp_name) => {
+                tracing::info!(component = comp_name, "attempting to enter pre-release");
+                current_version.pre(comp_name).map_err(Into::into)
+            }
+            Bump::Promote => {
+                tracing::info!("attempting to promote pre-release");
+                current_version.promote().map_err(Into::into)
+            }
+            Bump::Release => {
+                tracing::info!("attempting to release pre-release");
+                current_version.release().map_err(Into::into)
+            }
+        }
+    }
+
+    /// Bump the desired version component to the next value or set the version to `new_version`.
+    ///
+    /// Runs the setup hooks, then [plans](BumpVersion::plan) the bump and applies it.
+    ///
+    /// # Errors
+    /// - When the no current version is present.
+    /// - When the current or next version are empty.
+    /// - When one of the user-provided setup, pre, or post-commit hooks fails.
+    /// - When the current version component cannot be bumped.
+    /// - When the next version is not newer than the current one, unless `allow_downgrade` is set.
+    /// - When the next version cannot be serialized.
+    /// - When the preflight finds a missing file, a file without the current version or a template
+    ///   that cannot be rendered.
+    /// - When a version in a file cannot be replaced.
+    pub async fn bump(&self, bump: Bump<'_>) -> Result<report::BumpReport, BumpError<VCS>> {
+        let (current_version_serialized, current_version) = self.current_version()?;
+        self.log_version(
+            "current version",
+            current_version_serialized,
+            &current_version,
+        );
+
+        self.run_setup_hooks(&self.config.global.setup_hooks, Some(&current_version))
+            .await
+            .map_err(BumpError::SetupHook)?;
+
+        let plan = self.plan(bump).await?;
+        self.execute(plan).await
+    }
+
+    /// Finalizes a version bump that is already applied to the working tree.
+    ///
+    /// The latest VCS tag supplies the previous version, and the configured current version is the
+    /// release target.
+    /// This method does not replace version strings or rerun setup hooks.
     /// It reruns pre-commit hooks before committing configured and additional files, creates the
-    /// configured tag, and then runs post-commit hooks.
+    /// configured tag, runs post-commit hooks, and then pushes if `push` is enabled.
     ///
     /// # Errors
     ///
@@ -621,7 +1407,7 @@ where
         }
 
         let parse_version_pattern = &self.config.global.parse_version_pattern;
-        let version_spec = version::VersionSpec::from_components(self.components.clone());
+        let version_spec = self.version_spec();
         let current_version = version::Version::parse(
             current_version_serialized,
             parse_version_pattern,
@@ -643,26 +1429,35 @@ where
         let new_version_tag = self.new_version_tag(&context)?;
 
         self.run_pre_commit_hooks(
+            &self.config.global.pre_commit_hooks,
             Some(&current_version),
             Some(&new_version),
             new_version_serialized,
             new_version_tag.as_deref(),
         )
         .await
-        .map_err(BumpError::PreCommitHook)?;
+        .map_err(|source| BumpError::PreCommitHook {
+            source,
+            rolled_back: false,
+        })?;
 
         let configured_files = self.configured_files();
-        let additional_files = self.additional_files();
-        self.commit_changes(
-            &configured_files,
-            &additional_files,
-            current_version_serialized.clone(),
-            new_version_serialized.clone(),
-            &context,
+        let files_to_commit =
+            self.files_to_commit(configured_files.keys(), &self.additional_files());
+        let commit = self.commit_plan(files_to_commit, &context)?;
+        let tag = self.tag_plan(&context).await?;
+        let push = self.push_plan(commit.as_ref(), tag.as_ref());
+        self.commit(
+            &mut files::Transaction::default(),
+            commit.as_ref(),
+            current_version_serialized,
+            new_version_serialized,
         )
         .await?;
+        self.create_tag(tag.as_ref()).await?;
 
         self.run_post_commit_hooks(
+            &self.config.global.post_commit_hooks,
             Some(&current_version),
             Some(&new_version),
             new_version_serialized,
@@ -671,6 +1466,8 @@ where
         .await
         .map_err(BumpError::PostCommitHook)?;
 
+        self.push(push.as_ref()).await?;
+
         Ok(())
     }
 
@@ -698,6 +1495,21 @@ where
         config_file: &config::ConfigFile,
         ctx: &HashMap<K, V>,
     ) -> Result<Option<files::Modification>, BumpError<VCS>>
+    where
+        K: std::borrow::Borrow<str> + std::hash::Hash + Eq + std::fmt::Debug,
+        V: AsRef<str> + std::fmt::Debug,
+    {
+        self.config_file_modification(config_file, ctx, self.config.global.dry_run)
+            .await
+    }
+
+    /// Replace the version in the configuration file, writing it unless `dry_run` is set.
+    async fn config_file_modification<K, V>(
+        &self,
+        config_file: &config::ConfigFile,
+        ctx: &HashMap<K, V>,
+        dry_run: bool,
+    ) -> Result<Option<files::Modification>, BumpError<VCS>>
     where
         K: std::borrow::Borrow<str> + std::hash::Hash + Eq + std::fmt::Debug,
         V: AsRef<str> + std::fmt::Debug,
@@ -712,24 +1524,52 @@ where
         if config_path.starts_with(working_dir) {
             let modification = match config_file {
                 config::ConfigFile::SetupCfg(_) | config::ConfigFile::BumpversionCfg(_) => {
-                    config::ini::replace_version(
+                    config::ini::replace_version(config_path, &self.config, ctx, dry_run).await
+                }
+                config::ConfigFile::PyProject(_) | config::ConfigFile::BumpversionToml(_) => {
+                    let package_key_path;
+                    let key_paths: &[&[&str]] = match self.config.package.as_deref() {
+                        Some(name) => {
+                            package_key_path =
+                                ["tool", "bumpversion", "packages", name, "current_version"];
+                            &[&package_key_path]
+                        }
+                        None => &[&["tool", "bumpversion", "current_version"]],
+                    };
+                    config::toml::replace_version(
                         config_path,
+                        key_paths,
                         &self.config,
                         ctx,
-                        self.config.global.dry_run,
+                        dry_run,
                     )
                     .await
                 }
-                config::ConfigFile::PyProject(_) | config::ConfigFile::BumpversionToml(_) => {
+                config::ConfigFile::CargoToml(_) => {
+                    let cargo_toml =
+                        tokio::fs::read_to_string(config_path)
+                            .await
+                            .map_err(|source| {
+                                files::ReplaceVersionError::from(IoError::new(source, config_path))
+                            })?;
+                    // an invalid manifest fails to parse again when it is replaced
+                    let key_paths = toml_span::parse(&cargo_toml)
+                        .map(|value| {
+                            config::cargo_toml::current_version_key_paths(
+                                &value,
+                                self.config.package.as_deref(),
+                            )
+                        })
+                        .unwrap_or_default();
                     config::toml::replace_version(
                         config_path,
+                        &key_paths,
                         &self.config,
                         ctx,
-                        self.config.global.dry_run,
+                        dry_run,
                     )
                     .await
                 }
-                config::ConfigFile::CargoToml(_) => Ok(None),
             }?;
 
             Ok(modification)
@@ -741,145 +1581,197 @@ where
         }
     }
 
-    /// Stage and commit versioned files, and optionally create a VCS tag.
-    ///
-    /// This will:
-    /// 1. Collect files modified according to `configured_files` and any `additional_files`.
-    /// 2. Stage these files along with the configuration file if present.
-    /// 3. Commit with the configured commit message and arguments.
-    /// 4. If tagging is enabled in config, create a new tag with the configured name and message.
+    /// Stage and commit the planned files, if committing is enabled.
     ///
-    /// # Arguments
-    /// * `configured_files` - Map of file paths to their version change instructions.
-    /// * `additional_files` - Extra file paths to include in the commit (e.g., config file).
-    /// * `current_version_serialized` - Previous version string before bump.
-    /// * `next_version_serialized` - New version string after bump.
-    /// * `ctx` - Context map used to format commit and tag templates.
-    ///
-    /// # Errors
-    /// Returns a [`BumpError<VCS>`] if staging, committing, or tagging fails,
-    /// or if message/tag formatting encounters an error.
-    pub async fn commit_changes(
+    /// The staged files are recorded in `transaction`, so a rollback can unstage them.
+    async fn commit(
         &self,
-        configured_files: &IndexMap<PathBuf, Vec<config::change::FileChange>>,
-        additional_files: &[impl AsRef<Path>],
-        current_version_serialized: String,
-        next_version_serialized: String,
-        ctx: &HashMap<String, String>,
+        transaction: &mut files::Transaction,
+        commit: Option<&plan::CommitPlan>,
+        current_version_serialized: &str,
+        next_version_serialized: &str,
     ) -> Result<(), BumpError<VCS>> {
-        let extra_args = self
-            .config
-            .global
-            .commit_args
-            .as_deref()
-            .and_then(shlex::split)
-            .unwrap_or_default();
+        let Some(commit) = commit else {
+            return Ok(());
+        };
 
-        // Insertion-ordered: the staged files are logged in this order, and a set
-        // whose iteration order varies between runs would make the verbose report
-        // differ from one identical invocation to the next.
-        let mut files_to_commit: IndexSet<&Path> = configured_files
-            .keys()
-            .map(PathBuf::as_path)
-            .chain(additional_files.iter().map(std::convert::AsRef::as_ref))
-            .collect();
+        self.logger
+            .log(Verbosity::Low, &format!("{}", "[commit]".magenta()));
 
-        if let Some(ref config_file) = self.config_file {
-            // add config file
-            files_to_commit.insert(config_file.path());
+        for path in &commit.files {
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{} {}", "   add".dimmed(), path.to_string_lossy().cyan()),
+            );
         }
-
-        if self.config.global.commit {
-            self.logger
-                .log(Verbosity::Low, &format!("{}", "[commit]".magenta()));
-
-            for path in &files_to_commit {
-                self.logger.log(
-                    Verbosity::Low,
-                    &format!("\t{} {}", "   add".dimmed(), path.to_string_lossy().cyan()),
-                );
-            }
-            if !self.config.global.dry_run {
+        if !self.config.global.dry_run {
+            self.repo.add(&commit.files).await.map_err(BumpError::VCS)?;
+            // the bump creates the changelog if there is none yet
+            if let Some(changelog) = self
+                .changelog_path()
+                .filter(|path| commit.files.contains(path))
+            {
                 self.repo
-                    .add(&files_to_commit)
+                    .add_new([&changelog])
                     .await
                     .map_err(BumpError::VCS)?;
             }
+            transaction.stage(&commit.files);
+        }
 
-            let commit_message = self.config.global.commit_message.format(ctx, true)?;
-            tracing::info!(msg = commit_message, "commit");
+        tracing::info!(msg = commit.message, "commit");
 
-            self.logger.log(
-                Verbosity::Low,
-                &format!("\t{} {}", "commit".dimmed(), commit_message.cyan()),
-            );
+        self.logger.log(
+            Verbosity::Low,
+            &format!("\t{} {}", "commit".dimmed(), commit.message.cyan()),
+        );
 
-            if !self.config.global.dry_run {
-                // Only the commit-specific variables. `git` inherits the rest from
-                // this process, so copying them in adds nothing and would expose
-                // them in any `Debug` rendering of the command.
-                let env = [
-                    ("HGENCODING".to_string(), "utf-8".to_string()),
-                    (
-                        "BUMPVERSION_CURRENT_VERSION".to_string(),
-                        current_version_serialized,
-                    ),
-                    (
-                        "BUMPVERSION_NEW_VERSION".to_string(),
-                        next_version_serialized,
-                    ),
-                ];
-                self.repo
-                    .commit(commit_message.as_str(), extra_args.as_slice(), env)
-                    .await
-                    .map_err(BumpError::VCS)?;
-            }
+        if !self.config.global.dry_run {
+            // Only the commit-specific variables. `git` inherits the rest from
+            // this process, so copying them in adds nothing and would expose
+            // them in any `Debug` rendering of the command.
+            let env = [
+                ("HGENCODING".to_string(), "utf-8".to_string()),
+                (
+                    "BUMPVERSION_CURRENT_VERSION".to_string(),
+                    current_version_serialized.to_string(),
+                ),
+                (
+                    "BUMPVERSION_NEW_VERSION".to_string(),
+                    next_version_serialized.to_string(),
+                ),
+            ];
+            self.repo
+                .commit(commit.message.as_str(), commit.args.as_slice(), env)
+                .await
+                .map_err(BumpError::VCS)?;
         }
 
-        if self.config.global.tag {
-            let sign_tag = self.config.global.sign_tags;
-            let tag_name = self.config.global.tag_name.format(ctx, true)?;
-            let tag_message = self.config.global.tag_message.format(ctx, true)?;
+        Ok(())
+    }
 
-            tracing::info!(msg = tag_message, name = tag_name, "tag");
+    /// Create the planned tag, if tagging is enabled.
+    async fn create_tag(&self, tag: Option<&plan::TagPlan>) -> Result<(), BumpError<VCS>> {
+        let Some(tag) = tag else {
+            return Ok(());
+        };
 
-            let existing_tags = self.repo.tags().await.map_err(BumpError::VCS)?;
+        tracing::info!(msg = tag.message, name = tag.name, "tag");
 
-            self.logger
-                .log(Verbosity::Low, &format!("{}", "[tag]".magenta()));
+        self.logger
+            .log(Verbosity::Low, &format!("{}", "[tag]".magenta()));
 
-            if existing_tags.contains(&tag_name) {
+        if tag.exists && !tag.replace {
+            self.logger.log(
+                Verbosity::Low,
+                &format!(
+                    "\t{}",
+                    format!("tag {} already exists and will not be created", tag.name).dimmed()
+                ),
+            );
+            tracing::warn!("tag {:?} already exists and will not be created", tag.name);
+        } else {
+            if tag.replace {
                 self.logger.log(
                     Verbosity::Low,
                     &format!(
                         "\t{}",
-                        format!("tag {tag_name} already exists and will not be created").dimmed()
+                        format!("tag {} already exists and will be replaced", tag.name).dimmed()
                     ),
                 );
-                tracing::warn!("tag {tag_name:?} already exists and will not be created");
-            } else {
-                self.logger.log(
-                    Verbosity::Low,
-                    &format!("\t{}{}", "tag = ".dimmed(), tag_name.yellow()),
-                );
-                self.logger.log(
-                    Verbosity::Low,
-                    &format!("\t{}{}", "message = ".dimmed(), tag_message.yellow()),
-                );
-                self.logger.log(
-                    Verbosity::Low,
-                    &format!("\t{}{}", "sign = ".dimmed(), sign_tag.to_string().yellow()),
-                );
-                if !self.config.global.dry_run {
+                tracing::warn!("tag {:?} already exists and will be replaced", tag.name);
+            }
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{}{}", "tag = ".dimmed(), tag.name.yellow()),
+            );
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{}{}", "message = ".dimmed(), tag.message.yellow()),
+            );
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{}{}", "sign = ".dimmed(), tag.sign.to_string().yellow()),
+            );
+            if !self.config.global.dry_run {
+                // an existing tag is replaced in a single step, so it is never missing
+                let created = if tag.replace {
                     self.repo
-                        .tag(tag_name.as_str(), Some(&tag_message), sign_tag)
+                        .move_tag(tag.name.as_str(), Some(&tag.message), tag.sign)
                         .await
-                        .map_err(BumpError::VCS)?;
-                }
+                } else {
+                    self.repo
+                        .tag(tag.name.as_str(), Some(&tag.message), tag.sign)
+                        .await
+                };
+                created.map_err(BumpError::VCS)?;
+            }
+        }
+        for moved in &tag.moved {
+            tracing::info!(name = moved, "move tag");
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{}{}", "move = ".dimmed(), moved.yellow()),
+            );
+            if !self.config.global.dry_run {
+                self.repo
+                    .move_tag(moved, Some(&tag.message), tag.sign)
+                    .await
+                    .map_err(BumpError::VCS)?;
             }
         }
         Ok(())
     }
+
+    /// Push the planned branch and tag, if pushing is enabled.
+    async fn push(&self, push: Option<&plan::PushPlan>) -> Result<(), BumpError<VCS>> {
+        let Some(push) = push else {
+            return Ok(());
+        };
+
+        tracing::info!(
+            remote = push.remote,
+            branch = push.branch,
+            tag = push.tag,
+            "push"
+        );
+
+        self.logger
+            .log(Verbosity::Low, &format!("{}", "[push]".magenta()));
+        self.logger.log(
+            Verbosity::Low,
+            &format!("\t{}{}", "remote = ".dimmed(), push.remote.yellow()),
+        );
+        let names = [
+            ("branch = ", push.branch.as_ref()),
+            ("tag = ", push.tag.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, name)| Some((label, name?)))
+        .chain(push.moved_tags.iter().map(|name| ("move = ", name)));
+        for (label, name) in names {
+            self.logger.log(
+                Verbosity::Low,
+                &format!("\t{}{}", label.dimmed(), name.yellow()),
+            );
+        }
+
+        if !self.config.global.dry_run {
+            self.repo
+                .push(
+                    &push.remote,
+                    push.branch.as_deref(),
+                    push.tag.as_deref(),
+                    &push.moved_tags,
+                )
+                .await
+                .map_err(|source| BumpError::Push {
+                    remote: push.remote.clone(),
+                    source,
+                })?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -924,4 +1816,119 @@ pub mod tests {
             [Verbosity::Low, Verbosity::Medium, Verbosity::High]
         );
     }
+
+    /// A bump manager for the config and repository in `dir`.
+    async fn manager(
+        dir: &std::path::Path,
+    ) -> color_eyre::eyre::Result<
+        super::BumpVersion<crate::vcs::git::GitRepository, crate::logging::NoOpLogger>,
+    > {
+        use crate::vcs::VersionControlSystem;
+        use color_eyre::eyre;
+
+        let printer = crate::diagnostics::Printer::buffered();
+        let (config_file, mut config) = super::find_config(
+            dir,
+            None,
+            None,
+            &crate::config::GlobalConfig::empty(),
+            &printer,
+        )
+        .await?
+        .ok_or_else(|| eyre::eyre!("missing config"))?;
+        let repo = crate::vcs::git::GitRepository::open(dir)?;
+        let components = crate::config::version::version_component_configs(&config);
+        let file_map =
+            crate::files::resolve_files_from_config(&mut config, &components, Some(repo.path()))?;
+        Ok(super::BumpVersion {
+            repo,
+            config,
+            logger: crate::logging::NoOpLogger {},
+            tag_and_revision: crate::vcs::TagAndRevision::default(),
+            file_map,
+            components,
+            config_file: Some(config_file),
+        })
+    }
+
+    /// Planning touches nothing, applying writes exactly the plan, and a plan whose files
+    /// changed since is refused.
+    #[tokio::test]
+    async fn test_plan_and_apply() -> color_eyre::eyre::Result<()> {
+        init();
+        let dir = tempfile::tempdir()?;
+        let config = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#};
+        std::fs::write(dir.path().join(".bumpversion.toml"), config)?;
+        std::fs::write(dir.path().join("VERSION"), "1.2.3\n")?;
+        let manager = manager(dir.path()).await?;
+
+        let plan = manager.plan(super::Bump::Component("minor")).await?;
+        sim_assert_eq!(plan.new_version_serialized, "1.3.0");
+        let modifications: Vec<_> = plan
+            .modifications()
+            .map(|(path, modification)| (path.file_name(), modification.after.as_str()))
+            .collect();
+        sim_assert_eq!(
+            modifications,
+            [
+                (Some(std::ffi::OsStr::new("VERSION")), "1.3.0\n"),
+                (
+                    Some(std::ffi::OsStr::new(".bumpversion.toml")),
+                    config.replace("1.2.3", "1.3.0").as_str()
+                ),
+            ]
+        );
+        sim_assert_eq!(
+            std::fs::read_to_string(dir.path().join("VERSION"))?,
+            "1.2.3\n"
+        );
+
+        manager.apply(plan.clone()).await?;
+        sim_assert_eq!(
+            std::fs::read_to_string(dir.path().join("VERSION"))?,
+            "1.3.0\n"
+        );
+
+        let err = manager.apply(plan).await;
+        assert!(matches!(err, Err(super::BumpError::OutdatedPlan(_))));
+        Ok(())
+    }
+
+    /// A plan written to JSON and read back applies like the original.
+    #[tokio::test]
+    async fn test_plan_round_trips_through_json() -> color_eyre::eyre::Result<()> {
+        init();
+        let dir = tempfile::tempdir()?;
+        let config = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3-rc1"
+            parse = "(?P<major>\\d+)\\.(?P<minor>\\d+)\\.(?P<patch>\\d+)(-rc(?P<rc>\\d+))?"
+            serialize = ["{major}.{minor}.{patch}-rc{rc}", "{major}.{minor}.{patch}"]
+
+            [[tool.bumpversion.files]]
+            filename = "VERSION"
+        "#};
+        std::fs::write(dir.path().join(".bumpversion.toml"), config)?;
+        std::fs::write(dir.path().join("VERSION"), "1.2.3-rc1\n")?;
+        let manager = manager(dir.path()).await?;
+
+        let plan = manager.plan(super::Bump::Component("rc")).await?;
+        let json = serde_json::to_string(&plan)?;
+        let read_back: crate::plan::BumpPlan = serde_json::from_str(&json)?;
+        sim_assert_eq!(read_back, plan);
+
+        let report = manager.apply(read_back).await?;
+        sim_assert_eq!(report.new_version.serialized, "1.2.3-rc2");
+        sim_assert_eq!(
+            std::fs::read_to_string(dir.path().join("VERSION"))?,
+            "1.2.3-rc2\n"
+        );
+        Ok(())
+    }
 }
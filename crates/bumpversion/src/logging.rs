@@ -107,6 +107,18 @@ where
         let diff = modification.diff(None);
         for replacement in modification.replacements {
             let search = unescape(&replacement.search);
+            if replacement.matches == 0 {
+                self.log(
+                    Verbosity::Low,
+                    &format!(
+                        "\t{} `{}` ({}), ignoring missing version",
+                        "did not find".yellow(),
+                        replacement.search_pattern.color(search_color),
+                        search.color(search_color).dimmed(),
+                    ),
+                );
+                continue;
+            }
             let replace = unescape(&replacement.replace);
             self.log(
                 Verbosity::Low,
@@ -0,0 +1,109 @@
+//! Inspectable plan of a bump.
+//!
+//! A [`BumpPlan`] holds everything a bump is going to do, computed without touching the
+//! repository, so it can be reviewed before [`BumpVersion::apply`](crate::BumpVersion::apply)
+//! executes it.
+use crate::files::Modification;
+use std::path::PathBuf;
+
+/// The commit a bump is going to make.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CommitPlan {
+    /// Files to stage.
+    pub files: Vec<PathBuf>,
+    /// The rendered commit message.
+    pub message: String,
+    /// Extra arguments for the commit command, from `commit_args`.
+    pub args: Vec<String>,
+}
+
+/// The tag a bump is going to create.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TagPlan {
+    /// The rendered tag name.
+    pub name: String,
+    /// The rendered tag message.
+    pub message: String,
+    /// Whether the tag is signed.
+    pub sign: bool,
+    /// Whether the tag already exists, in which case it is not created unless it is replaced.
+    pub exists: bool,
+    /// Whether the existing tag is moved to the new commit in a single step, as with
+    /// `tag_conflict = "replace"`.
+    pub replace: bool,
+    /// The rendered `moveable_tags`, moved to the new tag. Empty if the tag already exists and
+    /// is not replaced.
+    pub moved: Vec<String>,
+}
+
+/// The push a bump is going to make.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PushPlan {
+    /// The remote to push to.
+    pub remote: String,
+    /// The current branch, or `None` if `HEAD` is detached.
+    pub branch: Option<String>,
+    /// The tag, if one is created.
+    pub tag: Option<String>,
+    /// The tags moved along with the tag, and the tag itself if it replaces an existing one,
+    /// which are force-pushed.
+    pub moved_tags: Vec<String>,
+}
+
+/// The hooks a bump is going to run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HookPlan {
+    /// Hooks run before any file is written.
+    pub setup: Vec<String>,
+    /// Hooks run after the files are written, before the commit.
+    pub pre_commit: Vec<String>,
+    /// Hooks run after the commit and the tag.
+    pub post_commit: Vec<String>,
+}
+
+/// Everything a bump is going to do.
+///
+/// The versions are kept as written to files, and parsed again when the plan is applied, so a
+/// plan can be serialized and applied later.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BumpPlan {
+    /// The version before the bump, as written to files.
+    pub current_version_serialized: String,
+    /// The version after the bump, as written to files.
+    pub new_version_serialized: String,
+    /// Every configured file with its modification, or `None` if it is left unchanged.
+    pub files: Vec<(PathBuf, Option<Modification>)>,
+    /// The config file with the modification of its `current_version`.
+    pub config_file: Option<(PathBuf, Option<Modification>)>,
+    /// The changelog with its new section.
+    pub changelog: Option<(PathBuf, Modification)>,
+    /// The commit, if committing is enabled.
+    pub commit: Option<CommitPlan>,
+    /// The tag, if tagging is enabled.
+    pub tag: Option<TagPlan>,
+    /// The push, if pushing is enabled.
+    pub push: Option<PushPlan>,
+    /// The hooks to run.
+    pub hooks: HookPlan,
+}
+
+impl BumpPlan {
+    /// Whether the bump leaves the version unchanged, so there is nothing to do.
+    #[must_use]
+    pub fn is_noop(&self) -> bool {
+        self.current_version_serialized == self.new_version_serialized
+    }
+
+    /// Every file the plan writes, with its modification.
+    pub fn modifications(&self) -> impl Iterator<Item = (&PathBuf, &Modification)> {
+        self.files
+            .iter()
+            .chain(self.config_file.iter())
+            .filter_map(|(path, modification)| Some((path, modification.as_ref()?)))
+            .chain(
+                self.changelog
+                    .iter()
+                    .map(|(path, modification)| (path, modification)),
+            )
+    }
+}
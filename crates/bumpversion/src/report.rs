@@ -0,0 +1,149 @@
+//! Machine-readable report of a bump.
+//!
+//! Records what a bump did, or would have done under dry-run, so it can be serialized instead
+//! of scraped from the verbose output. [`DescribeReport`] does the same for `describe`.
+use crate::{
+    diagnostics::FileId,
+    files::{Modification, Replacement},
+    version::Version,
+};
+use codespan_reporting::diagnostic::Diagnostic;
+use indexmap::IndexMap;
+use std::path::{Path, PathBuf};
+
+/// A version as both its serialized string and its components.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct VersionReport {
+    /// The version string, as written to files.
+    pub serialized: String,
+    /// The value of each component, in the order of the `parse` pattern.
+    pub components: IndexMap<String, Option<String>>,
+}
+
+impl VersionReport {
+    /// Describe `version`, serialized as `serialized`.
+    #[must_use]
+    pub fn new(serialized: &str, version: &Version) -> Self {
+        let components = version
+            .iter()
+            .map(|(name, component)| (name.clone(), component.value().map(ToString::to_string)))
+            .collect();
+        Self {
+            serialized: serialized.to_string(),
+            components,
+        }
+    }
+}
+
+/// A file rewritten by the bump.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FileReport {
+    /// Absolute path of the file.
+    pub path: PathBuf,
+    /// Whether the contents of the file changed.
+    pub modified: bool,
+    /// The search and replace templates that were applied.
+    pub replacements: Vec<Replacement>,
+    /// Unified diff of the change, if any.
+    pub diff: Option<String>,
+}
+
+impl FileReport {
+    /// Describe the `modification` of the file at `path`.
+    #[must_use]
+    pub fn new(path: &Path, modification: Option<&Modification>) -> Self {
+        let diff = modification.and_then(|modification| modification.unified_diff(path));
+        Self {
+            path: path.to_path_buf(),
+            modified: diff.is_some(),
+            replacements: modification
+                .map(|modification| modification.replacements.clone())
+                .unwrap_or_default(),
+            diff,
+        }
+    }
+
+    /// Warnings for the changes of this file whose version was missing and ignored.
+    #[must_use]
+    pub fn diagnostics(&self) -> Vec<Diagnostic<FileId>> {
+        self.replacements
+            .iter()
+            .filter_map(|replacement| replacement.diagnostic(&self.path))
+            .collect()
+    }
+}
+
+/// The commit of a bump.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CommitReport {
+    /// Files staged for the commit.
+    pub files: Vec<PathBuf>,
+    /// The rendered commit message.
+    pub message: String,
+}
+
+/// The tag of a bump.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TagReport {
+    /// The rendered tag name.
+    pub name: String,
+    /// The rendered tag message.
+    pub message: String,
+    /// Whether the tag is signed.
+    pub sign: bool,
+    /// Whether the tag is created, which it is not if it already exists, unless it is replaced.
+    pub created: bool,
+    /// Whether an existing tag was replaced by the new one.
+    pub replaced: bool,
+    /// The moveable tags moved to the new tag.
+    pub moved: Vec<String>,
+}
+
+/// The push of a bump.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PushReport {
+    /// The remote pushed to.
+    pub remote: String,
+    /// The branch pushed, if any.
+    pub branch: Option<String>,
+    /// The tag pushed, if any.
+    pub tag: Option<String>,
+    /// The moved tags force-pushed.
+    pub moved_tags: Vec<String>,
+}
+
+/// Everything a bump did.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BumpReport {
+    /// Whether this was a dry run, in which case nothing was written.
+    pub dry_run: bool,
+    /// The version before the bump.
+    pub current_version: VersionReport,
+    /// The version after the bump.
+    pub new_version: VersionReport,
+    /// Every file considered, including the config file and the changelog.
+    pub files: Vec<FileReport>,
+    /// The commit, if committing is enabled.
+    pub commit: Option<CommitReport>,
+    /// The tag, if tagging is enabled.
+    pub tag: Option<TagReport>,
+    /// The push, if pushing is enabled.
+    pub push: Option<PushReport>,
+}
+
+/// The development version of the working tree.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DescribeReport {
+    /// The development version, or the current version on a clean checkout of its tag.
+    pub version: String,
+    /// The current version.
+    pub current_version: String,
+    /// The latest tag.
+    pub tag: String,
+    /// Number of commits since the latest tag.
+    pub distance_to_latest_tag: usize,
+    /// The commit SHA of `HEAD`.
+    pub commit_sha: String,
+    /// Whether the working tree has uncommitted changes.
+    pub dirty: bool,
+}
@@ -33,12 +33,12 @@ pub enum Error {
     #[error("invalid tag: {0}")]
     InvalidTag(#[from] InvalidTagError),
 
-    /// Missing argument while formatting a template.
+    /// Failed to format a template.
     #[error("failed to template {format_string}")]
-    MissingArgument {
-        /// Underlying missing-argument error.
+    Format {
+        /// Underlying formatting error.
         #[source]
-        source: crate::f_string::MissingArgumentError,
+        source: crate::f_string::FormatError,
         /// Template that failed to format.
         format_string: PythonFormatString,
     },
@@ -112,7 +112,7 @@ fn extract_regex_flags(pattern: &str) -> (&str, &str) {
 /// # Errors
 /// - When the given `parse_version_regex` cannot be transformed to extract the
 ///   current version from the git tag
-fn get_version_from_tag<'a>(
+pub(crate) fn get_version_from_tag<'a>(
     tag: &'a str,
     tag_name: &PythonFormatString,
     parse_version_regex: &regex::Regex,
@@ -126,7 +126,7 @@ fn get_version_from_tag<'a>(
     let mut suffix = String::new();
     if let Some(idx) = values
         .iter()
-        .position(|value| value == &Value::Argument("new_version".to_string()))
+        .position(|value| value == &Value::argument("new_version"))
     {
         for value in values.iter().take(idx) {
             prefix.push_str(&value.to_string());
@@ -161,6 +161,85 @@ pub static BRANCH_NAME_REGEX: LazyLock<regex::Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
+/// The short form of a branch name, as exposed by [`RevisionInfo::short_branch_name`].
+pub(crate) fn short_branch_name(branch_name: &str) -> String {
+    BRANCH_NAME_REGEX
+        .replace_all(branch_name, "")
+        .to_lowercase()
+        .chars()
+        .take(20)
+        .collect()
+}
+
+/// The `describe`-style glob matching the tags rendered from `tag_name`.
+///
+/// # Errors
+/// - When `tag_name` references a placeholder other than `new_version`
+pub(crate) fn tag_pattern(tag_name: &PythonFormatString) -> Result<String, Error> {
+    tag_name
+        .format(&[("new_version", "*")].into_iter().collect(), true)
+        .map_err(|source| Error::Format {
+            source,
+            format_string: tag_name.clone(),
+        })
+}
+
+/// Whether `tag` carries a version, unlike the `moveable_tags` (e.g. `v1`) that follow a release.
+///
+/// Moved tags are annotated like the release tag, so they would otherwise shadow it.
+///
+/// # Errors
+/// - When the given `parse_version_regex` cannot be transformed to extract the
+///   current version from the git tag
+pub(crate) fn is_release_tag(
+    tag: &str,
+    tag_name: &PythonFormatString,
+    parse_version_regex: &regex::Regex,
+) -> Result<bool, regex::Error> {
+    Ok(get_version_from_tag(tag, tag_name, parse_version_regex)?.is_some())
+}
+
+/// The version a tag refers to, falling back to the tag name without a leading `v`.
+///
+/// # Errors
+/// - When the given `parse_version_regex` cannot be transformed to extract the
+///   current version from the git tag
+pub(crate) fn version_of_tag(
+    current_tag: &str,
+    tag_name: &PythonFormatString,
+    parse_version_regex: &regex::Regex,
+) -> Result<String, regex::Error> {
+    let version = get_version_from_tag(current_tag, tag_name, parse_version_regex)?;
+    let current_numeric_version = current_tag.trim_start_matches('v');
+    Ok(version.unwrap_or(current_numeric_version).to_string())
+}
+
+/// Push `branch`, `tag` and the `moved_tags` of the repository at `path` to `remote` in a single
+/// atomic `git push`.
+///
+/// # Errors
+/// - When `git push` fails, e.g. because the remote rejects one of the refs
+pub(crate) async fn push(
+    path: &Path,
+    remote: &str,
+    branch: Option<&str>,
+    tag: Option<&str>,
+    moved_tags: &[String],
+) -> Result<(), crate::command::Error> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(path);
+    cmd.args(["push", "--atomic", remote]);
+    if let Some(branch) = branch {
+        cmd.arg(format!("refs/heads/{branch}"));
+    }
+    if let Some(tag) = tag {
+        cmd.arg(format!("refs/tags/{tag}"));
+    }
+    cmd.args(moved_tags.iter().map(|tag| format!("+refs/tags/{tag}")));
+    let _push_output = run_command(&mut cmd).await?;
+    Ok(())
+}
+
 impl GitRepository {
     /// Returns a dictionary containing revision information.
     async fn revision_info(&self) -> Result<Option<RevisionInfo>, Error> {
@@ -176,16 +255,9 @@ impl GitRepository {
         let Some(branch_name) = lines.next() else {
             return Ok(None);
         };
-        let short_branch_name: String = BRANCH_NAME_REGEX
-            .replace_all(branch_name, "")
-            .to_lowercase()
-            .chars()
-            .take(20)
-            .collect();
-
         Ok(Some(RevisionInfo {
             branch_name: branch_name.to_string(),
-            short_branch_name,
+            short_branch_name: short_branch_name(branch_name),
             repository_root,
         }))
     }
@@ -199,13 +271,19 @@ impl GitRepository {
         tag_name: &PythonFormatString,
         parse_version_regex: &regex::Regex,
     ) -> Result<Option<TagInfo>, Error> {
-        let tag_pattern = tag_name
-            .format(&[("new_version", "*")].into_iter().collect(), true)
-            .map_err(|source| Error::MissingArgument {
-                source,
-                format_string: tag_name.clone(),
-            })?;
-        // let tag_pattern = tag_name.replace("{new_version}", "*");
+        let tag_pattern = tag_pattern(tag_name)?;
+
+        // tags without a version, such as moved tags, never count as the latest release
+        let mut cmd = Command::new("git");
+        cmd.args(["tag", "--list", &tag_pattern])
+            .current_dir(&self.path);
+        let tags = run_command(&mut cmd).await?;
+        let mut exclude_flags = vec![];
+        for tag in tags.stdout.lines().map(str::trim) {
+            if !is_release_tag(tag, tag_name, parse_version_regex)? {
+                exclude_flags.push(format!("--exclude={tag}"));
+            }
+        }
 
         // get info about the latest tag in git
         let match_tag_pattern_flag = format!("--match={tag_pattern}");
@@ -218,6 +296,7 @@ impl GitRepository {
             "--abbrev=40",
             &match_tag_pattern_flag,
         ])
+        .args(&exclude_flags)
         .current_dir(&self.path);
 
         match run_command(&mut cmd).await {
@@ -247,19 +326,13 @@ impl GitRepository {
                         tag: raw_tag.clone(),
                     })?;
                 let current_tag = tag_parts.join("-");
-                let version = get_version_from_tag(&current_tag, tag_name, parse_version_regex)?;
-                let current_numeric_version = current_tag.trim_start_matches('v').to_string();
-                let current_version = version
-                    .unwrap_or(current_numeric_version.as_str())
-                    .to_string();
+                let current_version = version_of_tag(&current_tag, tag_name, parse_version_regex)?;
 
                 tracing::debug!(
                     dirty,
                     commit_sha,
                     distance_to_latest_tag,
                     current_tag,
-                    version,
-                    current_numeric_version,
                     current_version
                 );
 
@@ -283,6 +356,30 @@ impl GitRepository {
             }
         }
     }
+
+    /// Create the tag `name` at `HEAD`, replacing an existing tag in a single step if `force`.
+    async fn create_tag(
+        &self,
+        name: &str,
+        message: Option<&str>,
+        sign: bool,
+        force: bool,
+    ) -> Result<(), Error> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.path);
+        cmd.args(["tag", name]);
+        if force {
+            cmd.arg("--force");
+        }
+        if sign {
+            cmd.arg("--sign");
+        }
+        if let Some(message) = message {
+            cmd.args(["--message", message]);
+        }
+        let _tag_output = run_command(&mut cmd).await?;
+        Ok(())
+    }
 }
 
 // #[async_trait::async_trait]
@@ -348,6 +445,33 @@ impl VersionControlSystem for GitRepository {
         Ok(())
     }
 
+    async fn add_new<P>(&self, files: impl IntoIterator<Item = P>) -> Result<(), Error>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        let mut cmd = Command::new("git");
+        cmd.arg("add").arg("--").args(files).current_dir(&self.path);
+        let _add_output = run_command(&mut cmd).await?;
+        Ok(())
+    }
+
+    async fn reset<P>(&self, files: impl IntoIterator<Item = P>) -> Result<(), Error>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        let files: Vec<P> = files.into_iter().collect();
+        if files.is_empty() {
+            // without paths, `git reset` would unstage everything
+            return Ok(());
+        }
+        let mut cmd = Command::new("git");
+        cmd.args(["reset", "--quiet", "--"])
+            .args(files)
+            .current_dir(&self.path);
+        let _reset_output = run_command(&mut cmd).await?;
+        Ok(())
+    }
+
     async fn dirty_files(&self) -> Result<Vec<PathBuf>, Error> {
         let mut cmd = Command::new("git");
         cmd.args(["status", "-u", "--porcelain"])
@@ -357,26 +481,32 @@ impl VersionControlSystem for GitRepository {
         let dirty = status_output
             .stdout
             .lines()
-            .map(str::trim)
-            .filter(|line| !line.is_empty())
+            .map(str::trim_end)
             .filter(|line| !line.starts_with("??"))
-            .filter_map(|line| line.split_once(' '))
-            .map(|(_, file)| self.path().join(file))
+            // each line is a two-letter status and a space, followed by the path
+            .filter_map(|line| line.get(3..))
+            .filter(|file| !file.is_empty())
+            .map(|file| self.path().join(file))
             .collect();
         Ok(dirty)
     }
 
     async fn tag(&self, name: &str, message: Option<&str>, sign: bool) -> Result<(), Error> {
-        let mut cmd = Command::new("git");
-        cmd.current_dir(&self.path);
-        cmd.args(["tag", name]);
-        if sign {
-            cmd.arg("--sign");
-        }
-        if let Some(message) = message {
-            cmd.args(["--message", message]);
-        }
-        let _tag_output = run_command(&mut cmd).await?;
+        self.create_tag(name, message, sign, false).await
+    }
+
+    async fn move_tag(&self, name: &str, message: Option<&str>, sign: bool) -> Result<(), Error> {
+        self.create_tag(name, message, sign, true).await
+    }
+
+    async fn push(
+        &self,
+        remote: &str,
+        branch: Option<&str>,
+        tag: Option<&str>,
+        moved_tags: &[String],
+    ) -> Result<(), Error> {
+        push(&self.path, remote, branch, tag, moved_tags).await?;
         Ok(())
     }
 
@@ -392,6 +522,25 @@ impl VersionControlSystem for GitRepository {
             .collect())
     }
 
+    async fn commit_messages(&self, since: Option<&str>) -> Result<Vec<String>, Error> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.path);
+        // messages are separated by NUL bytes, since they may span several lines
+        cmd.args(["log", "--format=%B%x00"]);
+        match since {
+            Some(since) => cmd.arg(format!("{since}..HEAD")),
+            None => cmd.arg("HEAD"),
+        };
+        let output = run_command(&mut cmd).await?;
+        Ok(output
+            .stdout
+            .split('\0')
+            .map(str::trim)
+            .filter(|message| !message.is_empty())
+            .map(ToString::to_string)
+            .collect())
+    }
+
     async fn latest_tag_and_revision(
         &self,
         tag_name: &PythonFormatString,
@@ -413,20 +562,10 @@ impl VersionControlSystem for GitRepository {
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        command::run_command,
-        f_string::PythonFormatString,
-        tests::sim_assert_eq_sorted,
-        vcs::{VersionControlSystem, git, temp::EphemeralRepository},
-    };
-    use async_process::Command;
+    use crate::f_string::PythonFormatString;
     use color_eyre::eyre;
-
     use similar_asserts::assert_eq as sim_assert_eq;
 
-    use std::io::Write;
-    use std::path::PathBuf;
-
     #[test]
     fn test_get_version_from_tag() -> eyre::Result<()> {
         crate::tests::init();
@@ -437,95 +576,4 @@ mod tests {
         sim_assert_eq!(version, Some("2.1.4"));
         Ok(())
     }
-
-    #[ignore = "wip"]
-    #[tokio::test]
-    async fn test_create_empty_git_repo() -> eyre::Result<()> {
-        crate::tests::init();
-        let repo: EphemeralRepository<git::GitRepository> = EphemeralRepository::new().await?;
-        let status = run_command(
-            Command::new("git")
-                .args(["status"])
-                .current_dir(repo.path()),
-        )
-        .await?;
-        assert!(status.stdout.contains("No commits yet"));
-        Ok(())
-    }
-
-    #[ignore = "wip"]
-    #[tokio::test]
-    async fn test_tag() -> eyre::Result<()> {
-        crate::tests::init();
-        let repo: EphemeralRepository<git::GitRepository> = EphemeralRepository::new().await?;
-        let tags = vec![
-            None,
-            Some(("tag1", Some("tag1 message"))),
-            Some(("tag2", Some("tag2 message"))),
-        ];
-        // add a single file so we can commit and get a HEAD
-        let initial_file = repo.path().join("README.md");
-        std::fs::File::create(&initial_file)?.write_all(b"Hello, world!")?;
-
-        repo.add(&[initial_file]).await?;
-        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
-            .await?;
-        similar_asserts::assert_eq!(repo.dirty_files().await?.len(), 0);
-
-        for (_tag, _previous) in tags.iter().skip(1).zip(&tags) {
-            // let latest = repo.latest_tag_info(None)?.map(|t| t.current_version);
-            // let previous = previous.map(|t| t.0.to_string());
-            // similar_asserts::assert_eq!(&previous, &latest);
-            // if let Some((tag_name, tag_message)) = *tag {
-            //     repo.tag(tag_name, tag_message, false)?;
-            // }
-        }
-        Ok(())
-    }
-
-    #[ignore = "wip"]
-    #[tokio::test]
-    async fn test_dirty_tree() -> eyre::Result<()> {
-        crate::tests::init();
-        let repo: EphemeralRepository<git::GitRepository> = EphemeralRepository::new().await?;
-        similar_asserts::assert_eq!(repo.dirty_files().await?.len(), 0);
-
-        // add some dirty files
-        let mut dirty_files: Vec<PathBuf> = ["foo.txt", "dir/bar.txt"]
-            .iter()
-            .map(|f| repo.path().join(f))
-            .collect();
-
-        for dirty_file in &dirty_files {
-            use tokio::io::AsyncWriteExt;
-            if let Some(parent) = dirty_file.parent() {
-                tokio::fs::create_dir_all(parent).await?;
-            }
-            let file = tokio::fs::OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(dirty_file)
-                .await?;
-            let mut writer = tokio::io::BufWriter::new(file);
-            writer.write_all(b"Hello, world!").await?;
-        }
-        similar_asserts::assert_eq!(repo.dirty_files().await?.len(), 0);
-
-        // track first file
-        let first_dirty_file = dirty_files
-            .first()
-            .ok_or_else(|| eyre::eyre!("expected at least one dirty file"))?
-            .clone();
-        let mut expected_first_dirty_files = vec![first_dirty_file];
-        repo.add(expected_first_dirty_files.as_slice()).await?;
-        let mut actual_dirty_files = repo.dirty_files().await?;
-        sim_assert_eq_sorted!(actual_dirty_files, expected_first_dirty_files);
-
-        // track all files
-        repo.add(&dirty_files).await?;
-        let mut actual_dirty_files = repo.dirty_files().await?;
-        sim_assert_eq_sorted!(actual_dirty_files, dirty_files);
-        Ok(())
-    }
 }
@@ -0,0 +1,690 @@
+//! In-process git backend for version control operations.
+//!
+//! Implements the `VersionControlSystem` trait on top of [gitoxide](https://github.com/GitoxideLabs/gitoxide),
+//! so no `git` process is spawned. Enabled by the `gitoxide` feature.
+//!
+//! Unlike [`GitRepository`](crate::vcs::git::GitRepository), committing does not run the
+//! repository's git hooks, and `commit_args` other than `--no-verify` are rejected. Of the
+//! commit environment, only the `GIT_AUTHOR_*` and `GIT_COMMITTER_*` identity variables apply.
+//! Signing tags and pushing still spawn `git`, since gitoxide can do neither yet.
+//!
+//! gix works synchronously, so every operation runs on a blocking thread.
+use crate::{
+    f_string::PythonFormatString,
+    vcs::{
+        RevisionInfo, TagAndRevision, TagInfo, VersionControlSystem,
+        git::{is_release_tag, short_branch_name, tag_pattern, version_of_tag},
+    },
+};
+use gix::bstr::{BStr, BString, ByteSlice};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Gitoxide VCS error type.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// I/O error while reading the working tree.
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Git operation failed.
+    #[error("git error: {0}")]
+    Git(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// Regex compilation error.
+    #[error("regex error: {0}")]
+    Regex(#[from] regex::Error),
+
+    /// `git push` failed.
+    #[error("command failed: {0}")]
+    CommandFailed(#[from] crate::command::Error),
+
+    /// Shared error of the git backend, e.g. while formatting the tag pattern.
+    #[error(transparent)]
+    Template(#[from] crate::vcs::git::Error),
+
+    /// The repository has no working tree.
+    #[error("repository at {0:?} has no working tree")]
+    Bare(PathBuf),
+
+    /// A file is not inside the working tree of the repository.
+    #[error("{0:?} is outside of the repository")]
+    OutsideRepository(PathBuf),
+
+    /// Background task join error.
+    #[error("failed to join spawned task")]
+    Join(#[from] tokio::task::JoinError),
+
+    /// Extra commit arguments that have no in-process equivalent.
+    #[error("commit arguments {0:?} are not supported by the gitoxide backend")]
+    UnsupportedCommitArgs(Vec<String>),
+}
+
+/// Conversion of the error types of the gix crates into [`Error::Git`].
+trait GitResultExt<T> {
+    fn git(self) -> Result<T, Error>;
+}
+
+impl<T, E> GitResultExt<T> for Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn git(self) -> Result<T, Error> {
+        self.map_err(|err| Error::Git(Box::new(err)))
+    }
+}
+
+/// Represents a git repository opened in-process.
+#[derive(Debug, Clone)]
+#[expect(
+    clippy::module_name_repetitions,
+    reason = "the public name distinguishes this implementation from other repository types"
+)]
+pub struct GitoxideRepository {
+    path: PathBuf,
+    repo: gix::ThreadSafeRepository,
+}
+
+/// Commit arguments that are accepted and ignored, since no git hooks are run.
+const IGNORED_COMMIT_ARGS: [&str; 2] = ["--no-verify", "-n"];
+
+/// Message as written by `git`, which strips trailing whitespace and ends with a newline.
+fn cleanup_message(message: &str) -> String {
+    format!("{}\n", message.trim_end())
+}
+
+/// The configuration key for an identity variable that `git commit` reads from its environment.
+fn identity_key(variable: &str) -> Option<&'static dyn gix::config::tree::Key> {
+    use gix::config::tree::{Author, Committer, gitoxide};
+
+    match variable {
+        "GIT_AUTHOR_NAME" => Some(&Author::NAME),
+        "GIT_AUTHOR_EMAIL" => Some(&Author::EMAIL),
+        "GIT_AUTHOR_DATE" => Some(&gitoxide::Commit::AUTHOR_DATE),
+        "GIT_COMMITTER_NAME" => Some(&Committer::NAME),
+        "GIT_COMMITTER_EMAIL" => Some(&Committer::EMAIL),
+        "GIT_COMMITTER_DATE" => Some(&gitoxide::Commit::COMMITTER_DATE),
+        _ => None,
+    }
+}
+
+impl GitoxideRepository {
+    fn repo(&self) -> gix::Repository {
+        self.repo.to_thread_local()
+    }
+
+    /// Run `f` on a blocking thread, since gix reads and writes the repository synchronously.
+    async fn blocking<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&Self) -> Result<T, Error> + Send + 'static,
+        T: Send + 'static,
+    {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || f(&this)).await?
+    }
+
+    fn workdir(repo: &gix::Repository) -> Result<PathBuf, Error> {
+        repo.workdir()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| Error::Bare(repo.git_dir().to_path_buf()))
+    }
+
+    /// The path of `file` relative to the root of the working tree.
+    ///
+    /// Relative paths are resolved against the path the repository was opened at, like `git`
+    /// resolves them against its working directory.
+    fn rela_path(&self, workdir: &Path, file: &Path) -> Result<BString, Error> {
+        let path = self.path.join(file);
+        let rela_path = if let Ok(rela_path) = path.strip_prefix(workdir) {
+            rela_path.to_path_buf()
+        } else {
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            canonical
+                .strip_prefix(workdir.canonicalize()?)
+                .map_err(|_| Error::OutsideRepository(path.clone()))?
+                .to_path_buf()
+        };
+        let rela_path = gix::path::into_bstr(rela_path);
+        Ok(gix::path::to_unix_separators_on_windows(rela_path).into_owned())
+    }
+
+    /// Release tags matching `tag_pattern` by the commit they point to.
+    ///
+    /// Tags without a version, such as moved tags, are skipped. When several tags point to the same commit, annotated tags win over lightweight ones,
+    /// then newer over older ones, like `git describe` picks them.
+    fn tags_by_commit(
+        repo: &gix::Repository,
+        tag_name: &PythonFormatString,
+        parse_version_regex: &regex::Regex,
+    ) -> Result<gix::hashtable::HashMap<gix::ObjectId, Cow<'static, BStr>>, Error> {
+        let tag_pattern = tag_pattern(tag_name)?;
+        let platform = repo.references().git()?;
+        let mut tags: Vec<_> = platform
+            .tags()
+            .git()?
+            .filter_map(Result::ok)
+            .filter(|tag| {
+                gix::glob::wildmatch(
+                    tag_pattern.as_str().into(),
+                    tag.name().shorten(),
+                    gix::glob::wildmatch::Mode::empty(),
+                )
+            })
+            .filter_map(|mut tag| {
+                let target_id = tag.target().try_id().map(ToOwned::to_owned);
+                let peeled_id = tag.peel_to_id().ok()?.detach();
+                let tag_time = match target_id {
+                    Some(target_id) if target_id != peeled_id => {
+                        let object = repo.find_object(target_id).ok()?.try_into_tag().ok()?;
+                        let tagger = object.tagger().ok().flatten();
+                        Some(tagger.map_or(0, |tagger| tagger.seconds()))
+                    }
+                    _ => None,
+                };
+                let name = tag.name().shorten().to_owned();
+                Some((peeled_id, tag_time, name))
+            })
+            .filter_map(|tag| {
+                match is_release_tag(&tag.2.to_string(), tag_name, parse_version_regex) {
+                    Ok(true) => Some(Ok(tag)),
+                    Ok(false) => None,
+                    Err(err) => Some(Err(err)),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        // later entries overwrite earlier ones when collected
+        tags.sort_by(|(_, a_time, a_name), (_, b_time, b_name)| {
+            a_time.cmp(b_time).then_with(|| b_name.cmp(a_name))
+        });
+        Ok(tags
+            .into_iter()
+            .map(|(id, _, name)| (id, Cow::Owned(name)))
+            .collect())
+    }
+
+    /// Returns a dictionary containing revision information.
+    fn revision_info(&self) -> Result<Option<RevisionInfo>, Error> {
+        let repo = self.repo();
+        let repository_root = Self::workdir(&repo)?;
+        let branch_name = match repo.head_name().git()? {
+            Some(name) => name.shorten().to_string(),
+            None => "HEAD".to_string(),
+        };
+        Ok(Some(RevisionInfo {
+            short_branch_name: short_branch_name(&branch_name),
+            branch_name,
+            repository_root,
+        }))
+    }
+
+    /// Get the commit info for the repo.
+    ///
+    /// The `tag_name` is the tag name format used to locate the latest tag.
+    /// The `parse_pattern` is a regular expression pattern used to parse the version from the tag.
+    fn latest_tag_info(
+        &self,
+        tag_name: &PythonFormatString,
+        parse_version_regex: &regex::Regex,
+    ) -> Result<Option<TagInfo>, Error> {
+        let repo = self.repo();
+        let Some(head) = repo.head().git()?.id() else {
+            return Ok(None);
+        };
+        let name_by_oid = Self::tags_by_commit(&repo, tag_name, parse_version_regex)?;
+
+        let cache = repo.commit_graph_if_enabled().git()?;
+        let mut graph = repo.revision_graph(cache.as_ref());
+        let outcome = gix::revision::plumbing::describe(
+            &head,
+            &mut graph,
+            gix::revision::plumbing::describe::Options {
+                name_by_oid,
+                ..gix::revision::plumbing::describe::Options::default()
+            },
+        )
+        .git()?;
+        let Some(outcome) = outcome else {
+            return Ok(None);
+        };
+        let Some(current_tag) = outcome.name.map(|name| name.to_string()) else {
+            return Ok(None);
+        };
+
+        let dirty = repo.is_dirty().git()?;
+        let commit_sha = outcome.id.to_string();
+        let distance_to_latest_tag = outcome.depth as usize;
+        let current_version = version_of_tag(&current_tag, tag_name, parse_version_regex)?;
+
+        tracing::debug!(
+            dirty,
+            commit_sha,
+            distance_to_latest_tag,
+            current_tag,
+            current_version
+        );
+
+        Ok(Some(TagInfo {
+            dirty,
+            commit_sha,
+            distance_to_latest_tag,
+            current_tag,
+            current_version,
+        }))
+    }
+
+    /// Stage the changes of `files`, like `git add --update`, and start tracking the untracked
+    /// ones if `track`, like `git add`.
+    async fn stage(&self, files: Vec<PathBuf>, track: bool) -> Result<(), Error> {
+        self.blocking(move |this| {
+            let repo = this.repo();
+            if !repo.index_path().is_file() {
+                // nothing is tracked yet
+                return Ok(());
+            }
+            let workdir = Self::workdir(&repo)?;
+            let mut index = repo.open_index().git()?;
+            let mut removed = Vec::new();
+            let mut added = false;
+
+            for file in &files {
+                let rela_path = this.rela_path(&workdir, file)?;
+                let entry_index = index.entry_index_by_path(rela_path.as_ref()).ok();
+                if entry_index.is_none() && !track {
+                    // untracked files are left alone, like `git add --update` does
+                    continue;
+                }
+                let path = workdir.join(gix::path::from_bstr(rela_path.as_bstr()));
+                let metadata = match gix::index::fs::Metadata::from_path_no_follow(&path) {
+                    Ok(metadata) => metadata,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                        removed.extend(entry_index);
+                        continue;
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                let data = if metadata.is_symlink() {
+                    gix::path::into_bstr(std::fs::read_link(&path)?).to_vec()
+                } else {
+                    std::fs::read(&path)?
+                };
+                let id = repo.write_blob(data).git()?.detach();
+                let stat = gix::index::entry::Stat::from_fs(&metadata).unwrap_or_default();
+                if let Some(entry) =
+                    entry_index.and_then(|entry_index| index.entries_mut().get_mut(entry_index))
+                {
+                    entry.id = id;
+                    entry.stat = stat;
+                } else {
+                    let mode = if metadata.is_symlink() {
+                        gix::index::entry::Mode::SYMLINK
+                    } else if metadata.is_executable() {
+                        gix::index::entry::Mode::FILE_EXECUTABLE
+                    } else {
+                        gix::index::entry::Mode::FILE
+                    };
+                    index.dangerously_push_entry(
+                        stat,
+                        id,
+                        gix::index::entry::Flags::empty(),
+                        mode,
+                        rela_path.as_ref(),
+                    );
+                    added = true;
+                }
+            }
+
+            index.remove_entries(|entry_index, _, _| removed.contains(&entry_index));
+            if added {
+                index.sort_entries();
+            }
+            index.write(gix::index::write::Options::default()).git()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Create the tag `name` at `HEAD`, replacing an existing tag in a single step if `force`.
+    async fn create_tag(
+        &self,
+        name: &str,
+        message: Option<&str>,
+        sign: bool,
+        force: bool,
+    ) -> Result<(), Error> {
+        use gix::refs::transaction::PreviousValue;
+
+        if sign {
+            // gix cannot sign objects, so signed tags are left to `git`
+            let workdir = self.blocking(|this| Self::workdir(&this.repo())).await?;
+            let git = crate::vcs::git::GitRepository::open(workdir)?;
+            if force {
+                git.move_tag(name, message, sign).await?;
+            } else {
+                git.tag(name, message, sign).await?;
+            }
+            return Ok(());
+        }
+
+        let previous = if force {
+            PreviousValue::Any
+        } else {
+            PreviousValue::MustNotExist
+        };
+        let name = name.to_string();
+        let message = message.map(cleanup_message);
+        self.blocking(move |this| {
+            let repo = this.repo();
+            let head = repo.head_id().git()?.detach();
+            let Some(message) = message else {
+                repo.tag_reference(name, head, previous).git()?;
+                return Ok(());
+            };
+
+            let tagger = repo
+                .committer()
+                .transpose()
+                .git()?
+                .map(|tagger| tagger.to_owned())
+                .transpose()
+                .git()?;
+            let tag = gix::objs::Tag {
+                target: head,
+                target_kind: gix::object::Kind::Commit,
+                name: name.as_str().into(),
+                tagger,
+                message: message.into(),
+                pgp_signature: None,
+            };
+            let tag_id = repo.write_object(&tag).git()?.detach();
+            repo.tag_reference(name, tag_id, previous).git()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl VersionControlSystem for GitoxideRepository {
+    type Error = Error;
+
+    fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let repo = gix::discover(&path).git()?.into_sync();
+        Ok(Self { path, repo })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn commit<A, E, AS, EK, EV>(
+        &self,
+        message: &str,
+        extra_args: A,
+        env: E,
+    ) -> Result<(), Error>
+    where
+        A: IntoIterator<Item = AS>,
+        E: IntoIterator<Item = (EK, EV)>,
+        AS: AsRef<std::ffi::OsStr>,
+        EK: AsRef<std::ffi::OsStr>,
+        EV: AsRef<std::ffi::OsStr>,
+    {
+        let unsupported: Vec<String> = extra_args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_string_lossy().to_string())
+            .filter(|arg| !IGNORED_COMMIT_ARGS.contains(&arg.as_str()))
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(Error::UnsupportedCommitArgs(unsupported));
+        }
+        let env: Vec<(String, String)> = env
+            .into_iter()
+            .map(|(variable, value)| {
+                (
+                    variable.as_ref().to_string_lossy().to_string(),
+                    value.as_ref().to_string_lossy().to_string(),
+                )
+            })
+            .collect();
+        let message = cleanup_message(message);
+
+        self.blocking(move |this| {
+            let mut repo = this.repo();
+            let identity: Vec<_> = env
+                .iter()
+                .filter_map(|(variable, value)| Some((identity_key(variable)?, value.as_str())))
+                .collect();
+            if !identity.is_empty() {
+                let mut config = repo.config_snapshot_mut();
+                for (key, value) in identity {
+                    config.set_value(key, value).git()?;
+                }
+                config.commit().git()?;
+            }
+
+            let index = repo.index_or_empty().git()?;
+            let mut tree = repo
+                .edit_tree(gix::ObjectId::empty_tree(repo.object_hash()))
+                .git()?;
+            for entry in index.entries() {
+                if entry.stage() != gix::index::entry::Stage::Unconflicted {
+                    continue;
+                }
+                let Some(mode) = entry.mode.to_tree_entry_mode() else {
+                    continue;
+                };
+                tree.upsert(entry.path(&index).to_owned(), mode.kind(), entry.id)
+                    .git()?;
+            }
+            let tree = tree.write().git()?;
+
+            let parents = repo.head().git()?.id().map(gix::Id::detach);
+            repo.commit("HEAD", message, tree, parents).git()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add<P>(&self, files: impl IntoIterator<Item = P>) -> Result<(), Error>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        let files = files
+            .into_iter()
+            .map(|file| PathBuf::from(file.as_ref()))
+            .collect();
+        self.stage(files, false).await
+    }
+
+    async fn add_new<P>(&self, files: impl IntoIterator<Item = P>) -> Result<(), Error>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        let files = files
+            .into_iter()
+            .map(|file| PathBuf::from(file.as_ref()))
+            .collect();
+        self.stage(files, true).await
+    }
+
+    async fn reset<P>(&self, files: impl IntoIterator<Item = P>) -> Result<(), Error>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        let files: Vec<PathBuf> = files
+            .into_iter()
+            .map(|file| PathBuf::from(file.as_ref()))
+            .collect();
+        self.blocking(move |this| {
+            let repo = this.repo();
+            if !repo.index_path().is_file() {
+                return Ok(());
+            }
+            let workdir = Self::workdir(&repo)?;
+            // an unborn branch has no tree, and resetting to it unstages the files entirely
+            let head_tree = repo.head_tree().ok();
+            let mut index = repo.open_index().git()?;
+            let mut removed = Vec::new();
+            let mut restored = false;
+
+            for file in &files {
+                let rela_path = this.rela_path(&workdir, file)?;
+                let head_entry = match &head_tree {
+                    Some(tree) => tree
+                        .lookup_entry_by_path(gix::path::from_bstr(rela_path.as_bstr()))
+                        .git()?,
+                    None => None,
+                };
+                match (index.entry_index_by_path(rela_path.as_ref()), head_entry) {
+                    (Ok(entry_index), Some(head_entry)) => {
+                        if let Some(entry) = index.entries_mut().get_mut(entry_index) {
+                            entry.id = head_entry.object_id();
+                            entry.mode = head_entry.mode().into();
+                            // compare the file by its contents again, since the stat is of the
+                            // staged version
+                            entry.stat = gix::index::entry::Stat::default();
+                        }
+                    }
+                    (Ok(entry_index), None) => removed.push(entry_index),
+                    (Err(_), Some(head_entry)) => {
+                        index.dangerously_push_entry(
+                            gix::index::entry::Stat::default(),
+                            head_entry.object_id(),
+                            gix::index::entry::Flags::empty(),
+                            head_entry.mode().into(),
+                            rela_path.as_ref(),
+                        );
+                        restored = true;
+                    }
+                    (Err(_), None) => {}
+                }
+            }
+
+            index.remove_entries(|entry_index, _, _| removed.contains(&entry_index));
+            if restored {
+                index.sort_entries();
+            }
+            index.write(gix::index::write::Options::default()).git()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn dirty_files(&self) -> Result<Vec<PathBuf>, Error> {
+        use gix::status::plumbing::index_as_worktree::EntryStatus;
+        use gix::status::{Item, index_worktree};
+
+        self.blocking(|this| {
+            let repo = this.repo();
+            let workdir = Self::workdir(&repo)?;
+            let status = repo
+                .status(gix::progress::Discard)
+                .git()?
+                .untracked_files(gix::status::UntrackedFiles::None)
+                .into_iter(None)
+                .git()?;
+
+            let mut dirty = Vec::new();
+            for item in status {
+                let item = item.git()?;
+                let unchanged = match &item {
+                    Item::IndexWorktree(index_worktree::Item::Modification { status, .. }) => {
+                        matches!(
+                            status,
+                            EntryStatus::NeedsUpdate(_) | EntryStatus::IntentToAdd
+                        )
+                    }
+                    Item::IndexWorktree(index_worktree::Item::DirectoryContents { .. }) => true,
+                    _ => false,
+                };
+                if !unchanged {
+                    dirty.push(workdir.join(gix::path::from_bstr(item.location())));
+                }
+            }
+            dirty.sort();
+            dirty.dedup();
+            Ok(dirty)
+        })
+        .await
+    }
+
+    async fn tag(&self, name: &str, message: Option<&str>, sign: bool) -> Result<(), Error> {
+        self.create_tag(name, message, sign, false).await
+    }
+
+    async fn move_tag(&self, name: &str, message: Option<&str>, sign: bool) -> Result<(), Error> {
+        self.create_tag(name, message, sign, true).await
+    }
+
+    async fn push(
+        &self,
+        remote: &str,
+        branch: Option<&str>,
+        tag: Option<&str>,
+        moved_tags: &[String],
+    ) -> Result<(), Error> {
+        let workdir = self.blocking(|this| Self::workdir(&this.repo())).await?;
+        crate::vcs::git::push(&workdir, remote, branch, tag, moved_tags).await?;
+        Ok(())
+    }
+
+    async fn tags(&self) -> Result<Vec<String>, Error> {
+        self.blocking(|this| {
+            let repo = this.repo();
+            let platform = repo.references().git()?;
+            let tags = platform
+                .tags()
+                .git()?
+                .filter_map(Result::ok)
+                .map(|tag| tag.name().shorten().to_string())
+                .collect();
+            Ok(tags)
+        })
+        .await
+    }
+
+    async fn commit_messages(&self, since: Option<&str>) -> Result<Vec<String>, Error> {
+        use gix::revision::walk::Sorting;
+        use gix::traverse::commit::simple::CommitTimeOrder;
+
+        let since = since.map(|since| format!("{since}^{{commit}}"));
+        self.blocking(move |this| {
+            let repo = this.repo();
+            let head = repo.head_id().git()?;
+            let mut walk = repo
+                .rev_walk([head])
+                .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst));
+            if let Some(since) = since {
+                let since = repo.rev_parse_single(since.as_str()).git()?;
+                walk = walk.with_hidden([since]);
+            }
+
+            let mut messages = Vec::new();
+            for info in walk.all().git()? {
+                let commit = info.git()?.object().git()?;
+                let message = commit.message_raw().git()?.to_str_lossy();
+                let message = message.trim();
+                if !message.is_empty() {
+                    messages.push(message.to_string());
+                }
+            }
+            Ok(messages)
+        })
+        .await
+    }
+
+    async fn latest_tag_and_revision(
+        &self,
+        tag_name: &PythonFormatString,
+        parse_version_regex: &regex::Regex,
+    ) -> Result<TagAndRevision, Error> {
+        let tag_name = tag_name.clone();
+        let parse_version_regex = parse_version_regex.clone();
+        self.blocking(move |this| {
+            let tag = this.latest_tag_info(&tag_name, &parse_version_regex)?;
+            let revision = this.revision_info().ok().flatten();
+            Ok(TagAndRevision { tag, revision })
+        })
+        .await
+    }
+}
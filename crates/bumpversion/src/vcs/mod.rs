@@ -3,6 +3,8 @@
 //! Defines the `VersionControlSystem` trait and related data structures
 //! for interacting with git and other VCS backends.
 pub mod git;
+#[cfg(feature = "gitoxide")]
+pub mod gitoxide;
 
 #[cfg(test)]
 /// Test utilities for VCS interactions.
@@ -69,6 +71,8 @@ pub trait VersionControlSystem {
     fn path(&self) -> &Path;
 
     /// Stage a set of files for commit.
+    ///
+    /// Only tracked files are staged, like `git add --update` does.
     fn add<P>(
         &self,
         files: impl IntoIterator<Item = P>,
@@ -76,6 +80,23 @@ pub trait VersionControlSystem {
     where
         P: AsRef<std::ffi::OsStr>;
 
+    /// Stage a set of files for commit, and start tracking the ones that are untracked, such as
+    /// a changelog the bump creates.
+    fn add_new<P>(
+        &self,
+        files: impl IntoIterator<Item = P>,
+    ) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        P: AsRef<std::ffi::OsStr>;
+
+    /// Unstage a set of files, resetting their index entries to `HEAD`.
+    fn reset<P>(
+        &self,
+        files: impl IntoIterator<Item = P>,
+    ) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        P: AsRef<std::ffi::OsStr>;
+
     /// Create a commit with the given message and environment.
     fn commit<A, E, AS, EK, EV>(
         &self,
@@ -98,9 +119,38 @@ pub trait VersionControlSystem {
         sign: bool,
     ) -> impl Future<Output = Result<(), Self::Error>>;
 
+    /// Create the tag `name` at `HEAD`, or move it there if it already exists.
+    ///
+    /// Like [`VersionControlSystem::tag`], the tag is annotated if a `message` is given. An
+    /// existing tag is replaced in a single step, so it is kept if the tag cannot be created.
+    fn move_tag(
+        &self,
+        name: &str,
+        message: Option<&str>,
+        sign: bool,
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Push `branch`, `tag` and the `moved_tags` to `remote` atomically, so either all are
+    /// updated or none is.
+    ///
+    /// The `moved_tags` are force-pushed, since they replace tags of an earlier release.
+    fn push(
+        &self,
+        remote: &str,
+        branch: Option<&str>,
+        tag: Option<&str>,
+        moved_tags: &[String],
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+
     /// List all tags in the repository.
     fn tags(&self) -> impl Future<Output = Result<Vec<String>, Self::Error>>;
 
+    /// List the messages of the commits after `since`, or of all commits if `since` is `None`.
+    fn commit_messages(
+        &self,
+        since: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<String>, Self::Error>>;
+
     /// List files with uncommitted changes.
     fn dirty_files(&self) -> impl Future<Output = Result<Vec<PathBuf>, Self::Error>>;
 
@@ -111,3 +161,353 @@ pub trait VersionControlSystem {
         parse_version_regex: &regex::Regex,
     ) -> impl Future<Output = Result<TagAndRevision, Self::Error>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        f_string::PythonFormatString,
+        tests::sim_assert_eq_sorted,
+        vcs::{TagInfo, VersionControlSystem, temp::EphemeralRepository},
+    };
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq as sim_assert_eq;
+    use std::path::{Path, PathBuf};
+
+    /// Run the backend-agnostic tests below against the backend `$vcs`.
+    macro_rules! backend_tests {
+        ($name:ident, $vcs:ty) => {
+            mod $name {
+                #[tokio::test]
+                async fn test_create_empty_repo() -> color_eyre::eyre::Result<()> {
+                    super::create_empty_repo::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_tag() -> color_eyre::eyre::Result<()> {
+                    super::tag::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_move_tag() -> color_eyre::eyre::Result<()> {
+                    super::move_tag::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_replace_tag() -> color_eyre::eyre::Result<()> {
+                    super::replace_tag::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_dirty_tree() -> color_eyre::eyre::Result<()> {
+                    super::dirty_tree::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_add_new() -> color_eyre::eyre::Result<()> {
+                    super::add_new::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_reset() -> color_eyre::eyre::Result<()> {
+                    super::reset::<$vcs>().await
+                }
+
+                #[tokio::test]
+                async fn test_commit_env() -> color_eyre::eyre::Result<()> {
+                    super::commit_env::<$vcs>().await
+                }
+            }
+        };
+    }
+
+    backend_tests!(git, crate::vcs::git::GitRepository);
+    #[cfg(feature = "gitoxide")]
+    backend_tests!(gitoxide, crate::vcs::gitoxide::GitoxideRepository);
+
+    async fn write(path: &Path, contents: &str) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, contents).await?;
+        Ok(())
+    }
+
+    async fn latest_tag<VCS>(repo: &EphemeralRepository<VCS>) -> eyre::Result<Option<TagInfo>>
+    where
+        VCS: VersionControlSystem,
+    {
+        let tag_name = PythonFormatString::parse("v{new_version}")?;
+        let parse_version_regex =
+            regex::Regex::new(r"(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)")?;
+        let info = repo
+            .latest_tag_and_revision(&tag_name, &parse_version_regex)
+            .await?;
+        Ok(info.tag)
+    }
+
+    /// A new repository has no tags and nothing to commit.
+    async fn create_empty_repo<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        sim_assert_eq!(repo.tags().await?, Vec::<String>::new());
+        sim_assert_eq!(repo.dirty_files().await?, Vec::<PathBuf>::new());
+        Ok(())
+    }
+
+    /// Lightweight and annotated tags are both found, with the distance of the commits after them.
+    async fn tag<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
+            .await?;
+        sim_assert_eq!(repo.dirty_files().await?, Vec::<PathBuf>::new());
+        sim_assert_eq!(latest_tag(&repo).await?, None);
+
+        let tags = [("v1.0.0", None), ("v1.1.0", Some("release 1.1.0"))];
+        for (idx, (name, message)) in tags.into_iter().enumerate() {
+            repo.tag(name, message, false).await?;
+            let latest = latest_tag(&repo).await?;
+            sim_assert_eq!(
+                latest
+                    .as_ref()
+                    .map(|tag| (tag.current_tag.as_str(), tag.current_version.as_str())),
+                Some((name, name.trim_start_matches('v')))
+            );
+            sim_assert_eq!(latest.map(|tag| tag.distance_to_latest_tag), Some(0));
+
+            write(&readme, &format!("Hello, world {idx}!")).await?;
+            repo.add([&readme]).await?;
+            repo.commit::<_, _, &str, &str, &str>(&format!("fix: change {idx}"), [], [])
+                .await?;
+        }
+
+        let latest = latest_tag(&repo)
+            .await?
+            .ok_or_else(|| eyre::eyre!("missing latest tag"))?;
+        sim_assert_eq!(latest.current_tag, "v1.1.0");
+        sim_assert_eq!(latest.distance_to_latest_tag, 1);
+        assert!(!latest.dirty);
+        sim_assert_eq!(repo.tags().await?, vec!["v1.0.0", "v1.1.0"]);
+        sim_assert_eq!(
+            repo.commit_messages(Some("v1.1.0")).await?,
+            vec!["fix: change 1"]
+        );
+        sim_assert_eq!(
+            repo.commit_messages(None).await?,
+            vec!["fix: change 1", "fix: change 0", "initial commit"]
+        );
+        Ok(())
+    }
+
+    /// A moved tag follows each release, and never shadows the release tag itself.
+    async fn move_tag<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+
+        for (idx, release) in ["v1.0.0", "v1.1.0"].into_iter().enumerate() {
+            write(&readme, &format!("Hello, world {idx}!")).await?;
+            repo.add([&readme]).await?;
+            repo.commit::<_, _, &str, &str, &str>(&format!("release {release}"), [], [])
+                .await?;
+            repo.tag(release, Some(release), false).await?;
+            repo.move_tag("v1", Some(release), false).await?;
+
+            // `v1` is at the release, and the release is still the latest tag
+            sim_assert_eq!(
+                repo.commit_messages(Some("v1")).await?,
+                Vec::<String>::new()
+            );
+            sim_assert_eq!(
+                latest_tag(&repo).await?.map(|tag| tag.current_tag),
+                Some(release.to_string())
+            );
+        }
+        sim_assert_eq!(repo.tags().await?, vec!["v1", "v1.0.0", "v1.1.0"]);
+        Ok(())
+    }
+
+    /// An annotated release tag can be moved to a later commit, replacing it.
+    async fn replace_tag<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
+            .await?;
+        repo.tag("v1.0.0", Some("release 1.0.0"), false).await?;
+
+        write(&readme, "Hello, world again!").await?;
+        repo.add([&readme]).await?;
+        repo.commit::<_, _, &str, &str, &str>("fix: change", [], [])
+            .await?;
+        repo.move_tag("v1.0.0", Some("release 1.0.0"), false)
+            .await?;
+        sim_assert_eq!(repo.tags().await?, vec!["v1.0.0"]);
+        sim_assert_eq!(
+            latest_tag(&repo)
+                .await?
+                .map(|tag| tag.distance_to_latest_tag),
+            Some(0)
+        );
+        Ok(())
+    }
+
+    /// Untracked files are not dirty, staged and modified tracked files are.
+    async fn dirty_tree<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        sim_assert_eq!(repo.dirty_files().await?.len(), 0);
+
+        // add some untracked files
+        let mut dirty_files: Vec<PathBuf> = ["foo.txt", "dir/bar.txt"]
+            .iter()
+            .map(|f| repo.path().join(f))
+            .collect();
+        for dirty_file in &dirty_files {
+            write(dirty_file, "Hello, world!").await?;
+        }
+        sim_assert_eq!(repo.dirty_files().await?.len(), 0);
+
+        // track first file
+        let first_dirty_file = dirty_files
+            .first()
+            .ok_or_else(|| eyre::eyre!("expected at least one dirty file"))?
+            .clone();
+        let mut expected_first_dirty_files = vec![first_dirty_file.clone()];
+        repo.track(&expected_first_dirty_files).await?;
+        let mut actual_dirty_files = repo.dirty_files().await?;
+        sim_assert_eq_sorted!(actual_dirty_files, expected_first_dirty_files);
+
+        // track all files
+        repo.track(&dirty_files).await?;
+        let mut actual_dirty_files = repo.dirty_files().await?;
+        sim_assert_eq_sorted!(actual_dirty_files, dirty_files);
+
+        // modified files are dirty until committed, whether staged or not
+        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
+            .await?;
+        sim_assert_eq!(repo.dirty_files().await?.len(), 0);
+        write(&first_dirty_file, "Goodbye, world!").await?;
+        sim_assert_eq!(repo.dirty_files().await?, vec![first_dirty_file.clone()]);
+        repo.add([&first_dirty_file]).await?;
+        sim_assert_eq!(repo.dirty_files().await?, vec![first_dirty_file]);
+        repo.commit::<_, _, &str, &str, &str>("update", [], [])
+            .await?;
+        sim_assert_eq!(repo.dirty_files().await?.len(), 0);
+        Ok(())
+    }
+
+    /// `add` leaves an untracked file alone, while `add_new` starts tracking it.
+    async fn add_new<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
+            .await?;
+
+        let changelog = repo.path().join("docs/CHANGELOG.md");
+        write(&changelog, "# Changelog").await?;
+        repo.add([&changelog]).await?;
+        sim_assert_eq!(repo.dirty_files().await?.len(), 0);
+
+        repo.add_new([&changelog]).await?;
+        sim_assert_eq!(repo.dirty_files().await?, vec![changelog]);
+        repo.commit::<_, _, &str, &str, &str>("add changelog", [], [])
+            .await?;
+        let tracked = std::process::Command::new("git")
+            .args(["ls-tree", "-r", "--name-only", "HEAD"])
+            .current_dir(repo.path())
+            .output()?;
+        sim_assert_eq!(
+            String::from_utf8(tracked.stdout)?,
+            "README.md\ndocs/CHANGELOG.md\n"
+        );
+        Ok(())
+    }
+
+    /// Resetting unstages a file, keeping the change in the working tree.
+    async fn reset<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+        repo.commit::<_, _, &str, &str, &str>("initial commit", [], [])
+            .await?;
+
+        write(&readme, "Goodbye, world!").await?;
+        repo.add([&readme]).await?;
+        repo.reset([&readme]).await?;
+
+        let staged = std::process::Command::new("git")
+            .args(["diff", "--cached", "--name-only"])
+            .current_dir(repo.path())
+            .output()?;
+        sim_assert_eq!(String::from_utf8(staged.stdout)?, "");
+        sim_assert_eq!(repo.dirty_files().await?, vec![readme.clone()]);
+        sim_assert_eq!(tokio::fs::read_to_string(&readme).await?, "Goodbye, world!");
+        Ok(())
+    }
+
+    /// The identity variables of the commit environment name the author and committer.
+    async fn commit_env<VCS>() -> eyre::Result<()>
+    where
+        VCS: VersionControlSystem,
+    {
+        crate::tests::init();
+        let repo: EphemeralRepository<VCS> = EphemeralRepository::new().await?;
+        let readme = repo.path().join("README.md");
+        write(&readme, "Hello, world!").await?;
+        repo.track(&[&readme]).await?;
+        repo.commit::<_, _, &str, _, _>(
+            "initial commit",
+            [],
+            [
+                ("GIT_AUTHOR_NAME", "Release Bot"),
+                ("GIT_COMMITTER_EMAIL", "release@example.com"),
+                ("BUMPVERSION_NEW_VERSION", "1.0.0"),
+            ],
+        )
+        .await?;
+
+        let output = std::process::Command::new("git")
+            .args(["log", "-1", "--format=%an <%ce>"])
+            .current_dir(repo.path())
+            .output()?;
+        sim_assert_eq!(
+            String::from_utf8(output.stdout)?.trim(),
+            "Release Bot <release@example.com>"
+        );
+        Ok(())
+    }
+}
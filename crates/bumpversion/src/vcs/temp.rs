@@ -44,24 +44,30 @@ where
 
     async fn init(path: &Path) -> eyre::Result<()> {
         tokio::fs::create_dir_all(path).await?;
+        let commands: [&[&str]; 4] = [
+            &["init"],
+            &["config", "user.name", "bumpversion"],
+            &["config", "user.email", "bumpversion@example.com"],
+            &["config", "commit.gpgsign", "false"],
+        ];
+        for args in commands {
+            let mut cmd = Command::new("git");
+            cmd.args(args);
+            cmd.current_dir(path);
+            let _ = run_command(&mut cmd).await?;
+        }
+        Ok(())
+    }
+
+    /// Start tracking `files`, which the `add` of the backends leaves alone.
+    pub(crate) async fn track(&self, files: &[impl AsRef<Path>]) -> eyre::Result<()> {
         let mut cmd = Command::new("git");
-        cmd.args(["init"]);
-        cmd.current_dir(path);
+        cmd.arg("add");
+        cmd.args(files.iter().map(AsRef::as_ref));
+        cmd.current_dir(self.path());
         let _ = run_command(&mut cmd).await?;
         Ok(())
     }
-
-    // async fn add(&self, files: &[impl AsRef<Path>]) -> eyre::Result<()> {
-    //     let files = files
-    //         .iter()
-    //         .map(|f| f.as_ref().to_string_lossy().to_string());
-    //     let mut cmd = Command::new("git");
-    //     cmd.arg("add");
-    //     cmd.args(files);
-    //     cmd.current_dir(self.path());
-    //     let _ = run_command(&mut cmd).await?;
-    //     Ok(())
-    // }
 }
 
 impl<VCS> std::ops::Deref for EphemeralRepository<VCS> {
@@ -1,5 +1,5 @@
 use crate::{
-    config::version::{VersionComponentConfigs, VersionComponentSpec},
+    config::version::{ComponentOrder, VersionComponentConfigs, VersionComponentSpec},
     f_string::PythonFormatString,
 };
 use indexmap::IndexMap;
@@ -179,6 +179,8 @@ pub mod numeric {
         pub first_value: usize,
         /// Optional starting value for bumping when unspecified.
         pub optional_value: usize,
+        /// Minimum number of digits of the bumped number, zero-padded.
+        pub width: usize,
         // pub first_value: &'a str,
         // pub optional_value: &'a str,
         // pub first_value: String,
@@ -218,6 +220,7 @@ pub mod numeric {
             Ok(Self {
                 first_value,
                 optional_value,
+                width: 0,
             })
         }
 
@@ -275,13 +278,208 @@ pub mod numeric {
                 });
             };
             Ok(format!(
-                "{}{}{}",
+                "{}{bumped_numeric:0width$}{}",
                 prefix_part.as_str(),
-                bumped_numeric.to_string().as_str(),
-                suffix_part.as_str()
+                suffix_part.as_str(),
+                width = self.width,
             ))
         }
     }
+
+    /// The number of digits of the first number in `value`, including leading zeros.
+    #[must_use]
+    pub fn width(value: &str) -> usize {
+        FIRST_NUMERIC_REGEX
+            .captures(value)
+            .and_then(|captures| captures.name("number"))
+            .map_or(0, |number| number.as_str().trim_start_matches('-').len())
+    }
+
+    /// Zero-pad the first number in `value` to at least `width` digits.
+    #[must_use]
+    pub fn pad(value: &str, width: usize) -> String {
+        let Some(captures) = FIRST_NUMERIC_REGEX.captures(value) else {
+            return value.to_string();
+        };
+        match (
+            captures.name("prefix"),
+            captures.name("number"),
+            captures.name("suffix"),
+        ) {
+            (Some(prefix), Some(number), Some(suffix)) => {
+                let number = number.as_str();
+                let (sign, digits) = match number.strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", number),
+                };
+                format!(
+                    "{}{sign}{digits:0>width$}{}",
+                    prefix.as_str(),
+                    suffix.as_str()
+                )
+            }
+            _ => value.to_string(),
+        }
+    }
+
+    /// Compare two values run of digits by run of digits, so `2024.10` comes after `2024.9`
+    /// and `007` equals `7`. Anything between the digits compares as text.
+    #[must_use]
+    pub fn compare(value: &str, other: &str) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        fn runs(value: &str) -> Vec<&[u8]> {
+            value
+                .as_bytes()
+                .chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit())
+                .collect()
+        }
+        let significant = |run: &[u8]| -> Vec<u8> {
+            run.iter()
+                .copied()
+                .skip_while(|digit| *digit == b'0')
+                .collect()
+        };
+        let is_number = |run: &[u8]| run.first().is_some_and(u8::is_ascii_digit);
+
+        let (runs, other_runs) = (runs(value), runs(other));
+        for (run, other_run) in runs.iter().zip(&other_runs) {
+            let ordering = if is_number(run) && is_number(other_run) {
+                let (number, other_number) = (significant(run), significant(other_run));
+                number
+                    .len()
+                    .cmp(&other_number.len())
+                    .then_with(|| number.cmp(&other_number))
+            } else {
+                run.cmp(other_run)
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        runs.len().cmp(&other_runs.len())
+    }
+}
+
+/// Calendar versioning utilities for version components.
+pub mod calver {
+    use crate::f_string::{FormatError, ParseError, PythonFormatString};
+    use chrono::Datelike;
+    use std::collections::HashMap;
+
+    /// Errors encountered when formatting `CalVer` components.
+    #[derive(thiserror::Error, Debug)]
+    pub enum Error {
+        /// The `CalVer` format is not a valid format string.
+        #[error("invalid calver format {format:?}")]
+        InvalidFormat {
+            /// Source parse error.
+            #[source]
+            source: ParseError,
+            /// The offending `CalVer` format.
+            format: String,
+        },
+        /// The `CalVer` format references an unknown token.
+        #[error("calver format {format:?} contains an unknown token")]
+        UnknownToken {
+            /// Source formatting error naming the token.
+            #[source]
+            source: FormatError,
+            /// The offending `CalVer` format.
+            format: String,
+        },
+    }
+
+    /// Return the `CalVer` tokens for the given date.
+    ///
+    /// Follows the conventions of <https://calver.org>: `YYYY`, `YY` and `0Y` for the year,
+    /// `MM` and `0M` for the month, `DD` and `0D` for the day, `WW` and `0W` for the week of
+    /// the year, as well as `MMM`, `JJJ`/`00J`, `Q`, `UU`/`0U`, `VV`/`0V` and `GGGG`/`GG`/`0G`.
+    #[must_use]
+    pub fn tokens(date: chrono::NaiveDate) -> HashMap<&'static str, String> {
+        let short_year = date.year() - 2000;
+        let iso_week = date.iso_week();
+        let short_iso_year = iso_week.year() - 2000;
+        let week_of_year = |format: &str| date.format(format).to_string();
+        let unpadded = |value: String| value.trim_start_matches('0').to_string();
+        let or_zero = |value: String| {
+            if value.is_empty() {
+                "0".to_string()
+            } else {
+                value
+            }
+        };
+        [
+            ("YYYY", date.year().to_string()),
+            ("YY", short_year.to_string()),
+            ("0Y", format!("{short_year:02}")),
+            ("MMM", date.format("%b").to_string()),
+            ("MM", date.month().to_string()),
+            ("0M", format!("{:02}", date.month())),
+            ("DD", date.day().to_string()),
+            ("0D", format!("{:02}", date.day())),
+            ("JJJ", date.ordinal().to_string()),
+            ("00J", format!("{:03}", date.ordinal())),
+            ("Q", (date.month0() / 3 + 1).to_string()),
+            ("WW", or_zero(unpadded(week_of_year("%W")))),
+            ("0W", week_of_year("%W")),
+            ("UU", or_zero(unpadded(week_of_year("%U")))),
+            ("0U", week_of_year("%U")),
+            ("VV", iso_week.week().to_string()),
+            ("0V", format!("{:02}", iso_week.week())),
+            ("GGGG", iso_week.year().to_string()),
+            ("GG", short_iso_year.to_string()),
+            ("0G", format!("{short_iso_year:02}")),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Function for bumping `CalVer` version components.
+    #[derive(Debug)]
+    pub struct CalVerFunction<'a> {
+        /// The `CalVer` format, e.g. `{YYYY}.{0M}`.
+        pub format: &'a str,
+    }
+
+    impl<'a> CalVerFunction<'a> {
+        /// Create a new `CalVerFunction`.
+        #[must_use]
+        pub fn new(format: &'a str) -> Self {
+            Self { format }
+        }
+
+        /// Format the given date using the `CalVer` format.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`Error::InvalidFormat`] if the format cannot be parsed, or
+        /// [`Error::UnknownToken`] if it references a token that is not a `CalVer` token.
+        pub fn format(&self, date: chrono::NaiveDate) -> Result<String, Error> {
+            let format =
+                PythonFormatString::parse(self.format).map_err(|source| Error::InvalidFormat {
+                    source,
+                    format: self.format.to_string(),
+                })?;
+            format
+                .format(&tokens(date), true)
+                .map_err(|source| Error::UnknownToken {
+                    source,
+                    format: self.format.to_string(),
+                })
+        }
+
+        /// Return today's date formatted using the `CalVer` format.
+        ///
+        /// The current value is irrelevant: a `CalVer` component always bumps to today.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`Error`] if the `CalVer` format is invalid.
+        pub fn bump(&self) -> Result<String, Error> {
+            self.format(chrono::Local::now().date_naive())
+        }
+    }
 }
 
 /// A single version component, combining a value and its bump/reset specification.
@@ -325,9 +523,18 @@ pub enum BumpError {
     /// Underlying values bump error.
     #[error(transparent)]
     Values(#[from] values::Error),
+    /// Underlying `CalVer` bump error.
+    #[error(transparent)]
+    CalVer(#[from] calver::Error),
     /// Specified component name does not exist in the version.
     #[error("invalid version component {0:?}")]
     InvalidComponent(String),
+    /// No component has a list of values that can serve as the pre-release label.
+    #[error("no version component has a list of pre-release values")]
+    NoPrereleaseComponent,
+    /// The version is not a pre-release.
+    #[error("version is not a pre-release")]
+    NotPrerelease,
 }
 
 impl Component {
@@ -343,7 +550,11 @@ impl Component {
         if spec.first_value.is_none() {
             if !spec.values.is_empty() {
                 spec.first_value = spec.values.first().cloned();
-            } else if spec.calver_format.is_none() {
+            } else if let Some(calver_format) = spec.calver_format.as_deref() {
+                // a calver component resets to today's date. The format is validated when the
+                // config is parsed, and `bump` reports the error for a spec built by hand.
+                spec.first_value = calver::CalVerFunction::new(calver_format).bump().ok();
+            } else {
                 spec.first_value = Some("0".to_string());
             }
         }
@@ -356,6 +567,15 @@ impl Component {
             }
         }
 
+        if let Some(width) = spec.width
+            && spec.values.is_empty()
+            && spec.calver_format.is_none()
+        {
+            let pad = |value: String| numeric::pad(&value, width);
+            spec.first_value = spec.first_value.map(pad);
+            spec.optional_value = spec.optional_value.map(pad);
+        }
+
         Self {
             value: value.map(std::string::ToString::to_string),
             spec,
@@ -375,28 +595,85 @@ impl Component {
 
     /// Return a new `Component` initialized with its `spec.first_value`.
     ///
-    /// Useful for resetting dependent components.
+    /// Useful for resetting dependent components. A numeric first value is zero-padded to the
+    /// width of this component.
     #[must_use]
     pub fn first(&self) -> Self {
+        let width = self.numeric_width();
+        let value = match self.spec.first_value.as_deref() {
+            Some(first_value) if width > 0 => Some(numeric::pad(first_value, width)),
+            first_value => first_value.map(ToString::to_string),
+        };
         Self {
-            value: self.spec.first_value.clone(),
+            value,
             ..self.clone()
         }
     }
 
+    /// The minimum number of digits of this numeric component, from its `width` and, if it
+    /// is padded, its current value.
+    fn numeric_width(&self) -> usize {
+        if self.spec.calver_format.is_some() || !self.spec.values.is_empty() {
+            return 0;
+        }
+        let current_width = match self.value.as_deref() {
+            Some(value) if self.spec.pad => numeric::width(value),
+            _ => 0,
+        };
+        self.spec.width.unwrap_or(0).max(current_width)
+    }
+
+    /// Whether this component is left out of the version, because it has its optional value.
+    fn is_absent(&self) -> bool {
+        self.value() == self.spec.optional_value.as_deref()
+    }
+
+    /// Compare the value of this component with the value of `other`, in the order of its spec.
+    ///
+    /// Values of a list compare by their position in it, numbers and `CalVer` dates
+    /// numerically. A [`ComponentOrder::PreRelease`] component sorts before its absence, and a
+    /// [`ComponentOrder::Ignore`] one is always equal. Returns `None` if a value is not one of
+    /// the values of the list.
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.spec.order.unwrap_or_default() {
+            ComponentOrder::Value => {}
+            ComponentOrder::Ignore => return Some(std::cmp::Ordering::Equal),
+            ComponentOrder::PreRelease => match (self.is_absent(), other.is_absent()) {
+                (true, true) => return Some(std::cmp::Ordering::Equal),
+                (true, false) => return Some(std::cmp::Ordering::Greater),
+                (false, true) => return Some(std::cmp::Ordering::Less),
+                (false, false) => {}
+            },
+        }
+        let value = self.value().unwrap_or_default();
+        let other_value = other.value().unwrap_or_default();
+        if self.spec.values.is_empty() {
+            return Some(numeric::compare(value, other_value));
+        }
+        let position = |value: &str| self.spec.values.iter().position(|v| v == value);
+        Some(position(value)?.cmp(&position(other_value)?))
+    }
+
     /// Bump this component according to its specification.
     ///
-    /// For components with explicit value lists, uses those; otherwise numeric bump.
+    /// `CalVer` components are set to today's date. For components with explicit value lists,
+    /// uses those; otherwise numeric bump.
     ///
     /// # Errors
-    /// Returns `BumpError::Numeric` or `BumpError::InvalidComponent` on failure.
+    /// Returns `BumpError::Numeric`, `BumpError::CalVer` or `BumpError::InvalidComponent` on failure.
     pub fn bump(&self) -> Result<Self, BumpError> {
-        let value = if self.spec.values.is_empty() {
+        let value = if let Some(calver_format) = self.spec.calver_format.as_deref() {
+            calver::CalVerFunction::new(calver_format).bump()?
+        } else if self.spec.values.is_empty() {
             // numeric
-            let func = numeric::NumericFunction::new(
-                self.spec.first_value.as_deref(),
-                self.spec.optional_value.as_deref(),
-            )?;
+            let func = numeric::NumericFunction {
+                width: self.numeric_width(),
+                ..numeric::NumericFunction::new(
+                    self.spec.first_value.as_deref(),
+                    self.spec.optional_value.as_deref(),
+                )?
+            };
             let value = self
                 .value
                 .as_deref()
@@ -442,10 +719,43 @@ pub struct Version {
 }
 
 impl std::fmt::Display for Version {
+    /// Serialize the version with the patterns of its spec, or list its components if none of
+    /// them fits.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_map()
-            .entries(self.components.iter().map(|(k, v)| (k, v.value())))
-            .finish()
+        let ctx = HashMap::<&str, &str>::new();
+        match self.serialize(&self.spec.serialize_version_patterns, &ctx) {
+            Ok(serialized) => f.write_str(&serialized),
+            Err(_) => f
+                .debug_map()
+                .entries(self.components.iter().map(|(k, v)| (k, v.value())))
+                .finish(),
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(std::cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Version {
+    /// Compare two versions component by component, in the order of the `parse` pattern.
+    ///
+    /// Returns `None` if the versions have different components, or a value is not one of the
+    /// values of its component.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if !self.components.keys().eq(other.components.keys()) {
+            return None;
+        }
+        for (component, other_component) in self.components.values().zip(other.components.values())
+        {
+            match component.compare(other_component)? {
+                std::cmp::Ordering::Equal => {}
+                ordering => return Some(ordering),
+            }
+        }
+        Some(std::cmp::Ordering::Equal)
     }
 }
 
@@ -594,14 +904,18 @@ impl Version {
 
         let should_reset = components_to_reset.contains(component);
         if !should_reset {
-            let bumped = self
+            let current = self
                 .components
                 .get(component)
-                .ok_or_else(|| BumpError::InvalidComponent(component.to_string()))?
-                .bump()?;
+                .ok_or_else(|| BumpError::InvalidComponent(component.to_string()))?;
+            let bumped = current.bump()?;
+            // a calver component bumped on the same date keeps its dependents
+            let changed = bumped.value() != current.value();
             new_components.insert(component.to_string(), bumped);
-            let dependants = self.spec.dependents(component);
-            components_to_reset.extend(dependants);
+            if changed {
+                let dependants = self.spec.dependents(component);
+                components_to_reset.extend(dependants);
+            }
         }
 
         // dbg!(&new_components, &components_to_reset);
@@ -633,6 +947,132 @@ impl Version {
             ..self.clone()
         })
     }
+
+    /// The component holding the pre-release label, e.g. `dev` or `rc`.
+    ///
+    /// This is the first component with a list of values. Its optional value, e.g. `final`,
+    /// marks a release.
+    fn prerelease_label(&self) -> Result<(&str, &Component), BumpError> {
+        self.components
+            .iter()
+            .find(|(_, component)| !component.spec.values.is_empty())
+            .map(|(comp_name, component)| (comp_name.as_str(), component))
+            .ok_or(BumpError::NoPrereleaseComponent)
+    }
+
+    /// Whether this version is a pre-release.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BumpError::NoPrereleaseComponent`] if no component has a list of values.
+    pub fn is_prerelease(&self) -> Result<bool, BumpError> {
+        let (_, label) = self.prerelease_label()?;
+        Ok(label.value() != label.spec.optional_value.as_deref())
+    }
+
+    /// Set `component` to `value` and reset its dependents.
+    fn set(&self, component: &str, value: &str) -> Result<Self, BumpError> {
+        let current = self
+            .components
+            .get(component)
+            .ok_or_else(|| BumpError::InvalidComponent(component.to_string()))?;
+        let mut new_components = self.components.clone();
+        new_components.insert(
+            component.to_string(),
+            Component {
+                value: Some(value.to_string()),
+                ..current.clone()
+            },
+        );
+        for comp_name in self.spec.dependents(component) {
+            if let Some(dependent) = self.components.get(comp_name)
+                && dependent.spec.independent != Some(true)
+            {
+                new_components.insert(comp_name.to_string(), dependent.first());
+            }
+        }
+        Ok(Self {
+            components: new_components,
+            ..self.clone()
+        })
+    }
+
+    /// Enter or continue a pre-release.
+    ///
+    /// With a `component`, it is bumped and a released label is set to its first pre-release
+    /// value, e.g. `1.2.3` to `1.3.0-rc.0` for `minor`. Without one, the component following the
+    /// label is bumped, e.g. `1.3.0-rc.0` to `1.3.0-rc.1`, or the label itself if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BumpError::NotPrerelease`] if no `component` is given for a release, and
+    /// [`BumpError`] if a component cannot be bumped.
+    pub fn pre(&self, component: Option<&str>) -> Result<Self, BumpError> {
+        let Some(component) = component else {
+            if !self.is_prerelease()? {
+                return Err(BumpError::NotPrerelease);
+            }
+            let (label, _) = self.prerelease_label()?;
+            return match self
+                .spec
+                .dependency_map
+                .get(label)
+                .and_then(|deps| deps.first())
+            {
+                Some(number) => self.bump(number),
+                None => self.promote(),
+            };
+        };
+
+        let bumped = self.bump(component)?;
+        if bumped.is_prerelease()? {
+            return Ok(bumped);
+        }
+        let (label, label_component) = bumped.prerelease_label()?;
+        let release = label_component.spec.optional_value.as_deref();
+        let first_prerelease = label_component
+            .spec
+            .values
+            .iter()
+            .find(|value| Some(value.as_str()) != release)
+            .ok_or(BumpError::NoPrereleaseComponent)?;
+        bumped.set(label, first_prerelease)
+    }
+
+    /// Promote a pre-release to its next label, e.g. from `dev` to `rc`.
+    ///
+    /// Promoting the last pre-release label, e.g. `rc` to `final`, releases the version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BumpError::NotPrerelease`] if the version is already released, and
+    /// [`BumpError::Values`] if the label has no next value.
+    pub fn promote(&self) -> Result<Self, BumpError> {
+        if !self.is_prerelease()? {
+            return Err(BumpError::NotPrerelease);
+        }
+        let (label, _) = self.prerelease_label()?;
+        self.bump(label)
+    }
+
+    /// Release a pre-release by setting its label to the optional value, e.g. `1.3.0-rc.1` to
+    /// `1.3.0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BumpError::NotPrerelease`] if the version is already released.
+    pub fn release(&self) -> Result<Self, BumpError> {
+        if !self.is_prerelease()? {
+            return Err(BumpError::NotPrerelease);
+        }
+        let (label, label_component) = self.prerelease_label()?;
+        let release = label_component
+            .spec
+            .optional_value
+            .as_deref()
+            .ok_or(BumpError::NotPrerelease)?;
+        self.set(label, release)
+    }
 }
 
 /// Specification of version components, dependencies, and auto-increment rules.
@@ -647,6 +1087,7 @@ pub struct VersionSpec {
     components: VersionComponentConfigs,
     dependency_map: HashMap<String, Vec<String>>,
     components_to_always_increment: Vec<String>,
+    serialize_version_patterns: Vec<PythonFormatString>,
 }
 
 impl VersionSpec {
@@ -701,6 +1142,19 @@ impl VersionSpec {
             components,
             dependency_map,
             components_to_always_increment,
+            serialize_version_patterns: vec![],
+        }
+    }
+
+    /// Display the versions of this spec with the given serialization patterns.
+    #[must_use]
+    pub fn with_serialize_version_patterns(
+        self,
+        serialize_version_patterns: Vec<PythonFormatString>,
+    ) -> Self {
+        Self {
+            serialize_version_patterns,
+            ..self
         }
     }
 
@@ -765,9 +1219,9 @@ pub enum SerializeError {
         /// List of attempted (index, format pattern) pairs.
         formats: Vec<(usize, PythonFormatString)>,
     },
-    /// A required argument for formatting was missing.
+    /// A required argument for formatting was missing or could not be formatted.
     #[error(transparent)]
-    MissingArgument(#[from] crate::f_string::MissingArgumentError),
+    Format(#[from] crate::f_string::FormatError),
 }
 
 /// Attempts to serialize a version with the given serialization format.
@@ -955,4 +1409,139 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_prerelease_lifecycle() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "1.2.3"
+            parse = '(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)(-(?P<pre_l>[a-z]+)\.(?P<pre_n>\d+))?'
+            serialize = ["{major}.{minor}.{patch}-{pre_l}.{pre_n}", "{major}.{minor}.{patch}"]
+
+            [tool.bumpversion.parts.pre_l]
+            values = ["dev", "rc", "final"]
+            optional_value = "final"
+            first_value = "final"
+        "#};
+
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("pyproject.toml".to_string(), toml.to_string());
+        let mut diagnostics = vec![];
+        let config = config::Config::from_pyproject_toml(toml, file_id, true, &mut diagnostics)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .finalize();
+        let components = config::version::version_component_configs(&config);
+        let version_spec = VersionSpec::from_components(components);
+        let ctx = HashMap::<String, String>::new();
+        let serialize =
+            |version: &Version| version.serialize(&config.global.serialize_version_patterns, &ctx);
+
+        let released = Version::parse("1.2.3", &config.global.parse_version_pattern, &version_spec)
+            .ok_or_else(|| eyre::eyre!("expected current version to parse"))?;
+        assert!(!released.is_prerelease()?);
+        assert!(matches!(
+            released.pre(None),
+            Err(super::BumpError::NotPrerelease)
+        ));
+        assert!(matches!(
+            released.release(),
+            Err(super::BumpError::NotPrerelease)
+        ));
+
+        let dev = released.pre(Some("minor"))?;
+        sim_assert_eq!(serialize(&dev)?, "1.3.0-dev.0");
+        let dev = dev.pre(None)?;
+        sim_assert_eq!(serialize(&dev)?, "1.3.0-dev.1");
+        let rc = dev.promote()?;
+        sim_assert_eq!(serialize(&rc)?, "1.3.0-rc.0");
+        sim_assert_eq!(serialize(&rc.pre(Some("patch"))?)?, "1.3.1-dev.0");
+        sim_assert_eq!(serialize(&rc.release()?)?, "1.3.0");
+        sim_assert_eq!(serialize(&rc.promote()?)?, "1.3.0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_calver_format() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let date = chrono::NaiveDate::from_ymd_opt(2006, 1, 2)
+            .ok_or_else(|| eyre::eyre!("invalid date"))?;
+        let format = |format: &str| super::calver::CalVerFunction::new(format).format(date);
+
+        sim_assert_eq!(format("{YYYY}.{MM}.{DD}")?, "2006.1.2");
+        sim_assert_eq!(format("{YY}.{0M}.{0D}")?, "6.01.02");
+        sim_assert_eq!(format("{0Y}{0M}")?, "0601");
+        sim_assert_eq!(format("{YYYY}-{WW}")?, "2006-1");
+        sim_assert_eq!(format("{YYYY}-{0W}")?, "2006-01");
+        sim_assert_eq!(format("{YYYY}-Q{Q}-{JJJ}")?, "2006-Q1-2");
+        sim_assert_eq!(format("{MMM}{00J}")?, "Jan002");
+        assert!(matches!(
+            format("{YYYY}.{invalid}"),
+            Err(super::calver::Error::UnknownToken { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_calver_bump_resets_dependents_only_on_new_date() -> eyre::Result<()> {
+        crate::tests::init();
+
+        let toml = indoc::indoc! {r#"
+            [tool.bumpversion]
+            current_version = "2006.01.3"
+            parse = '(?P<release>\d+\.\d+)\.(?P<patch>\d+)'
+            serialize = ["{release}.{patch}"]
+
+            [tool.bumpversion.parts.release]
+            calver_format = "{YYYY}.{0M}"
+            always_increment = true
+        "#};
+
+        let printer = BufferedPrinter::default();
+        let file_id = printer.add_source_file("pyproject.toml".to_string(), toml.to_string());
+        let mut diagnostics = vec![];
+        let config = config::Config::from_pyproject_toml(toml, file_id, true, &mut diagnostics)?
+            .ok_or_else(|| eyre::eyre!("expected config to be present"))?
+            .finalize();
+        let components = config::version::version_component_configs(&config);
+        let version_spec = VersionSpec::from_components(components);
+        let today = super::calver::CalVerFunction::new("{YYYY}.{0M}").bump()?;
+        let ctx = HashMap::<String, String>::new();
+
+        // the release date changed, so the patch is reset
+        let current_version = Version::parse(
+            "2006.01.3",
+            &config.global.parse_version_pattern,
+            &version_spec,
+        )
+        .ok_or_else(|| eyre::eyre!("expected current version to parse"))?;
+        let new_version = current_version.bump("patch")?;
+        sim_assert_eq!(
+            new_version.serialize(&config.global.serialize_version_patterns, &ctx)?,
+            format!("{today}.0")
+        );
+
+        // the release date is unchanged, so the patch is bumped
+        let current_version = Version::parse(
+            &format!("{today}.3"),
+            &config.global.parse_version_pattern,
+            &version_spec,
+        )
+        .ok_or_else(|| eyre::eyre!("expected current version to parse"))?;
+        let new_version = current_version.bump("patch")?;
+        sim_assert_eq!(
+            new_version.serialize(&config.global.serialize_version_patterns, &ctx)?,
+            format!("{today}.4")
+        );
+
+        // bumping the release on the same date leaves the version unchanged
+        let new_version = current_version.bump("release")?;
+        sim_assert_eq!(
+            new_version.serialize(&config.global.serialize_version_patterns, &ctx)?,
+            format!("{today}.3")
+        );
+        Ok(())
+    }
 }
@@ -88,3 +88,65 @@ fn test_reset_to_first_values() {
     let reset = component.first();
     assert_eq!(reset.value(), Some("a"));
 }
+
+#[test]
+fn test_numeric_bump_drops_leading_zeros() -> eyre::Result<()> {
+    let spec = VersionComponentSpec::default();
+    let component = Component::new(Some("007"), spec);
+    assert_eq!(component.bump()?.value(), Some("8"));
+    Ok(())
+}
+
+#[test]
+fn test_numeric_bump_pad() -> eyre::Result<()> {
+    let spec = VersionComponentSpec {
+        pad: true,
+        ..Default::default()
+    };
+    let component = Component::new(Some("0099"), spec.clone());
+    assert_eq!(component.bump()?.value(), Some("0100"));
+
+    let component = Component::new(Some("007"), spec.clone());
+    assert_eq!(component.bump()?.value(), Some("008"));
+
+    // Outgrowing the width adds a digit
+    let component = Component::new(Some("99"), spec);
+    assert_eq!(component.bump()?.value(), Some("100"));
+    Ok(())
+}
+
+#[test]
+fn test_numeric_bump_width() -> eyre::Result<()> {
+    let spec = VersionComponentSpec {
+        width: Some(4),
+        ..Default::default()
+    };
+    let component = Component::new(Some("7"), spec.clone());
+    assert_eq!(component.bump()?.value(), Some("0008"));
+
+    let component = Component::new(None, spec);
+    assert_eq!(component.value(), Some("0000"));
+    assert_eq!(component.bump()?.value(), Some("0001"));
+    Ok(())
+}
+
+#[test]
+fn test_reset_to_first_pad() {
+    let spec = VersionComponentSpec {
+        pad: true,
+        ..Default::default()
+    };
+    let component = Component::new(Some("0099"), spec);
+    assert_eq!(component.first().value(), Some("0000"));
+}
+
+#[test]
+fn test_reset_to_first_width() {
+    let spec = VersionComponentSpec {
+        first_value: Some("1".to_string()),
+        width: Some(3),
+        ..Default::default()
+    };
+    let component = Component::new(Some("12"), spec);
+    assert_eq!(component.first().value(), Some("001"));
+}
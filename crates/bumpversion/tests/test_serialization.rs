@@ -1,7 +1,7 @@
 //! Integration tests for version parsing and serialization.
 
 use bumpversion::{
-    config::version::VersionComponentSpec,
+    config::version::{ComponentOrder, VersionComponentSpec},
     f_string::PythonFormatString,
     version::{Version, VersionSpec},
 };
@@ -154,3 +154,138 @@ fn test_serialize_with_newlines() -> eyre::Result<()> {
     assert_eq!(serialized, "MAJOR=31\nMINOR=0\nPATCH=3\n");
     Ok(())
 }
+
+fn prerelease_spec() -> VersionSpec {
+    let mut components = IndexMap::new();
+    components.insert("major".to_string(), VersionComponentSpec::default());
+    components.insert("minor".to_string(), VersionComponentSpec::default());
+    components.insert("patch".to_string(), VersionComponentSpec::default());
+    components.insert(
+        "release".to_string(),
+        VersionComponentSpec {
+            values: vec!["alpha".to_string(), "beta".to_string(), "final".to_string()],
+            optional_value: Some("final".to_string()),
+            ..Default::default()
+        },
+    );
+    VersionSpec::from_components(components)
+}
+
+#[test]
+fn test_version_ordering_is_numeric() {
+    let spec = semver_spec();
+    let version = |major, minor, patch| {
+        create_version(
+            &spec,
+            &[("major", major), ("minor", minor), ("patch", patch)],
+        )
+    };
+
+    assert!(version("1", "2", "3") < version("1", "2", "10"));
+    assert!(version("1", "2", "10") < version("1", "10", "0"));
+    assert!(version("2", "0", "0") > version("1", "99", "99"));
+    assert!(version("1", "02", "3") == version("1", "2", "3"));
+}
+
+#[test]
+fn test_version_ordering_follows_values() {
+    let spec = prerelease_spec();
+    let version = |release| {
+        create_version(
+            &spec,
+            &[
+                ("major", "1"),
+                ("minor", "0"),
+                ("patch", "0"),
+                ("release", release),
+            ],
+        )
+    };
+    let released = create_version(&spec, &[("major", "1"), ("minor", "0"), ("patch", "0")]);
+
+    assert!(version("alpha") < version("beta"));
+    assert!(version("beta") < released);
+    assert!(version("final") == released);
+    assert_eq!(version("gamma").partial_cmp(&released), None);
+}
+
+#[test]
+fn test_version_ordering_follows_component_order() {
+    let mut components = IndexMap::new();
+    components.insert("major".to_string(), VersionComponentSpec::default());
+    for (name, order) in [
+        ("dev", ComponentOrder::PreRelease),
+        ("build", ComponentOrder::Ignore),
+    ] {
+        components.insert(
+            name.to_string(),
+            VersionComponentSpec {
+                order: Some(order),
+                ..Default::default()
+            },
+        );
+    }
+    let spec = VersionSpec::from_components(components);
+    let version = |values: &[(&str, &str)]| create_version(&spec, values);
+
+    assert!(version(&[("major", "1"), ("dev", "4")]) < version(&[("major", "1")]));
+    assert!(version(&[("major", "1"), ("dev", "4")]) < version(&[("major", "1"), ("dev", "5")]));
+    assert!(version(&[("major", "1")]) < version(&[("major", "2"), ("dev", "1")]));
+    assert!(
+        version(&[("major", "1"), ("build", "7")]) == version(&[("major", "1"), ("build", "9")])
+    );
+}
+
+#[test]
+fn test_version_ordering_requires_the_same_components() {
+    let semver = create_version(
+        &semver_spec(),
+        &[("major", "1"), ("minor", "0"), ("patch", "0")],
+    );
+    let prerelease = create_version(
+        &prerelease_spec(),
+        &[("major", "1"), ("minor", "0"), ("patch", "0")],
+    );
+    assert_eq!(semver.partial_cmp(&prerelease), None);
+}
+
+#[test]
+fn test_numeric_compare_by_runs_of_digits() {
+    use bumpversion::version::numeric::compare;
+    use std::cmp::Ordering;
+
+    assert_eq!(compare("2024.9", "2024.10"), Ordering::Less);
+    assert_eq!(compare("007", "7"), Ordering::Equal);
+    assert_eq!(compare("r9", "r10"), Ordering::Less);
+    assert_eq!(compare("2024.05", "2024.05.1"), Ordering::Less);
+}
+
+#[test]
+fn test_version_display_uses_serialize_patterns() -> eyre::Result<()> {
+    let patterns: Vec<PythonFormatString> = vec![
+        "{major}.{minor}.{patch}-{release}".parse()?,
+        "{major}.{minor}.{patch}".parse()?,
+    ];
+    let spec = prerelease_spec().with_serialize_version_patterns(patterns);
+
+    let released = create_version(&spec, &[("major", "1"), ("minor", "2"), ("patch", "3")]);
+    assert_eq!(released.to_string(), "1.2.3");
+    let prerelease = create_version(
+        &spec,
+        &[
+            ("major", "1"),
+            ("minor", "2"),
+            ("patch", "3"),
+            ("release", "beta"),
+        ],
+    );
+    assert_eq!(prerelease.to_string(), "1.2.3-beta");
+
+    // without patterns, the components are listed
+    let version = create_version(
+        &prerelease_spec(),
+        &[("major", "1"), ("minor", "2"), ("patch", "3")],
+    );
+    assert!(version.to_string().contains(r#""major": Some("1")"#));
+    Ok(())
+}